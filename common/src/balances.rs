@@ -0,0 +1,306 @@
+//! Summation of proven balances across chains.
+//!
+//! Balances proven on different chains are stored in different encodings and with
+//! different decimal precisions. This module decodes each proven value, scales it
+//! to a common precision and sums the results, failing instead of wrapping when a
+//! value does not fit in 256 bits.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use alloy_primitives::U256;
+use anyhow::{Context, Result};
+use num_bigint::BigUint;
+
+/// The largest precision difference that can be scaled within 256 bits, since
+/// `10^77 < 2^256 < 10^78`.
+pub const MAX_PRECISION_DIFFERENCE: u32 = 77;
+
+/// The encoding of a proven balance value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueDecoder {
+    /// An RLP-encoded unsigned integer of at most 32 bytes, as stored in an
+    /// Ethereum storage trie leaf
+    RlpU256,
+    /// A base-10 ASCII string, optionally wrapped in JSON double quotes as
+    /// CosmWasm stores a `Uint128`
+    DecimalString,
+    /// A protobuf-encoded Cosmos SDK `Coin` whose `amount` field is a base-10 string
+    ProtobufCoin,
+}
+
+/// A raw proven value together with how to decode and scale it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainValue {
+    /// The proven value as stored on chain
+    pub value: Vec<u8>,
+    /// The encoding of `value`
+    pub decoder: ValueDecoder,
+    /// The number of decimals of the decoded amount
+    pub precision: u32,
+}
+
+impl ChainValue {
+    /// Creates a new chain value.
+    ///
+    /// # Arguments
+    /// * `value` - The proven value as stored on chain
+    /// * `decoder` - The encoding of `value`
+    /// * `precision` - The number of decimals of the decoded amount
+    ///
+    /// # Returns
+    /// A new `ChainValue` instance
+    pub fn new(value: Vec<u8>, decoder: ValueDecoder, precision: u32) -> Self {
+        Self {
+            value,
+            decoder,
+            precision,
+        }
+    }
+
+    /// Decodes the value into an unscaled amount.
+    ///
+    /// # Returns
+    /// The decoded amount, in units of `10^-precision`
+    ///
+    /// # Errors
+    /// Returns an error if the value is malformed for its decoder or exceeds 256 bits
+    pub fn decode(&self) -> Result<U256> {
+        let amount = match self.decoder {
+            ValueDecoder::RlpU256 => decode_rlp_uint(&self.value)?,
+            ValueDecoder::DecimalString => decode_decimal(&self.value)?,
+            ValueDecoder::ProtobufCoin => decode_decimal(protobuf_coin_amount(&self.value)?)?,
+        };
+        ensure_u256(amount)
+    }
+}
+
+/// Decodes, normalizes and sums balances proven on different chains.
+///
+/// Every amount is scaled up to the highest precision among `entries`, so no
+/// digits are lost. The result is expressed in that precision.
+///
+/// # Arguments
+/// * `entries` - The proven balances to sum
+///
+/// # Returns
+/// The sum of all balances, in units of `10^-p` where `p` is the highest precision
+///
+/// # Errors
+/// Returns an error if any value cannot be decoded, if the precisions of two
+/// entries differ by more than [`MAX_PRECISION_DIFFERENCE`], or if any scaled
+/// amount or the sum exceeds 256 bits
+pub fn sum_cross_chain_balances(entries: &[ChainValue]) -> Result<U256> {
+    let precision = entries
+        .iter()
+        .map(|entry| entry.precision)
+        .max()
+        .unwrap_or_default();
+    let mut total = U256::ZERO;
+    for entry in entries {
+        let difference = precision - entry.precision;
+        if difference > MAX_PRECISION_DIFFERENCE {
+            anyhow::bail!(
+                "Precision {} cannot be scaled to precision {} within 256 bits",
+                entry.precision,
+                precision
+            );
+        }
+        let scale = U256::from(10u8).pow(U256::from(difference));
+        let scaled = entry
+            .decode()?
+            .checked_mul(scale)
+            .context("Scaled amount exceeds 256 bits")?;
+        total = total.checked_add(scaled).context("Sum exceeds 256 bits")?;
+    }
+    Ok(total)
+}
+
+/// Converts an amount to a `U256`, rejecting amounts that do not fit.
+fn ensure_u256(amount: BigUint) -> Result<U256> {
+    U256::try_from_be_slice(&amount.to_bytes_be()).context("Amount exceeds 256 bits")
+}
+
+/// Decodes an RLP-encoded unsigned integer of at most 32 bytes.
+///
+/// An empty value is zero, as proven for a storage slot that was never written.
+/// Non-canonical encodings, with leading zero bytes or a single byte below
+/// `0x80` behind a length prefix, are rejected.
+fn decode_rlp_uint(rlp: &[u8]) -> Result<BigUint> {
+    let Some((&prefix, rest)) = rlp.split_first() else {
+        return Ok(BigUint::default());
+    };
+    let payload = match prefix {
+        0x00..=0x7f if rest.is_empty() => &rlp[..1],
+        0x80..=0xa0 if rest.len() == usize::from(prefix - 0x80) => rest,
+        _ => anyhow::bail!("RLP value is not an integer of at most 32 bytes"),
+    };
+    if payload.first() == Some(&0) {
+        anyhow::bail!("RLP integer has leading zero bytes");
+    }
+    if prefix == 0x81 && payload[0] < 0x80 {
+        anyhow::bail!("RLP integer below 0x80 is not encoded as a single byte");
+    }
+    Ok(BigUint::from_bytes_be(payload))
+}
+
+/// Decodes a base-10 ASCII amount, optionally wrapped in JSON double quotes.
+fn decode_decimal(bytes: &[u8]) -> Result<BigUint> {
+    let digits = bytes
+        .strip_prefix(b"\"")
+        .and_then(|inner| inner.strip_suffix(b"\""))
+        .unwrap_or(bytes);
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        anyhow::bail!("Amount is not a decimal number");
+    }
+    BigUint::parse_bytes(digits, 10).context("Amount is not a decimal number")
+}
+
+/// Extracts the `amount` field (field 2) of a protobuf-encoded Cosmos SDK `Coin`.
+fn protobuf_coin_amount(mut bytes: &[u8]) -> Result<&[u8]> {
+    let mut amount = None;
+    while !bytes.is_empty() {
+        let tag = read_varint(&mut bytes)?;
+        // both `denom` and `amount` are length-delimited strings
+        if tag & 0x07 != 2 {
+            anyhow::bail!("Unexpected protobuf wire type in Coin");
+        }
+        let len = usize::try_from(read_varint(&mut bytes)?)?;
+        if len > bytes.len() {
+            anyhow::bail!("Truncated protobuf field in Coin");
+        }
+        let (field, rest) = bytes.split_at(len);
+        if tag >> 3 == 2 {
+            amount = Some(field);
+        }
+        bytes = rest;
+    }
+    amount.context("Coin has no amount")
+}
+
+/// Reads a protobuf varint, advancing `bytes` past it.
+fn read_varint(bytes: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().context("Truncated protobuf varint")?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    anyhow::bail!("Protobuf varint is too long")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn coin(denom: &str, amount: &str) -> Vec<u8> {
+        let mut out = vec![0x0a, denom.len() as u8];
+        out.extend_from_slice(denom.as_bytes());
+        out.extend([0x12, amount.len() as u8]);
+        out.extend_from_slice(amount.as_bytes());
+        out
+    }
+
+    #[test]
+    fn test_sum_normalizes_precision() {
+        let entries = [
+            // 1.5 with 18 decimals, RLP-encoded as stored in an Ethereum slot
+            ChainValue::new(
+                [
+                    vec![0x88],
+                    1_500_000_000_000_000_000u64.to_be_bytes().to_vec(),
+                ]
+                .concat(),
+                ValueDecoder::RlpU256,
+                18,
+            ),
+            // 2.25 with 6 decimals, as a CosmWasm Uint128
+            ChainValue::new(b"\"2250000\"".to_vec(), ValueDecoder::DecimalString, 6),
+            // 0.000001 with 6 decimals, as a bank Coin
+            ChainValue::new(coin("untrn", "1"), ValueDecoder::ProtobufCoin, 6),
+        ];
+        assert_eq!(
+            sum_cross_chain_balances(&entries).unwrap(),
+            U256::from(3_750_001_000_000_000_000u128)
+        );
+    }
+
+    #[test]
+    fn test_single_byte_and_empty_rlp_values() {
+        let small = ChainValue::new(vec![0x2a], ValueDecoder::RlpU256, 0);
+        assert_eq!(small.decode().unwrap(), U256::from(42u32));
+        let zero = ChainValue::new(vec![0x80], ValueDecoder::RlpU256, 0);
+        assert_eq!(zero.decode().unwrap(), U256::ZERO);
+        assert_eq!(sum_cross_chain_balances(&[]).unwrap(), U256::ZERO);
+        // an unwritten slot, proven by an exclusion proof, holds zero
+        let unwritten = ChainValue::new(vec![], ValueDecoder::RlpU256, 0);
+        assert_eq!(unwritten.decode().unwrap(), U256::ZERO);
+        assert_eq!(
+            sum_cross_chain_balances(&[unwritten, small]).unwrap(),
+            U256::from(42u32)
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_rlp_values() {
+        for value in [
+            vec![0x00],
+            vec![0x81, 0x2a],
+            vec![0x81, 0x00],
+            vec![0x82, 0x00, 0x2a],
+        ] {
+            assert!(ChainValue::new(value, ValueDecoder::RlpU256, 0)
+                .decode()
+                .is_err());
+        }
+        let canonical = ChainValue::new(vec![0x81, 0x80], ValueDecoder::RlpU256, 0);
+        assert_eq!(canonical.decode().unwrap(), U256::from(0x80u32));
+    }
+
+    #[test]
+    fn test_rejects_malformed_values() {
+        let oversized = ChainValue::new(
+            [vec![0xa1], vec![0xff; 33]].concat(),
+            ValueDecoder::RlpU256,
+            0,
+        );
+        assert!(oversized.decode().is_err());
+        let truncated = ChainValue::new(vec![0x82, 0x01], ValueDecoder::RlpU256, 0);
+        assert!(truncated.decode().is_err());
+        let not_digits = ChainValue::new(b" 12".to_vec(), ValueDecoder::DecimalString, 0);
+        assert!(not_digits.decode().is_err());
+        let no_amount = ChainValue::new(vec![0x0a, 0x01, b'u'], ValueDecoder::ProtobufCoin, 0);
+        assert!(no_amount.decode().is_err());
+    }
+
+    #[test]
+    fn test_rejects_overflow() {
+        let max = ChainValue::new(
+            [vec![0xa0], vec![0xff; 32]].concat(),
+            ValueDecoder::RlpU256,
+            0,
+        );
+        assert!(sum_cross_chain_balances(core::slice::from_ref(&max)).is_ok());
+        assert!(sum_cross_chain_balances(&[max.clone(), max.clone()]).is_err());
+        // scaling to a higher precision can overflow too
+        let precise = ChainValue::new(vec![0x01], ValueDecoder::RlpU256, 1);
+        assert!(sum_cross_chain_balances(&[max, precise]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unscalable_precision() {
+        let one = ChainValue::new(vec![0x01], ValueDecoder::RlpU256, 0);
+        let zero = ChainValue::new(vec![0x80], ValueDecoder::RlpU256, 0);
+        let scaled = |precision| ChainValue::new(vec![0x01], ValueDecoder::RlpU256, precision);
+        assert_eq!(
+            sum_cross_chain_balances(&[one.clone(), scaled(MAX_PRECISION_DIFFERENCE)]).unwrap(),
+            U256::from(10u8).pow(U256::from(MAX_PRECISION_DIFFERENCE)) + U256::from(1u8)
+        );
+        assert!(sum_cross_chain_balances(&[one, scaled(MAX_PRECISION_DIFFERENCE + 1)]).is_err());
+        // even a zero amount cannot be scaled by more than 10^77
+        assert!(sum_cross_chain_balances(&[zero, scaled(u32::MAX)]).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! Memoization of successful proof verifications.
+//!
+//! Services that verify the same proofs repeatedly (e.g. one contract's supply
+//! slot across many requests) can skip the trie walk for entries they have
+//! already verified against the same root. This is an optimization outside the
+//! circuit only: a zkVM guest must always verify its proofs and must never use
+//! this module.
+extern crate alloc;
+extern crate std;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::Result;
+use lru::LruCache;
+
+use crate::merkle::types::{MerkleVerifiable, ProvenEntry};
+
+/// Verified values, keyed by `(root, key)`.
+type VerifiedEntries = LruCache<(Vec<u8>, Vec<u8>), Vec<u8>>;
+
+/// A verifier that remembers the values it has verified, keyed by `(root, key)`.
+///
+/// The root is part of the cache key, so a proof verified at one block is never
+/// served for another. A cache hit only skips verification if the proof claims
+/// the remembered value; any other proof is verified in full.
+pub struct CachingVerifier {
+    cache: Mutex<VerifiedEntries>,
+}
+
+impl CachingVerifier {
+    /// Creates a verifier that remembers up to `capacity` entries.
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of `(root, key)` entries to keep
+    ///
+    /// # Returns
+    /// A new `CachingVerifier` instance
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Verifies a proof against a root, using and updating the cache.
+    ///
+    /// # Arguments
+    /// * `proof` - The proof to verify
+    /// * `root` - The root to verify against
+    ///
+    /// # Returns
+    /// A boolean indicating whether the proof is valid for the given root
+    ///
+    /// # Errors
+    /// Returns the error of the underlying verification; failures are not cached
+    pub fn verify<P: MerkleVerifiable + ProvenEntry>(
+        &self,
+        proof: &P,
+        root: &[u8],
+    ) -> Result<bool> {
+        let cache_key = (root.to_vec(), proof.proven_key());
+        let value = proof.proven_value();
+        if self.lock().get(&cache_key) == Some(&value) {
+            return Ok(true);
+        }
+        let is_valid = proof.verify(root)?;
+        if is_valid {
+            self.lock().put(cache_key, value);
+        }
+        Ok(is_valid)
+    }
+
+    /// Verifies proofs ahead of time so that later verifications against the
+    /// same root are cache hits.
+    ///
+    /// Proofs taken at another height are not invalidated by warming; call
+    /// [`Self::clear`] first when the verified height changes.
+    ///
+    /// # Arguments
+    /// * `proofs` - The proofs to verify
+    /// * `root` - The root to verify against
+    ///
+    /// # Errors
+    /// Returns an error naming the index of the first proof that does not verify
+    pub fn warm<P: MerkleVerifiable + ProvenEntry>(&self, proofs: &[P], root: &[u8]) -> Result<()> {
+        for (index, proof) in proofs.iter().enumerate() {
+            if !self.verify(proof, root)? {
+                anyhow::bail!("Proof at index {} does not verify against the root", index);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all cached entries.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VerifiedEntries> {
+        // a panic while holding the lock cannot leave the cache inconsistent
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::stub::StubProof;
+
+    #[test]
+    fn test_caches_successful_verifications() {
+        let verifier = CachingVerifier::new(NonZeroUsize::new(2).unwrap());
+        let proof = StubProof::entry(b"supply", b"root-1", b"root-1");
+        assert!(verifier.verify(&proof, b"root-1").unwrap());
+        assert!(verifier.verify(&proof, b"root-1").unwrap());
+        assert_eq!(proof.verifications.get(), 1);
+
+        // a failed verification is not cached
+        assert!(!verifier.verify(&proof, b"root-2").unwrap());
+        assert!(!verifier.verify(&proof, b"root-2").unwrap());
+        assert_eq!(proof.verifications.get(), 3);
+        assert_eq!(verifier.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_requires_matching_value() {
+        let verifier = CachingVerifier::new(NonZeroUsize::new(2).unwrap());
+        assert!(verifier
+            .verify(
+                &StubProof::entry(b"supply", b"root-1", b"root-1"),
+                b"root-1"
+            )
+            .unwrap());
+        // same root and key, but another value: verified in full and rejected
+        let forged = StubProof::entry(b"supply", b"forged", b"root-2");
+        assert!(!verifier.verify(&forged, b"root-1").unwrap());
+        assert_eq!(forged.verifications.get(), 1);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let verifier = CachingVerifier::new(NonZeroUsize::new(1).unwrap());
+        let first = StubProof::entry(b"a", b"root", b"root");
+        let second = StubProof::entry(b"b", b"root", b"root");
+        verifier.verify(&first, b"root").unwrap();
+        verifier.verify(&second, b"root").unwrap();
+        verifier.verify(&first, b"root").unwrap();
+        assert_eq!(first.verifications.get(), 2);
+        assert_eq!(verifier.len(), 1);
+        assert!(!verifier.is_empty());
+    }
+
+    #[test]
+    fn test_warm_then_clear() {
+        let verifier = CachingVerifier::new(NonZeroUsize::new(4).unwrap());
+        let proofs = [
+            StubProof::entry(b"a", b"root", b"root"),
+            StubProof::entry(b"b", b"root", b"root"),
+        ];
+        verifier.warm(&proofs, b"root").unwrap();
+        assert!(verifier.verify(&proofs[1], b"root").unwrap());
+        assert_eq!(proofs[1].verifications.get(), 1);
+
+        verifier.clear();
+        assert!(verifier.is_empty());
+        assert!(verifier.warm(&proofs, b"other").is_err());
+        assert!(verifier.is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Verification against a file of trusted roots.
+//!
+//! Offline verifiers can keep the roots they trust in a checkpoint file instead
+//! of accepting roots from wherever the proofs come from. The file is a JSON
+//! array of checkpoints, each naming a chain, a height and the hex-encoded root
+//! at that height, e.g. an Ethereum state root or a Cosmos app hash:
+//!
+//! ```json
+//! [
+//!   { "chain": "ethereum-1", "height": 22000000, "root": "0x5a8d..." },
+//!   { "chain": "neutron-1", "height": 24000000, "root": "C6E3CBE1..." }
+//! ]
+//! ```
+//!
+//! Authenticating the file itself, e.g. by checking a detached signature, is left
+//! to the caller.
+extern crate alloc;
+extern crate std;
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::merkle::types::{DomainProof, MerkleProofOutput};
+
+/// A checkpoint as it appears in a checkpoint file.
+#[derive(Deserialize)]
+struct CheckpointEntry {
+    chain: String,
+    height: u64,
+    root: String,
+}
+
+/// Trusted roots, keyed by chain and height.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckpointStore {
+    roots: BTreeMap<(String, u64), Vec<u8>>,
+}
+
+impl CheckpointStore {
+    /// Creates an empty store.
+    ///
+    /// # Returns
+    /// A new `CheckpointStore` instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a store from a checkpoint file.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the checkpoint file
+    ///
+    /// # Returns
+    /// A store holding every checkpoint of the file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, see [`Self::from_json`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read(path)
+            .with_context(|| format!("Failed to read checkpoint file {}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// Parses a store from the contents of a checkpoint file.
+    ///
+    /// # Arguments
+    /// * `json` - The JSON array of checkpoints
+    ///
+    /// # Returns
+    /// A store holding every checkpoint
+    ///
+    /// # Errors
+    /// Returns an error if the JSON is not an array of checkpoints, a root is not
+    /// hex-encoded, or two checkpoints disagree on the root at a height
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let entries: Vec<CheckpointEntry> =
+            serde_json::from_slice(json).context("Failed to parse checkpoints")?;
+        let mut store = Self::new();
+        for entry in entries {
+            let root = hex::decode(entry.root.trim_start_matches("0x")).map_err(|_| {
+                anyhow::anyhow!(
+                    "Checkpoint root for {} at height {} is not hex",
+                    entry.chain,
+                    entry.height
+                )
+            })?;
+            store.insert(&entry.chain, entry.height, root)?;
+        }
+        Ok(store)
+    }
+
+    /// Adds a trusted root.
+    ///
+    /// # Arguments
+    /// * `chain` - The chain the root belongs to
+    /// * `height` - The height of the root
+    /// * `root` - The root
+    ///
+    /// # Errors
+    /// Returns an error if another root is already trusted for the chain and height
+    pub fn insert(&mut self, chain: &str, height: u64, root: Vec<u8>) -> Result<()> {
+        match self.roots.get(&(chain.to_string(), height)) {
+            Some(existing) if *existing != root => {
+                anyhow::bail!("Conflicting checkpoints for {} at height {}", chain, height)
+            }
+            _ => {
+                self.roots.insert((chain.to_string(), height), root);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the trusted root of a chain at a height.
+    ///
+    /// # Arguments
+    /// * `chain` - The chain to look up
+    /// * `height` - The height to look up
+    ///
+    /// # Returns
+    /// The trusted root, or `None` if there is no checkpoint at that height
+    pub fn root(&self, chain: &str, height: u64) -> Option<&[u8]> {
+        self.roots
+            .get(&(chain.to_string(), height))
+            .map(Vec::as_slice)
+    }
+
+    /// Verifies a proof against the trusted root of a chain at a height.
+    ///
+    /// # Arguments
+    /// * `proof` - The proof to verify
+    /// * `chain` - The chain the proof was taken from
+    /// * `height` - The height the proof was taken at
+    ///
+    /// # Returns
+    /// The proven entry, tagged with the trusted root
+    ///
+    /// # Errors
+    /// Returns an error if there is no checkpoint for the chain at that height
+    /// or the proof does not verify against its root
+    pub fn verify_against_checkpoint<P: DomainProof>(
+        &self,
+        proof: &P,
+        chain: &str,
+        height: u64,
+    ) -> Result<MerkleProofOutput> {
+        let root = self
+            .root(chain, height)
+            .with_context(|| format!("No checkpoint for {} at height {}", chain, height))?;
+        proof.verify_output(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::stub::StubProof;
+
+    const CHECKPOINTS: &str = r#"[
+        { "chain": "ethereum-1", "height": 100, "root": "0x0101010101010101010101010101010101010101010101010101010101010101" },
+        { "chain": "ethereum-1", "height": 101, "root": "0202020202020202020202020202020202020202020202020202020202020202" },
+        { "chain": "neutron-1", "height": 100, "root": "C6E3CBE15B7F52A5CEBD87DA54DB04E6BAAD3AA0778F5508886182EC1B4AF3CF" }
+    ]"#;
+
+    #[test]
+    fn test_verify_against_checkpoint() {
+        let proof = StubProof::new(&[0x01; 32]);
+        let store = CheckpointStore::from_json(CHECKPOINTS.as_bytes()).unwrap();
+        assert_eq!(store.root("neutron-1", 100).unwrap()[0], 0xC6);
+
+        let output = store
+            .verify_against_checkpoint(&proof, "ethereum-1", 100)
+            .unwrap();
+        assert_eq!(output.root, [0x01; 32]);
+        assert_eq!(output.value, b"v");
+
+        assert!(store
+            .verify_against_checkpoint(&proof, "ethereum-1", 101)
+            .is_err());
+        let missing = store
+            .verify_against_checkpoint(&proof, "ethereum-1", 102)
+            .unwrap_err();
+        assert_eq!(
+            missing.to_string(),
+            "No checkpoint for ethereum-1 at height 102"
+        );
+    }
+
+    #[test]
+    fn test_rejects_conflicting_checkpoints() {
+        let conflicting = r#"[
+            { "chain": "ethereum-1", "height": 100, "root": "01" },
+            { "chain": "ethereum-1", "height": 100, "root": "02" }
+        ]"#;
+        assert!(CheckpointStore::from_json(conflicting.as_bytes()).is_err());
+        let duplicate = r#"[
+            { "chain": "ethereum-1", "height": 100, "root": "01" },
+            { "chain": "ethereum-1", "height": 100, "root": "0x01" }
+        ]"#;
+        assert!(CheckpointStore::from_json(duplicate.as_bytes()).is_ok());
+        assert!(
+            CheckpointStore::from_json(br#"[{ "chain": "a", "height": 1, "root": "zz" }]"#)
+                .is_err()
+        );
+    }
+}
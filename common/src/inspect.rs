@@ -0,0 +1,61 @@
+//! Helpers for rendering proof contents for humans.
+//!
+//! These are meant for debugging tools and test failures, where a raw `Debug`
+//! dump of nested byte vectors is unreadable. The same holds for the `inspect`
+//! methods of the proof types in each domain, which render through these
+//! helpers: they are never used during verification, and nothing in a
+//! verification path should call into this module.
+extern crate alloc;
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Encodes bytes as lowercase hex with a `0x` prefix.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+///
+/// # Returns
+/// The hex string, `0x` for empty input
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(2 + bytes.len() * 2);
+    out.push_str("0x");
+    for byte in bytes {
+        let _ = write!(out, "{:02x}", byte);
+    }
+    out
+}
+
+/// Describes a byte string as hex, followed by its UTF-8 rendering when every
+/// character is printable.
+///
+/// # Arguments
+/// * `bytes` - The bytes to describe
+///
+/// # Returns
+/// A single-line description such as `0x6869 ("hi")`
+pub fn describe_bytes(bytes: &[u8]) -> String {
+    let mut out = to_hex(bytes);
+    if let Some(text) = printable_utf8(bytes) {
+        let _ = write!(out, " ({:?})", text);
+    }
+    out
+}
+
+/// Returns `bytes` as a string if it is non-empty, valid UTF-8 and free of control characters.
+fn printable_utf8(bytes: &[u8]) -> Option<&str> {
+    let text = core::str::from_utf8(bytes).ok()?;
+    (!text.is_empty() && !text.chars().any(char::is_control)).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_bytes() {
+        assert_eq!(to_hex(&[]), "0x");
+        assert_eq!(describe_bytes(b"\"100\""), "0x2231303022 (\"\\\"100\\\"\")");
+        assert_eq!(describe_bytes(&[0x82, 0x01, 0x00]), "0x820100");
+        assert_eq!(describe_bytes(&[0x0a, 0x05]), "0x0a05");
+    }
+}
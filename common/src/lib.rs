@@ -1,2 +1,10 @@
 #![no_std]
+pub mod balances;
+#[cfg(feature = "no-zkvm")]
+pub mod cache;
+#[cfg(feature = "no-zkvm")]
+pub mod checkpoint;
+pub mod inspect;
 pub mod merkle;
+#[cfg(feature = "no-zkvm")]
+pub mod observe;
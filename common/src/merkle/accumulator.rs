@@ -0,0 +1,321 @@
+//! A Merkle commitment to a batch of verified outputs.
+//!
+//! Committing a flat list of [`MerkleProofOutput`]s makes a consumer read the
+//! whole batch to use any one of them. [`OutputAccumulator`] instead commits to
+//! a single 32-byte root over all outputs, and hands out an
+//! [`OutputInclusionProof`] per output, so that an on-chain consumer can check a
+//! single proven entry against the root. The outputs remain available for a flat
+//! commitment where that is preferred.
+//!
+//! The tree uses keccak256, which is cheap to recompute on the EVM:
+//! * a leaf is `keccak256(0x00 || encode(output))`, where `encode` concatenates
+//!   the domain byte (0 for Ethereum, 1 for ICS23) with the root, key and value,
+//!   each prefixed with its length as a big-endian `u32`, followed by `0x00` if
+//!   the output has no height or `0x01` and the height as a big-endian `u64`
+//! * an inner node is `keccak256(0x01 || left || right)`
+//! * the last node of a level without a sibling is promoted to the next level
+//!   unchanged
+//! * the root is `keccak256(0x02 || leaf_count || tree_root)`, with the number of
+//!   outputs as a big-endian `u64`
+//!
+//! The distinct leaf and node prefixes keep an inner node from being passed off
+//! as a leaf. Binding the number of outputs into the root fixes the shape of the
+//! tree, so an output can only be proven at the index it was added at: without
+//! it, the last of three outputs would also prove as the second of two.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use super::types::{Domain, MerkleProofOutput};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+const ROOT_PREFIX: u8 = 0x02;
+
+/// Collects verified outputs and commits to them with a Merkle root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputAccumulator {
+    outputs: Vec<MerkleProofOutput>,
+}
+
+/// A proof that an output is part of an [`OutputAccumulator`]'s root.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OutputInclusionProof {
+    /// The position of the output in the accumulator
+    pub index: u64,
+    /// The number of outputs in the accumulator
+    pub leaf_count: u64,
+    /// The sibling hashes from the leaf towards the root, skipping levels where
+    /// the node was promoted without a sibling
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl OutputAccumulator {
+    /// Creates an empty accumulator.
+    ///
+    /// # Returns
+    /// A new `OutputAccumulator` instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a verified output.
+    ///
+    /// # Arguments
+    /// * `output` - The output of a verified proof
+    pub fn push(&mut self, output: MerkleProofOutput) {
+        self.outputs.push(output);
+    }
+
+    /// Returns the accumulated outputs, in the order they were added.
+    pub fn outputs(&self) -> &[MerkleProofOutput] {
+        &self.outputs
+    }
+
+    /// Consumes the accumulator, returning the outputs for a flat commitment.
+    pub fn into_outputs(self) -> Vec<MerkleProofOutput> {
+        self.outputs
+    }
+
+    /// Computes the Merkle root over all outputs.
+    ///
+    /// # Returns
+    /// The root, or 32 zero bytes if no output was added
+    pub fn root(&self) -> [u8; 32] {
+        let mut level: Vec<[u8; 32]> = self.outputs.iter().map(leaf_hash).collect();
+        if level.is_empty() {
+            return [0u8; 32];
+        }
+        while level.len() > 1 {
+            level = next_level(&level);
+        }
+        root_hash(self.outputs.len() as u64, &level[0])
+    }
+
+    /// Builds the inclusion proof of an output.
+    ///
+    /// # Arguments
+    /// * `index` - The position of the output
+    ///
+    /// # Returns
+    /// The proof that the output is part of [`Self::root`]
+    ///
+    /// # Errors
+    /// Returns an error if there is no output at `index`
+    pub fn inclusion_proof(&self, index: usize) -> Result<OutputInclusionProof> {
+        if index >= self.outputs.len() {
+            anyhow::bail!(
+                "No output at index {}, the accumulator holds {}",
+                index,
+                self.outputs.len()
+            );
+        }
+        let mut level: Vec<[u8; 32]> = self.outputs.iter().map(leaf_hash).collect();
+        let mut position = index;
+        let mut siblings = Vec::new();
+        while level.len() > 1 {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            level = next_level(&level);
+            position /= 2;
+        }
+        Ok(OutputInclusionProof {
+            index: index as u64,
+            leaf_count: self.outputs.len() as u64,
+            siblings,
+        })
+    }
+}
+
+impl OutputInclusionProof {
+    /// Verifies that an output is part of an accumulator root.
+    ///
+    /// # Arguments
+    /// * `output` - The output to check
+    /// * `root` - The committed accumulator root
+    ///
+    /// # Returns
+    /// `true` if the proof leads from `output` at `index` to `root` of an
+    /// accumulator holding `leaf_count` outputs
+    pub fn verify(&self, output: &MerkleProofOutput, root: &[u8; 32]) -> bool {
+        if self.index >= self.leaf_count {
+            return false;
+        }
+        let mut hash = leaf_hash(output);
+        let mut position = self.index;
+        let mut width = self.leaf_count;
+        let mut siblings = self.siblings.iter();
+        while width > 1 {
+            // the last node of an odd level is promoted without a sibling
+            if !(position % 2 == 0 && position == width - 1) {
+                let Some(sibling) = siblings.next() else {
+                    return false;
+                };
+                hash = if position % 2 == 0 {
+                    node_hash(&hash, sibling)
+                } else {
+                    node_hash(sibling, &hash)
+                };
+            }
+            position /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none() && root_hash(self.leaf_count, &hash) == *root
+    }
+}
+
+fn leaf_hash(output: &MerkleProofOutput) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update([match output.domain {
+        Domain::Ethereum => 0u8,
+        Domain::Ics23 => 1u8,
+    }]);
+    for field in [&output.root, &output.key, &output.value] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field);
+    }
+    match output.height {
+        Some(height) => {
+            hasher.update([0x01]);
+            hasher.update(height.to_be_bytes());
+        }
+        None => hasher.update([0x00]),
+    }
+    hasher.finalize().into()
+}
+
+/// Hashes each pair of nodes of a level, promoting a last node without a sibling.
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks of 2 hold one or two nodes"),
+        })
+        .collect()
+}
+
+/// Binds the number of outputs to the root of their tree.
+fn root_hash(leaf_count: u64, tree_root: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([ROOT_PREFIX]);
+    hasher.update(leaf_count.to_be_bytes());
+    hasher.update(tree_root);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn output(i: u8) -> MerkleProofOutput {
+        MerkleProofOutput {
+            root: vec![0xAA; 32],
+            key: vec![i],
+            value: vec![i; 3],
+            domain: if i % 2 == 0 {
+                Domain::Ethereum
+            } else {
+                Domain::Ics23
+            },
+            height: None,
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proofs_verify() {
+        for count in 1..=9u8 {
+            let mut accumulator = OutputAccumulator::new();
+            (0..count).for_each(|i| accumulator.push(output(i)));
+            let root = accumulator.root();
+            for i in 0..count {
+                let proof = accumulator.inclusion_proof(i as usize).unwrap();
+                assert!(proof.verify(&output(i), &root), "{} of {}", i, count);
+                assert!(!proof.verify(&output(i + 1), &root));
+            }
+            assert!(accumulator.inclusion_proof(count as usize).is_err());
+        }
+    }
+
+    #[test]
+    fn test_root_commits_to_every_output() {
+        let mut accumulator = OutputAccumulator::new();
+        assert_eq!(accumulator.root(), [0u8; 32]);
+        (0..3).for_each(|i| accumulator.push(output(i)));
+        let root = accumulator.root();
+
+        let mut reordered = OutputAccumulator::new();
+        [1, 0, 2]
+            .into_iter()
+            .for_each(|i| reordered.push(output(i)));
+        assert_ne!(reordered.root(), root);
+
+        let mut other_domain = output(2);
+        other_domain.domain = Domain::Ics23;
+        let mut changed = OutputAccumulator::new();
+        changed.push(output(0));
+        changed.push(output(1));
+        changed.push(other_domain);
+        assert_ne!(changed.root(), root);
+
+        let mut with_height = output(2);
+        with_height.height = Some(7);
+        let mut changed = OutputAccumulator::new();
+        changed.push(output(0));
+        changed.push(output(1));
+        changed.push(with_height);
+        assert_ne!(changed.root(), root);
+
+        assert_eq!(accumulator.into_outputs().len(), 3);
+    }
+
+    #[test]
+    fn test_rejects_tampered_proofs() {
+        let mut accumulator = OutputAccumulator::new();
+        (0..5).for_each(|i| accumulator.push(output(i)));
+        let root = accumulator.root();
+        let proof = accumulator.inclusion_proof(2).unwrap();
+
+        let mut wrong_index = proof.clone();
+        wrong_index.index = 3;
+        assert!(!wrong_index.verify(&output(2), &root));
+        let mut extra_sibling = proof.clone();
+        extra_sibling.siblings.push([0u8; 32]);
+        assert!(!extra_sibling.verify(&output(2), &root));
+        let mut out_of_range = proof;
+        out_of_range.index = 5;
+        assert!(!out_of_range.verify(&output(2), &root));
+    }
+
+    #[test]
+    fn test_proof_binds_index_and_leaf_count() {
+        let mut accumulator = OutputAccumulator::new();
+        (0..3).for_each(|i| accumulator.push(output(i)));
+        let root = accumulator.root();
+        let proof = accumulator.inclusion_proof(2).unwrap();
+        assert!(proof.verify(&output(2), &root));
+
+        // the last of three outputs has the same path as the second of two
+        let fewer_leaves = OutputInclusionProof {
+            index: 1,
+            leaf_count: 2,
+            siblings: proof.siblings,
+        };
+        assert!(!fewer_leaves.verify(&output(2), &root));
+    }
+}
@@ -1 +1,5 @@
+pub mod accumulator;
+#[cfg(test)]
+pub(crate) mod stub;
 pub mod types;
+pub mod versioned;
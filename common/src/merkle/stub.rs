@@ -0,0 +1,77 @@
+//! A stub proof shared by the unit tests of this crate.
+// only the merkle tests run without `no-zkvm`; the cache, checkpoint and
+// observe tests use the rest of the stub
+#![cfg_attr(not(feature = "no-zkvm"), allow(dead_code))]
+extern crate alloc;
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+use anyhow::{bail, Result};
+
+use super::types::{Domain, DomainProof, HasRoot, MerkleVerifiable, ProvenEntry};
+
+/// An Ethereum-domain proof of a fixed entry that verifies against one root only.
+pub(crate) struct StubProof {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    root: Vec<u8>,
+    malformed: bool,
+    /// How many times `verify` was called
+    pub(crate) verifications: Cell<usize>,
+}
+
+impl StubProof {
+    /// A proof of `b"k" = b"v"` that commits to and verifies against `root`.
+    pub(crate) fn new(root: &[u8]) -> Self {
+        Self::entry(b"k", b"v", root)
+    }
+
+    /// A proof of `key = value` that commits to and verifies against `root`.
+    pub(crate) fn entry(key: &[u8], value: &[u8], root: &[u8]) -> Self {
+        Self {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            root: root.to_vec(),
+            malformed: false,
+            verifications: Cell::new(0),
+        }
+    }
+
+    /// A proof whose verification fails with an error against any root.
+    pub(crate) fn malformed() -> Self {
+        Self {
+            malformed: true,
+            ..Self::new(&[])
+        }
+    }
+}
+
+impl MerkleVerifiable for StubProof {
+    fn verify(&self, root: &[u8]) -> Result<bool> {
+        self.verifications.set(self.verifications.get() + 1);
+        if self.malformed {
+            bail!("malformed proof");
+        }
+        Ok(root == self.root.as_slice())
+    }
+}
+
+impl ProvenEntry for StubProof {
+    fn proven_key(&self) -> Vec<u8> {
+        self.key.clone()
+    }
+
+    fn proven_value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+}
+
+impl DomainProof for StubProof {
+    const DOMAIN: Domain = Domain::Ethereum;
+}
+
+impl HasRoot for StubProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        Ok(self.root.clone())
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 extern crate alloc;
 use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
 
 /// A trait for types that can generate Merkle proofs from RPC calls.
 ///
@@ -47,3 +48,287 @@ pub trait MerkleVerifiable {
     /// 3. The root hash matches the expected root
     fn verify(&self, root: &[u8]) -> Result<bool>;
 }
+
+/// A trait for proofs of a single key-value entry.
+///
+/// Exposes the entry a proof claims, so that the outcome of verifying it against
+/// a root can be cached by `(root, key)`.
+pub trait ProvenEntry {
+    /// Returns the key the proof is for.
+    fn proven_key(&self) -> Vec<u8>;
+
+    /// Returns the value the proof claims for its key.
+    fn proven_value(&self) -> Vec<u8>;
+
+    /// Returns the block height the proof reports it was taken at, if any.
+    ///
+    /// The height is metadata supplied alongside the proof and is not
+    /// authenticated by it.
+    fn proven_height(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// The chain family whose trie a proof was verified against.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Domain {
+    /// An Ethereum Merkle-Patricia trie
+    Ethereum,
+    /// A Cosmos SDK store, proven with ICS23
+    Ics23,
+}
+
+/// The entry proven by a verified proof, together with the root and domain it
+/// was verified against.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MerkleProofOutput {
+    /// The root the proof was verified against
+    pub root: Vec<u8>,
+    /// The proven key
+    pub key: Vec<u8>,
+    /// The proven value
+    pub value: Vec<u8>,
+    /// The domain of the proof
+    pub domain: Domain,
+    /// The height the proof reports it was taken at. This is unauthenticated
+    /// metadata: neither the proof nor `root` commits to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+}
+
+/// A trait for single-entry proofs of a known domain.
+///
+/// Verifying through [`DomainProof::verify_output`] yields the proven entry
+/// directly, so callers that commit to verified entries neither assemble the
+/// output from the proof by hand nor verify the proof a second time.
+pub trait DomainProof: MerkleVerifiable + ProvenEntry {
+    /// The domain of the proof.
+    const DOMAIN: Domain;
+
+    /// Verifies the proof and returns the entry it proves.
+    ///
+    /// # Arguments
+    /// * `root` - The expected Merkle root to verify against
+    ///
+    /// # Returns
+    /// The proven entry, tagged with `root` and the proof's domain
+    ///
+    /// # Errors
+    /// Returns an error if the proof does not verify against `root`
+    fn verify_output(&self, root: &[u8]) -> Result<MerkleProofOutput> {
+        if !self.verify(root)? {
+            anyhow::bail!("Proof does not verify against the given root");
+        }
+        Ok(MerkleProofOutput {
+            root: root.to_vec(),
+            key: self.proven_key(),
+            value: self.proven_value(),
+            domain: Self::DOMAIN,
+            height: self.proven_height(),
+        })
+    }
+}
+
+/// A trait for proofs that commit to the root they were built against.
+///
+/// The root is derived from the proof data itself (e.g. the hash of the first
+/// proof node), so it can be compared across proofs before any of them is
+/// verified against a trusted root.
+pub trait HasRoot {
+    /// Returns the root that the proof hashes up to.
+    fn root(&self) -> Result<Vec<u8>>;
+}
+
+/// Asserts that all proofs commit to the same root.
+///
+/// Use this to ensure that several proofs of one domain (e.g. a balance and a
+/// mint total) were taken from the same block and cannot be mixed across heights.
+///
+/// # Arguments
+/// * `proofs` - The proofs that must share a root
+///
+/// # Returns
+/// `Ok(())` if all proofs share a root (or fewer than two proofs are given)
+///
+/// # Errors
+/// Returns an error naming the first proof whose root differs, or if a root
+/// cannot be derived from a proof
+pub fn assert_same_root(proofs: &[&dyn HasRoot]) -> Result<()> {
+    let Some((first, rest)) = proofs.split_first() else {
+        return Ok(());
+    };
+    let expected = first.root()?;
+    for (index, proof) in rest.iter().enumerate() {
+        if proof.root()? != expected {
+            anyhow::bail!(
+                "Proof at index {} does not share the root of proof 0",
+                index + 1
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Verifies a proof against a trusted root of its domain and returns the entry it proves.
+///
+/// The root the proof commits to must be one of `trusted_roots` for the proof's
+/// domain, so the root of the returned output is always a trusted root rather
+/// than one supplied alongside the proof.
+///
+/// # Arguments
+/// * `proof` - The proof to verify
+/// * `trusted_roots` - The roots trusted for each domain
+///
+/// # Returns
+/// The proven entry, tagged with the trusted root it verified against
+///
+/// # Errors
+/// Returns an error if the proof's root cannot be derived, is not a trusted root
+/// of its domain, or the proof does not verify against it
+pub fn verify_against_trusted_roots<P: DomainProof + HasRoot>(
+    proof: &P,
+    trusted_roots: &[(Domain, Vec<u8>)],
+) -> Result<MerkleProofOutput> {
+    let root = proof.root()?;
+    if !trusted_roots
+        .iter()
+        .any(|(domain, trusted)| *domain == P::DOMAIN && *trusted == root)
+    {
+        anyhow::bail!(
+            "Proof commits to a root that is not a trusted {:?} root",
+            P::DOMAIN
+        );
+    }
+    proof.verify_output(&root)
+}
+
+/// The reason [`verify_batch`] rejected a batch.
+#[derive(Debug)]
+pub enum BatchError {
+    /// The number of roots does not match the number of proofs, so no proof was verified
+    LengthMismatch {
+        /// The number of proofs given
+        proofs: usize,
+        /// The number of roots given
+        roots: usize,
+    },
+    /// A proof did not verify against its root
+    Proof {
+        /// The index of the failing proof
+        index: usize,
+        /// Why the proof failed
+        error: anyhow::Error,
+    },
+}
+
+impl core::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::LengthMismatch { proofs, roots } => {
+                write!(f, "Got {} roots for {} proofs", roots, proofs)
+            }
+            Self::Proof { index, error } => write!(f, "Proof at index {} failed: {}", index, error),
+        }
+    }
+}
+
+impl core::error::Error for BatchError {}
+
+/// Verifies proofs against their roots, stopping at the first proof that fails.
+///
+/// Each output is tagged with the proof's domain. A failure carries the index
+/// of the failing proof, so a caller committing the result can report which
+/// proof was bad instead of panicking.
+///
+/// # Arguments
+/// * `proofs` - The proofs to verify
+/// * `roots` - The root to verify each proof against, by index
+///
+/// # Returns
+/// The proven entry of every proof, in input order
+///
+/// # Errors
+/// Returns [`BatchError::LengthMismatch`] if there is not exactly one root per
+/// proof, or [`BatchError::Proof`] with the index and error of the first proof
+/// that does not verify
+pub fn verify_batch<P: DomainProof>(
+    proofs: &[P],
+    roots: &[Vec<u8>],
+) -> core::result::Result<Vec<MerkleProofOutput>, BatchError> {
+    if roots.len() != proofs.len() {
+        return Err(BatchError::LengthMismatch {
+            proofs: proofs.len(),
+            roots: roots.len(),
+        });
+    }
+    proofs
+        .iter()
+        .zip(roots)
+        .enumerate()
+        .map(|(index, (proof, root))| {
+            proof
+                .verify_output(root)
+                .map_err(|error| BatchError::Proof { index, error })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::stub::StubProof;
+    use alloc::vec;
+
+    #[test]
+    fn test_verify_against_trusted_roots() {
+        let trusted = vec![
+            (Domain::Ics23, vec![0x02; 32]),
+            (Domain::Ethereum, vec![0x01; 32]),
+        ];
+        let proof = StubProof::new(&[0x01; 32]);
+        let output = verify_against_trusted_roots(&proof, &trusted).unwrap();
+        assert_eq!(output.root, vec![0x01; 32]);
+        assert_eq!(output.domain, Domain::Ethereum);
+
+        // the root is trusted, but for another domain
+        let other_domain = StubProof::new(&[0x02; 32]);
+        assert!(verify_against_trusted_roots(&other_domain, &trusted).is_err());
+        assert!(verify_against_trusted_roots(&proof, &[]).is_err());
+    }
+
+    #[test]
+    fn test_verify_batch_reports_first_failure() {
+        let proofs = vec![StubProof::new(&[0x01; 32]), StubProof::new(&[0x02; 32])];
+        let outputs = verify_batch(&proofs, &[vec![0x01; 32], vec![0x02; 32]]).unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[1].root, vec![0x02; 32]);
+        assert!(outputs
+            .iter()
+            .all(|output| output.domain == Domain::Ethereum));
+
+        let error = verify_batch(&proofs, &[vec![0x01; 32], vec![0x01; 32]]).unwrap_err();
+        assert!(matches!(error, BatchError::Proof { index: 1, .. }));
+    }
+
+    #[test]
+    fn test_verify_batch_length_mismatch() {
+        let proofs = vec![StubProof::new(&[0x01; 32]), StubProof::new(&[0x02; 32])];
+        let error = verify_batch(&proofs, &[vec![0x01; 32]]).unwrap_err();
+        assert!(matches!(
+            error,
+            BatchError::LengthMismatch {
+                proofs: 2,
+                roots: 1
+            }
+        ));
+        let error =
+            verify_batch(&proofs, &[vec![0x01; 32], vec![0x02; 32], vec![0x03; 32]]).unwrap_err();
+        assert!(matches!(
+            error,
+            BatchError::LengthMismatch {
+                proofs: 2,
+                roots: 3
+            }
+        ));
+    }
+}
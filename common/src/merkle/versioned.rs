@@ -0,0 +1,153 @@
+//! Version tagging of serialized proofs.
+//!
+//! A proof serialized by one crate version may be deserialized by another, e.g.
+//! when a host prepares inputs for a guest built from a different release. If
+//! the proof layout changed in between, plain deserialization can succeed and
+//! still produce the wrong proof. Every proof type therefore carries a `version`
+//! field as its first field, deserialized with [`deserialize_version`], which
+//! rejects versions newer than [`PROOF_FORMAT_VERSION`] with an error naming the
+//! version.
+//!
+//! The version is an ordinary leading field rather than an untagged envelope, so
+//! it is read before the rest of the proof by self-describing formats such as
+//! JSON and by positional formats such as bincode alike.
+//!
+//! # Migration
+//! Proofs serialized before versioning, including the stored test vectors, have
+//! no `version` field. In self-describing formats they deserialize as version 0,
+//! whose layout is the same as that of version 1 apart from the missing field,
+//! so existing JSON vectors keep working unchanged. Positional formats cannot
+//! tell a missing field apart, so proofs stored that way must be re-serialized.
+//!
+//! # Equality
+//! The version records how a proof was read, not what it proves, so a legacy
+//! proof read as version 0 and the same proof rebuilt as version 1 are equal.
+//! Proof types implement `PartialEq`, `Eq` and `Hash` with [`impl_proof_eq`]
+//! over their content fields instead of deriving them, so that deduplication
+//! compares the proven content alone.
+extern crate alloc;
+
+use serde::{Deserialize, Deserializer};
+
+/// The version of the proof layouts of this crate.
+pub const PROOF_FORMAT_VERSION: u8 = 1;
+
+/// Deserializes the `version` field of a proof, rejecting unknown versions.
+///
+/// Meant for `#[serde(default, deserialize_with = "deserialize_version")]`, so
+/// that proofs serialized without the field read as version 0.
+///
+/// # Errors
+/// Returns an error if the version is newer than [`PROOF_FORMAT_VERSION`]
+pub fn deserialize_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let version = u8::deserialize(deserializer)?;
+    if version > PROOF_FORMAT_VERSION {
+        return Err(serde::de::Error::custom(alloc::format!(
+            "Unsupported proof version {}, this crate reads versions up to {}",
+            version,
+            PROOF_FORMAT_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+/// Implements `PartialEq`, `Eq` and `Hash` for a proof type over the listed
+/// fields, which must be all of its fields except `version`.
+#[macro_export]
+macro_rules! impl_proof_eq {
+    ($proof:ty, $($field:ident),+ $(,)?) => {
+        impl ::core::cmp::PartialEq for $proof {
+            fn eq(&self, other: &Self) -> bool {
+                $(self.$field == other.$field)&&+
+            }
+        }
+
+        impl ::core::cmp::Eq for $proof {}
+
+        impl ::core::hash::Hash for $proof {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                $(::core::hash::Hash::hash(&self.$field, state);)+
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec, vec::Vec};
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    struct Proof {
+        #[serde(default, deserialize_with = "deserialize_version")]
+        version: u8,
+        nodes: Vec<Vec<u8>>,
+        key: Vec<u8>,
+    }
+
+    fn proof() -> Proof {
+        Proof {
+            version: PROOF_FORMAT_VERSION,
+            nodes: vec![vec![0x01, 0x02]],
+            key: vec![0x03],
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_tags_current_version() {
+        let json = serde_json::to_string(&proof()).unwrap();
+        assert_eq!(json, r#"{"version":1,"nodes":[[1,2]],"key":[3]}"#);
+        let decoded: Proof = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, proof());
+    }
+
+    #[test]
+    fn test_version_is_not_part_of_equality() {
+        extern crate std;
+        use std::collections::HashSet;
+
+        #[derive(Deserialize, Debug)]
+        struct Nodes {
+            #[serde(default, deserialize_with = "deserialize_version")]
+            version: u8,
+            nodes: Vec<Vec<u8>>,
+        }
+        crate::impl_proof_eq!(Nodes, nodes);
+
+        let legacy: Nodes = serde_json::from_str(r#"{"nodes":[[1,2]]}"#).unwrap();
+        let current: Nodes = serde_json::from_str(r#"{"version":1,"nodes":[[1,2]]}"#).unwrap();
+        let other: Nodes = serde_json::from_str(r#"{"version":1,"nodes":[[3]]}"#).unwrap();
+        assert_ne!(legacy.version, current.version);
+        assert_eq!(legacy, current);
+        assert_ne!(current, other);
+        let unique: HashSet<_> = [legacy, current, other].into_iter().collect();
+        assert_eq!(unique.len(), 2);
+    }
+
+    #[test]
+    fn test_unversioned_proof_is_v0() {
+        let decoded: Proof = serde_json::from_str(r#"{"nodes":[[1,2]],"key":[3]}"#).unwrap();
+        assert_eq!(decoded.version, 0);
+        assert_eq!(decoded.nodes, proof().nodes);
+    }
+
+    #[test]
+    fn test_rejects_unknown_versions() {
+        let future = r#"{"version":2,"nodes":[[1,2]],"key":[3]}"#;
+        let error = serde_json::from_str::<Proof>(future).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unsupported proof version 2, this crate reads versions up to 1"));
+
+        // the version is read before a future layout's fields
+        let relayout = r#"{"version":2,"trie":[[1,2]]}"#;
+        let error = serde_json::from_str::<Proof>(relayout).unwrap_err();
+        assert!(error.to_string().starts_with("Unsupported proof version 2"));
+
+        // a sequence, as positional formats present a struct
+        let positional = r#"[2,[[1,2]],[3]]"#;
+        let error = serde_json::from_str::<Proof>(positional).unwrap_err();
+        assert!(error.to_string().starts_with("Unsupported proof version 2"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Hooks for monitoring proof verification.
+//!
+//! Services can count verified and rejected proofs and record verification
+//! latency by passing a [`VerificationObserver`] to
+//! [`ObservedVerify::verify_observed`], without this crate depending on any
+//! metrics backend. Like the cache, this module is only available outside the
+//! zkVM; guest builds compile it out entirely.
+extern crate std;
+use core::time::Duration;
+use std::time::Instant;
+
+use anyhow::Result;
+
+use crate::merkle::types::{Domain, DomainProof};
+
+/// Why a proof failed verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureKind {
+    /// The proof was well-formed but does not prove its entry under the root
+    Rejected,
+    /// Verification returned an error, e.g. for a malformed proof or a root mismatch
+    Error,
+}
+
+/// Receives the outcome of every observed verification.
+///
+/// Both hooks default to doing nothing, so implementations only override the
+/// ones they need.
+pub trait VerificationObserver {
+    /// Called after a proof verified successfully.
+    ///
+    /// # Arguments
+    /// * `domain` - The domain of the proof
+    /// * `duration` - How long verification took
+    fn on_success(&self, domain: Domain, duration: Duration) {
+        let _ = (domain, duration);
+    }
+
+    /// Called after a proof failed verification.
+    ///
+    /// # Arguments
+    /// * `domain` - The domain of the proof
+    /// * `error_kind` - Why verification failed
+    fn on_failure(&self, domain: Domain, error_kind: FailureKind) {
+        let _ = (domain, error_kind);
+    }
+}
+
+/// An observer that ignores every event.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl VerificationObserver for NoopObserver {}
+
+/// Verification that reports its outcome to an observer.
+///
+/// Implemented for every [`DomainProof`], so that each domain's proofs report
+/// under their own [`Domain`].
+pub trait ObservedVerify {
+    /// Verifies the proof against `root` and reports the outcome.
+    ///
+    /// # Arguments
+    /// * `root` - The expected Merkle root to verify against
+    /// * `observer` - The observer to report to, or `None` to verify without reporting
+    ///
+    /// # Returns
+    /// The result of `MerkleVerifiable::verify`, unchanged
+    ///
+    /// # Errors
+    /// Returns the error of `MerkleVerifiable::verify`, unchanged
+    fn verify_observed(
+        &self,
+        root: &[u8],
+        observer: Option<&dyn VerificationObserver>,
+    ) -> Result<bool>;
+}
+
+impl<P: DomainProof> ObservedVerify for P {
+    fn verify_observed(
+        &self,
+        root: &[u8],
+        observer: Option<&dyn VerificationObserver>,
+    ) -> Result<bool> {
+        let Some(observer) = observer else {
+            return self.verify(root);
+        };
+        let start = Instant::now();
+        let result = self.verify(root);
+        match &result {
+            Ok(true) => observer.on_success(P::DOMAIN, start.elapsed()),
+            Ok(false) => observer.on_failure(P::DOMAIN, FailureKind::Rejected),
+            Err(_) => observer.on_failure(P::DOMAIN, FailureKind::Error),
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec::Vec;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::merkle::stub::StubProof;
+
+    #[derive(Default)]
+    struct Recorder {
+        successes: Mutex<Vec<Domain>>,
+        failures: Mutex<Vec<(Domain, FailureKind)>>,
+    }
+
+    impl VerificationObserver for Recorder {
+        fn on_success(&self, domain: Domain, _duration: Duration) {
+            self.successes.lock().unwrap().push(domain);
+        }
+
+        fn on_failure(&self, domain: Domain, error_kind: FailureKind) {
+            self.failures.lock().unwrap().push((domain, error_kind));
+        }
+    }
+
+    #[test]
+    fn test_verify_observed() {
+        let recorder = Recorder::default();
+        assert!(StubProof::new(&[])
+            .verify_observed(&[], Some(&recorder))
+            .unwrap());
+        assert!(!StubProof::new(&[0x01])
+            .verify_observed(&[], Some(&recorder))
+            .unwrap());
+        assert!(StubProof::malformed()
+            .verify_observed(&[], Some(&recorder))
+            .is_err());
+        assert!(StubProof::new(&[])
+            .verify_observed(&[], Some(&NoopObserver))
+            .unwrap());
+        assert!(StubProof::new(&[]).verify_observed(&[], None).unwrap());
+
+        assert_eq!(*recorder.successes.lock().unwrap(), [Domain::Ethereum]);
+        assert_eq!(
+            *recorder.failures.lock().unwrap(),
+            [
+                (Domain::Ethereum, FailureKind::Rejected),
+                (Domain::Ethereum, FailureKind::Error)
+            ]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Verifies a batch of 100 account proofs one after another and with
+//! `verify_batch_parallel`, and reports the speedup on this machine.
+//!
+//! Run with `cargo bench -p ethereum --features mock,rayon --bench batch`.
+use std::{hint::black_box, thread::available_parallelism, time::Instant};
+
+use common::merkle::types::MerkleVerifiable;
+use ethereum::{
+    merkle_lib::{
+        batch::verify_batch_parallel,
+        digest_keccak,
+        types::{EthereumAccountProof, EthereumProofType},
+    },
+    mock::{account_rlp, EMPTY_CODE_HASH},
+    timewave_trie::builder::TrieBuilder,
+};
+
+const PROOFS: u64 = 100;
+const ACCOUNTS: u64 = 10_000;
+const ITERATIONS: u32 = 200;
+
+fn main() {
+    // prove 100 accounts of a larger state trie so that proofs have a realistic depth
+    let addresses: Vec<Vec<u8>> = (0..ACCOUNTS)
+        .map(|index| digest_keccak(&index.to_be_bytes())[..20].to_vec())
+        .collect();
+    let accounts: Vec<Vec<u8>> = (0..ACCOUNTS)
+        .map(|index| account_rlp(index, index, &[0u8; 32], &EMPTY_CODE_HASH))
+        .collect();
+    let mut state = TrieBuilder::new();
+    for (address, account) in addresses.iter().zip(&accounts) {
+        state
+            .insert(&digest_keccak(address), account.clone())
+            .unwrap();
+    }
+    let root = state.root().to_vec();
+    let proofs: Vec<EthereumProofType> = addresses
+        .iter()
+        .zip(&accounts)
+        .take(PROOFS as usize)
+        .map(|(address, account)| {
+            EthereumProofType::Account(EthereumAccountProof::new(
+                state.proof(&digest_keccak(address)),
+                address.clone(),
+                account.clone(),
+            ))
+        })
+        .collect();
+    let roots = vec![root; proofs.len()];
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for (proof, root) in black_box(&proofs).iter().zip(&roots) {
+            assert!(proof.verify(root).unwrap());
+        }
+    }
+    let sequential = start.elapsed() / ITERATIONS;
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let results = verify_batch_parallel(black_box(&proofs), &roots).unwrap();
+        assert!(results.iter().all(|result| *result.as_ref().unwrap()));
+    }
+    let parallel = start.elapsed() / ITERATIONS;
+
+    println!(
+        "threads available: {}",
+        available_parallelism().map_or(1, |threads| threads.get())
+    );
+    println!("{PROOFS} proofs sequentially:               {sequential:?}");
+    println!("{PROOFS} proofs with verify_batch_parallel: {parallel:?}");
+    println!(
+        "speedup: {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
@@ -0,0 +1,97 @@
+//! Verifies ten storage slots of one account, once as ten combined proofs and
+//! once through `EthereumAccountProof::verify_storage_proofs`, which verifies
+//! and decodes the account a single time.
+//!
+//! Run with `cargo bench -p ethereum --features mock --bench storage_slots`.
+use std::{hint::black_box, time::Instant};
+
+use common::merkle::types::MerkleVerifiable;
+use ethereum::{
+    merkle_lib::{
+        digest_keccak,
+        types::{EthereumAccountProof, EthereumCombinedProof, EthereumStorageProof},
+    },
+    mock::{account_rlp, EMPTY_CODE_HASH},
+    timewave_trie::builder::TrieBuilder,
+};
+
+const SLOTS: u64 = 10;
+const ACCOUNTS: u64 = 1_000;
+const ITERATIONS: u32 = 2_000;
+
+fn main() {
+    let slots: Vec<[u8; 32]> = (0..SLOTS)
+        .map(|slot| {
+            let mut key = [0u8; 32];
+            key[24..].copy_from_slice(&slot.to_be_bytes());
+            key
+        })
+        .collect();
+    let mut storage = TrieBuilder::new();
+    for slot in &slots {
+        storage
+            .insert(&digest_keccak(slot), vec![0x82, 0x04, 0xd2])
+            .unwrap();
+    }
+
+    // surround the account with others so that its proof has a realistic depth
+    let address = [0x11u8; 20];
+    let account = account_rlp(1, 100, &storage.root(), &EMPTY_CODE_HASH);
+    let mut state = TrieBuilder::new();
+    state
+        .insert(&digest_keccak(&address), account.clone())
+        .unwrap();
+    for index in 0..ACCOUNTS {
+        let other = digest_keccak(&index.to_be_bytes());
+        let other_account = account_rlp(index, index, &[0u8; 32], &EMPTY_CODE_HASH);
+        state
+            .insert(&digest_keccak(&other[..20]), other_account)
+            .unwrap();
+    }
+    let state_root = state.root();
+
+    let account_proof = EthereumAccountProof::new(
+        state.proof(&digest_keccak(&address)),
+        address.to_vec(),
+        account,
+    );
+    let storage_proofs: Vec<EthereumStorageProof> = slots
+        .iter()
+        .map(|slot| {
+            EthereumStorageProof::new(
+                storage.proof(&digest_keccak(slot)),
+                slot.to_vec(),
+                vec![0x82, 0x04, 0xd2],
+            )
+        })
+        .collect();
+    let combined_proofs: Vec<EthereumCombinedProof> = storage_proofs
+        .iter()
+        .map(|storage_proof| {
+            EthereumCombinedProof::new(account_proof.clone(), storage_proof.clone())
+        })
+        .collect();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for combined_proof in &combined_proofs {
+            assert!(black_box(combined_proof).verify(&state_root).unwrap());
+        }
+    }
+    let per_combined = start.elapsed() / ITERATIONS;
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert!(black_box(&account_proof)
+            .verify_storage_proofs(&state_root, black_box(&storage_proofs))
+            .unwrap());
+    }
+    let per_shared = start.elapsed() / ITERATIONS;
+
+    println!("{SLOTS} slots as combined proofs:            {per_combined:?}");
+    println!("{SLOTS} slots through verify_storage_proofs: {per_shared:?}");
+    println!(
+        "speedup: {:.2}x",
+        per_combined.as_secs_f64() / per_shared.as_secs_f64()
+    );
+}
@@ -0,0 +1,198 @@
+//! Fail-over between several Ethereum RPC endpoints.
+//!
+//! [`FallbackEvmMerkleRpcClient`] wraps one [`EvmMerkleRpcClient`] per endpoint
+//! and sends every request to them in order of preference, retrying transient
+//! failures with exponential backoff before moving on to the next endpoint. It
+//! implements [`MerkleClient`], so it can stand in for a single client.
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use alloy::transports::TransportError;
+use anyhow::Result;
+use common::merkle::types::MerkleClient;
+use core::{future::Future, time::Duration};
+use futures::future::try_join_all;
+
+use crate::{
+    ethereum_rpc::rpc::{EvmMerkleRpcClient, StatePruned},
+    merkle_lib::types::EthereumCombinedProof,
+};
+
+/// The number of times a request is retried against one endpoint before failing over.
+pub const DEFAULT_RETRIES: u32 = 2;
+
+/// The delay before the first retry against an endpoint, doubled on every further retry.
+pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Returns whether a failed request may succeed if sent again, i.e. whether
+/// it failed in the transport or on the node rather than on its own input or
+/// on a response that does not verify.
+fn is_retryable(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<TransportError>().is_some()
+}
+
+/// An Ethereum RPC client that fails over between several endpoints.
+///
+/// Requests are sent to the endpoints in order. A request failing in the
+/// transport or on the node is retried against the same endpoint with
+/// exponential backoff, and only once those retries are exhausted does the
+/// client move on to the next endpoint. [`StatePruned`] errors are not
+/// retried, since the node will not regain the state, but the next endpoint
+/// is still tried as it may be an archive node. Any other error, such as an
+/// invalid address or a proof that does not verify, would fail the same way
+/// on every endpoint and is returned right away.
+///
+/// This crate has no coprocessor configuration of its own; a configuration
+/// listing several URLs for a chain builds this client from them with
+/// [`Self::new`].
+///
+/// # Fields
+/// * `clients` - One client per endpoint, in order of preference
+/// * `retries` - The number of retries per endpoint after the first attempt
+/// * `backoff` - The delay before the first retry against an endpoint
+pub struct FallbackEvmMerkleRpcClient {
+    /// The clients for each endpoint, in order of preference
+    pub clients: Vec<EvmMerkleRpcClient>,
+    /// The number of retries per endpoint after the first attempt
+    pub retries: u32,
+    /// The delay before the first retry against an endpoint
+    pub backoff: Duration,
+}
+
+impl FallbackEvmMerkleRpcClient {
+    /// Creates a client over an ordered list of endpoints with the default retry policy.
+    ///
+    /// # Arguments
+    /// * `rpc_urls` - The RPC endpoint URLs, primary first
+    ///
+    /// # Returns
+    /// A new `FallbackEvmMerkleRpcClient` instance
+    pub fn new(rpc_urls: Vec<String>) -> Self {
+        Self {
+            clients: rpc_urls
+                .into_iter()
+                .map(|rpc_url| EvmMerkleRpcClient { rpc_url })
+                .collect(),
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+        }
+    }
+
+    /// Sets the retry policy applied to each endpoint.
+    ///
+    /// # Arguments
+    /// * `retries` - The number of retries per endpoint after the first attempt
+    /// * `backoff` - The delay before the first retry, doubled on every further retry
+    ///
+    /// # Returns
+    /// The client with the new retry policy
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// Runs a request against each endpoint in turn until one succeeds.
+    ///
+    /// Any method of [`EvmMerkleRpcClient`] can be made fault tolerant this way,
+    /// e.g. `client.call(|c| c.get_receipt_proof(height, index)).await`.
+    ///
+    /// # Arguments
+    /// * `request` - Sends the request through the client for one endpoint
+    ///
+    /// # Returns
+    /// The result of the first successful attempt
+    ///
+    /// # Errors
+    /// Returns the error of the last attempt if every endpoint failed, the
+    /// error of the first attempt that cannot succeed on retry, or an error if
+    /// the client holds no endpoints
+    pub async fn call<'a, T, F, Fut>(&'a self, request: F) -> Result<T>
+    where
+        F: Fn(&'a EvmMerkleRpcClient) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        for client in &self.clients {
+            let mut backoff = self.backoff;
+            for attempt in 0..=self.retries {
+                if attempt > 0 {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                match request(client).await {
+                    Ok(value) => return Ok(value),
+                    Err(error) => {
+                        let pruned = error.downcast_ref::<StatePruned>().is_some();
+                        if !pruned && !is_retryable(&error) {
+                            return Err(error);
+                        }
+                        last_error = Some(error);
+                        if pruned {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        match last_error {
+            Some(error) => {
+                Err(error.context(format!("All {} RPC endpoints failed", self.clients.len())))
+            }
+            None => anyhow::bail!("No RPC endpoints configured"),
+        }
+    }
+
+    /// Retrieves a storage slot's proof at each of several heights.
+    ///
+    /// Behaves like
+    /// [`EvmMerkleRpcClient::get_storage_proofs_over_heights`], but each height
+    /// is retried and failed over on its own, so one failing request does not
+    /// refetch the other heights.
+    ///
+    /// # Arguments
+    /// * `key` - The storage key to prove
+    /// * `address` - The account address to prove
+    /// * `heights` - The block heights to prove at
+    ///
+    /// # Returns
+    /// Each height paired with its proof, verified against the state root at
+    /// that height, in the order of `heights`
+    ///
+    /// # Errors
+    /// Returns the error of the last attempt for the first height that every
+    /// endpoint failed
+    pub async fn get_storage_proofs_over_heights(
+        &self,
+        key: &str,
+        address: &str,
+        heights: &[u64],
+    ) -> Result<Vec<(u64, EthereumCombinedProof)>> {
+        try_join_all(heights.iter().map(|&height| async move {
+            let proof = self
+                .call(|client| client.get_verified_storage_proof(key, address, height))
+                .await?;
+            Ok((height, proof))
+        }))
+        .await
+    }
+}
+
+impl MerkleClient for FallbackEvmMerkleRpcClient {
+    /// Retrieves an account proof from the first endpoint that serves it.
+    ///
+    /// # Arguments
+    /// * `key` - The storage key to prove
+    /// * `address` - The account address to prove
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// A vector of bytes containing the serialized proof
+    ///
+    /// # Errors
+    /// Returns the error of the last attempt if every endpoint failed
+    async fn get_proof(&self, key: &str, address: &str, height: u64) -> Result<Vec<u8>> {
+        self.call(|client| client.get_proof(key, address, height))
+            .await
+    }
+}
@@ -1,5 +1,11 @@
 //! Ethereum RPC client implementation for fetching Merkle proofs.
 
+/// RPC client that fails over between several Ethereum endpoints.
+pub mod fallback;
+
+/// Recording and offline replay of RPC responses.
+pub mod replay;
+
 /// RLP encoding utilities for Ethereum data structures.
 pub mod rlp;
 
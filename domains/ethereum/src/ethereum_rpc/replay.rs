@@ -0,0 +1,299 @@
+//! Recording and replay of Ethereum RPC responses.
+//!
+//! [`RecordingClient`] forwards requests to a live endpoint and keeps every
+//! response in an [`RpcFixture`], which can be written to a JSON file.
+//! [`ReplayClient`] serves responses from such a file without touching the
+//! network, so that tests built on recorded data are deterministic and do not
+//! age out as the chain moves on.
+//!
+//! Fixtures are JSON objects mapping request keys to responses, ordered by key
+//! so that re-recording produces minimal diffs:
+//! * `eth_getProof/<address>/<key>/<height>` - the `eth_getProof` response
+//! * `stateRoot/<height>` - the hex-encoded state root of the block
+//! * `param/<name>` - a value noted by the recording test, e.g. the height used
+extern crate alloc;
+
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use anyhow::{Context, Result};
+use common::merkle::types::MerkleClient;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{
+    ethereum_rpc::rpc::{
+        combined_proof_from_response, storage_proof_from_response, EvmMerkleRpcClient,
+    },
+    merkle_lib::types::{EthereumCombinedProof, EthereumStorageProof},
+};
+
+/// A source of `eth_getProof` responses and block state roots.
+///
+/// Implemented by the live [`EvmMerkleRpcClient`] as well as by the recording
+/// and replay clients, so that code written against it runs unchanged on
+/// recorded data.
+pub trait ProofSource: MerkleClient {
+    #[allow(async_fn_in_trait)]
+    /// Retrieves the state root of the block at a height.
+    ///
+    /// # Arguments
+    /// * `height` - The block height
+    ///
+    /// # Returns
+    /// The 32-byte state root
+    ///
+    /// # Errors
+    /// Returns an error if the block cannot be retrieved
+    async fn state_root_at(&self, height: u64) -> Result<Vec<u8>>;
+
+    #[allow(async_fn_in_trait)]
+    /// Retrieves an account proof together with a storage proof for one of its slots.
+    ///
+    /// # Arguments
+    /// * `key` - The hex-encoded storage key to prove
+    /// * `address` - The hex-encoded account address to prove
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// The combined account and storage proof
+    ///
+    /// # Errors
+    /// Returns an error if the proof cannot be retrieved or decoded
+    async fn combined_proof(
+        &self,
+        key: &str,
+        address: &str,
+        height: u64,
+    ) -> Result<EthereumCombinedProof> {
+        combined_proof_from_response(&self.get_proof(key, address, height).await?, address)
+    }
+
+    #[allow(async_fn_in_trait)]
+    /// Retrieves a storage proof for a slot of an account.
+    ///
+    /// # Arguments
+    /// * `key` - The hex-encoded storage key to prove
+    /// * `address` - The hex-encoded account address
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// The storage proof for `key`
+    ///
+    /// # Errors
+    /// Returns an error if the proof cannot be retrieved or decoded
+    async fn storage_proof(
+        &self,
+        key: &str,
+        address: &str,
+        height: u64,
+    ) -> Result<EthereumStorageProof> {
+        storage_proof_from_response(&self.get_proof(key, address, height).await?)
+    }
+}
+
+impl ProofSource for EvmMerkleRpcClient {
+    async fn state_root_at(&self, height: u64) -> Result<Vec<u8>> {
+        EvmMerkleRpcClient::state_root_at(self, height).await
+    }
+}
+
+/// Recorded RPC responses, keyed by request parameters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RpcFixture {
+    /// The recorded responses, see the module documentation for the key format
+    pub entries: BTreeMap<String, serde_json::Value>,
+}
+
+impl RpcFixture {
+    /// Reads a fixture from a JSON file.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the fixture file
+    ///
+    /// # Returns
+    /// The fixture held by the file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a fixture
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read(path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+
+    /// Writes the fixture to a JSON file, replacing any existing file.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the fixture file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut json = serde_json::to_vec_pretty(self)?;
+        json.push(b'\n');
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write fixture {}", path.display()))
+    }
+
+    /// Returns the value noted under `name` when the fixture was recorded.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the parameter
+    ///
+    /// # Returns
+    /// The noted value
+    ///
+    /// # Errors
+    /// Returns an error if no such parameter was noted
+    pub fn param(&self, name: &str) -> Result<&str> {
+        self.lookup(&param_key(name))?
+            .as_str()
+            .with_context(|| format!("Parameter {} is not a string", name))
+    }
+
+    fn lookup(&self, key: &str) -> Result<&serde_json::Value> {
+        self.entries
+            .get(key)
+            .with_context(|| format!("No recorded response for {}", key))
+    }
+}
+
+/// Forwards requests to a live endpoint and records every response.
+pub struct RecordingClient {
+    /// The client for the live endpoint
+    pub client: EvmMerkleRpcClient,
+    fixture: Mutex<RpcFixture>,
+}
+
+impl RecordingClient {
+    /// Creates a recording client with an empty fixture.
+    ///
+    /// # Arguments
+    /// * `client` - The client for the live endpoint
+    ///
+    /// # Returns
+    /// A new `RecordingClient` instance
+    pub fn new(client: EvmMerkleRpcClient) -> Self {
+        Self {
+            client,
+            fixture: Mutex::new(RpcFixture::default()),
+        }
+    }
+
+    /// Notes a parameter of the recording, such as the height or address used,
+    /// so that replaying tests can read it back with [`RpcFixture::param`].
+    ///
+    /// # Arguments
+    /// * `name` - The name of the parameter
+    /// * `value` - The value of the parameter
+    pub fn note(&self, name: &str, value: &str) {
+        self.record(param_key(name), serde_json::Value::from(value));
+    }
+
+    /// Returns a copy of everything recorded so far.
+    pub fn fixture(&self) -> RpcFixture {
+        self.lock().clone()
+    }
+
+    fn record(&self, key: String, value: serde_json::Value) {
+        self.lock().entries.insert(key, value);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RpcFixture> {
+        // a panic while holding the lock cannot leave a half-inserted entry behind
+        self.fixture
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl MerkleClient for RecordingClient {
+    async fn get_proof(&self, key: &str, address: &str, height: u64) -> Result<Vec<u8>> {
+        let proof = self.client.get_proof(key, address, height).await?;
+        self.record(
+            proof_key(key, address, height),
+            serde_json::from_slice(&proof)?,
+        );
+        Ok(proof)
+    }
+}
+
+impl ProofSource for RecordingClient {
+    async fn state_root_at(&self, height: u64) -> Result<Vec<u8>> {
+        let state_root = self.client.state_root_at(height).await?;
+        self.record(
+            state_root_key(height),
+            serde_json::Value::from(hex::encode(&state_root)),
+        );
+        Ok(state_root)
+    }
+}
+
+/// Serves recorded responses without a network connection.
+pub struct ReplayClient {
+    /// The recorded responses
+    pub fixture: RpcFixture,
+}
+
+impl ReplayClient {
+    /// Creates a replay client over a fixture file.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the fixture file
+    ///
+    /// # Returns
+    /// A new `ReplayClient` instance
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be read
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            fixture: RpcFixture::load(path)?,
+        })
+    }
+}
+
+impl MerkleClient for ReplayClient {
+    async fn get_proof(&self, key: &str, address: &str, height: u64) -> Result<Vec<u8>> {
+        let proof = self.fixture.lookup(&proof_key(key, address, height))?;
+        Ok(serde_json::to_vec(proof)?)
+    }
+}
+
+impl ProofSource for ReplayClient {
+    async fn state_root_at(&self, height: u64) -> Result<Vec<u8>> {
+        let state_root = self
+            .fixture
+            .lookup(&state_root_key(height))?
+            .as_str()
+            .context("Recorded state root is not a string")?;
+        Ok(hex::decode(state_root)?)
+    }
+}
+
+/// Normalizes a hex string so that `0xAB` and `ab` map to the same fixture key.
+fn normalize_hex(value: &str) -> String {
+    value.trim_start_matches("0x").to_lowercase()
+}
+
+fn proof_key(key: &str, address: &str, height: u64) -> String {
+    format!(
+        "eth_getProof/{}/{}/{}",
+        normalize_hex(address),
+        normalize_hex(key),
+        height
+    )
+}
+
+fn state_root_key(height: u64) -> String {
+    format!("stateRoot/{}", height)
+}
+
+fn param_key(name: &str) -> String {
+    format!("param/{}", name)
+}
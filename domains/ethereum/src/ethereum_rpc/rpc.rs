@@ -2,24 +2,30 @@ extern crate alloc;
 
 use alloc::{string::String, vec, vec::Vec};
 use alloy::{
+    eips::{BlockId, Encodable2718, RpcBlockHash},
     hex::FromHex,
     providers::{Provider, ProviderBuilder},
-    rpc::types::{EIP1186AccountProofResponse, TransactionReceipt},
-    serde::JsonStorageKey,
+    rpc::types::{EIP1186AccountProofResponse, EIP1186StorageProof, TransactionReceipt},
+    transports::TransportError,
 };
-use alloy_primitives::{Address, FixedBytes};
+use alloy_primitives::{Address, FixedBytes, B256};
 use alloy_trie::{proof::ProofRetainer, root::adjust_index_for_rlp, HashBuilder, Nibbles};
 use anyhow::{Context, Result};
-use common::merkle::types::MerkleClient;
+use common::merkle::types::{MerkleClient, MerkleVerifiable};
 use core::str::FromStr;
+use futures::future::try_join_all;
 use url::Url;
 
 use crate::{
     ethereum_rpc::rlp::encode_receipt,
     merkle_lib::{
+        eip1967::{decode_address_slot, IMPLEMENTATION_SLOT},
+        eip4788::{root_slot, timestamp_slot, BEACON_ROOTS_ADDRESS},
+        erc20::TokenStorageLayout,
         rlp_decode_bytes,
         types::{
-            EthereumAccountProof, EthereumCombinedProof, EthereumReceiptProof, EthereumStorageProof,
+            EthereumAccessListProof, EthereumAccountProof, EthereumCombinedProof,
+            EthereumReceiptProof, EthereumStorageProof, EthereumTransactionProof,
         },
     },
 };
@@ -36,6 +42,210 @@ pub struct EvmMerkleRpcClient {
     pub rpc_url: String,
 }
 
+/// Error messages with which nodes report that the state at a height has been pruned.
+const PRUNED_STATE_ERRORS: [&str; 6] = [
+    "missing trie node",
+    "state not available",
+    "state is not available",
+    "historical state",
+    "world state unavailable",
+    "distance to target block exceeds maximum proof window",
+];
+
+/// The node no longer holds the state at the requested height.
+///
+/// Non-archive nodes prune old state, so proofs for heights outside their
+/// pruning window must be fetched from an archive endpoint; retrying against
+/// the same node will not succeed. Proof methods return this error wrapped in
+/// an [`anyhow::Error`], from which it can be recovered with `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatePruned {
+    /// The height whose state is unavailable
+    pub height: u64,
+}
+
+impl core::fmt::Display for StatePruned {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "State at height {} has been pruned, use an archive endpoint",
+            self.height
+        )
+    }
+}
+
+impl std::error::Error for StatePruned {}
+
+/// A serialized proof together with the block it was taken at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockProof {
+    /// The hash of the block the proof was taken at
+    pub block_hash: Vec<u8>,
+    /// The height of the block
+    pub height: u64,
+    /// The state root of the block, to verify the proof against
+    pub state_root: Vec<u8>,
+    /// The serialized proof, in the same format as `get_proof`
+    pub proof: Vec<u8>,
+}
+
+/// Returns whether an RPC error message reports pruned state.
+pub(crate) fn is_pruned_state_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    PRUNED_STATE_ERRORS
+        .iter()
+        .any(|pattern| message.contains(pattern))
+}
+
+/// Converts an `eth_getProof` failure at `height` into [`StatePruned`] if the
+/// node reports pruned state.
+fn proof_error(error: TransportError, height: u64) -> anyhow::Error {
+    if is_pruned_state_error(&error.to_string()) {
+        StatePruned { height }.into()
+    } else {
+        error.into()
+    }
+}
+
+/// Decodes a serialized `eth_getProof` response into a combined proof for its
+/// account and first storage slot.
+///
+/// # Arguments
+/// * `proof` - The serialized response, as returned by `get_proof`
+/// * `address` - The hex-encoded account address the proof was requested for
+///
+/// # Returns
+/// The combined account and storage proof
+///
+/// # Errors
+/// Returns an error if the response cannot be decoded, holds no storage proof,
+/// or its storage proof does not belong to the account
+pub(crate) fn combined_proof_from_response(
+    proof: &[u8],
+    address: &str,
+) -> Result<EthereumCombinedProof> {
+    let proof_deserialized: EIP1186AccountProofResponse = serde_json::from_slice(proof)?;
+    let account_proof: Vec<Vec<u8>> = proof_deserialized
+        .account_proof
+        .iter()
+        .map(|b| b.to_vec())
+        .collect();
+    let leaf_node_decoded: Vec<crate::timewave_rlp::Bytes> = rlp_decode_bytes(
+        proof_deserialized
+            .account_proof
+            .last()
+            .context("Failed to get last account proof")?,
+    )?;
+    let stored_account = leaf_node_decoded
+        .last()
+        .context("Failed to extract leaf from account proof")?
+        .to_vec();
+    let account_proof =
+        EthereumAccountProof::new(account_proof.clone(), hex::decode(address)?, stored_account);
+    let storage_proof = storage_proof_from_entry(
+        proof_deserialized
+            .storage_proof
+            .first()
+            .context("Failed to get first storage proof")?,
+    )?;
+    let combined_proof = EthereumCombinedProof::new(account_proof, storage_proof);
+    combined_proof.assert_consistent()?;
+    Ok(combined_proof)
+}
+
+/// Decodes the first storage proof of a serialized `eth_getProof` response.
+///
+/// # Arguments
+/// * `proof` - The serialized response, as returned by `get_proof`
+///
+/// # Returns
+/// The storage proof for the first requested slot
+///
+/// # Errors
+/// Returns an error if the response cannot be decoded or holds no storage proof
+pub(crate) fn storage_proof_from_response(proof: &[u8]) -> Result<EthereumStorageProof> {
+    let proof_deserialized: EIP1186AccountProofResponse = serde_json::from_slice(proof)?;
+    storage_proof_from_entry(
+        proof_deserialized
+            .storage_proof
+            .first()
+            .context("Failed to get first storage proof")?,
+    )
+}
+
+/// Decodes one storage proof of an `eth_getProof` response.
+///
+/// A slot that was never written reads as zero and is proven by an exclusion
+/// proof, whose last node is not a leaf of the slot, so its value is left
+/// empty rather than read from that node.
+///
+/// # Arguments
+/// * `storage_proof` - The storage proof entry of the response
+///
+/// # Returns
+/// The storage proof, with an empty value if the slot reads as zero
+///
+/// # Errors
+/// Returns an error if the leaf of a written slot cannot be decoded
+fn storage_proof_from_entry(storage_proof: &EIP1186StorageProof) -> Result<EthereumStorageProof> {
+    let nodes: Vec<Vec<u8>> = storage_proof.proof.iter().map(|b| b.to_vec()).collect();
+    let value = if storage_proof.value.is_zero() {
+        // the slot was never written, so the proof is an exclusion proof
+        vec![]
+    } else {
+        let leaf_node_decoded = rlp_decode_bytes(
+            nodes
+                .last()
+                .context("Failed to extract leaf from storage proof")?,
+        )?;
+        leaf_node_decoded
+            .last()
+            .context("Failed to extract value from leaf")?
+            .to_vec()
+    };
+    Ok(EthereumStorageProof::new(
+        nodes,
+        storage_proof.key.as_b256().to_vec(),
+        value,
+    ))
+}
+
+/// Builds a trie keyed by RLP-encoded index, as the receipts and transactions
+/// tries are, and retains a proof for each of `indices`.
+///
+/// # Arguments
+/// * `values` - The encoded values, in index order
+/// * `indices` - The indices to retain proofs for, each less than `values.len()`
+///
+/// # Returns
+/// The trie root and the proof nodes for each index, in the order of `indices`
+pub(crate) fn index_trie_proofs(values: &[Vec<u8>], indices: &[u32]) -> (B256, Vec<Vec<Vec<u8>>>) {
+    let targets: Vec<Nibbles> = indices
+        .iter()
+        .map(|index| Nibbles::unpack(crate::timewave_rlp::encode_fixed_size(index)))
+        .collect();
+    let retainer = ProofRetainer::new(targets.clone());
+    let mut hb: HashBuilder = HashBuilder::default().with_proof_retainer(retainer);
+    for i in 0..values.len() {
+        let index = adjust_index_for_rlp(i, values.len());
+        let index_buffer = crate::timewave_rlp::encode_fixed_size(&index);
+        hb.add_leaf(Nibbles::unpack(&index_buffer), &values[index]);
+    }
+    let root = hb.root();
+    let proof_nodes = hb.take_proof_nodes();
+    let proofs = targets
+        .iter()
+        .map(|target| {
+            proof_nodes
+                .matching_nodes_sorted(target)
+                .into_iter()
+                .map(|(_, node)| node.to_vec())
+                .collect()
+        })
+        .collect();
+    (root, proofs)
+}
+
 impl MerkleClient for EvmMerkleRpcClient {
     /// Retrieves an account proof from an Ethereum node.
     ///
@@ -47,88 +257,223 @@ impl MerkleClient for EvmMerkleRpcClient {
     /// # Returns
     /// A vector of bytes containing the serialized proof
     ///
-    /// # Panics
-    /// Panics if the RPC call fails or if the proof cannot be serialized
+    /// # Errors
+    /// Returns [`StatePruned`] if the node no longer holds the state at `height`,
+    /// or another error if the RPC call fails or the proof cannot be serialized
     async fn get_proof(&self, key: &str, address: &str, height: u64) -> Result<Vec<u8>> {
         let address_object = Address::from_hex(address)?;
         let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
         let proof: EIP1186AccountProofResponse = provider
             .get_proof(address_object, vec![FixedBytes::from_hex(key)?])
             .block_id(height.into())
-            .await?;
+            .await
+            .map_err(|e| proof_error(e, height))?;
         Ok(serde_json::to_vec(&proof)?)
     }
 }
 
 impl EvmMerkleRpcClient {
+    /// Checks whether the endpoint can serve state at an old height.
+    ///
+    /// # Arguments
+    /// * `test_height` - A height older than the pruning window of a full node
+    ///
+    /// # Returns
+    /// `true` if the endpoint returned a proof at `test_height`, `false` if it
+    /// reported the state as pruned
+    ///
+    /// # Errors
+    /// Returns an error if the RPC call fails for any other reason
+    pub async fn probe_archive(&self, test_height: u64) -> Result<bool> {
+        let key = hex::encode([0u8; 32]);
+        match self
+            .get_proof(&key, &hex::encode([0u8; 20]), test_height)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(error) if error.downcast_ref::<StatePruned>().is_some() => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Retrieves the latest finalized block height and its state root.
+    ///
+    /// # Returns
+    /// A tuple of the finalized block height and its state root
+    ///
+    /// # Errors
+    /// Returns an error if the RPC call fails or the node has no finalized block
+    pub async fn latest_finalized_root(&self) -> Result<(u64, Vec<u8>)> {
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
+        let block = provider
+            .get_block_by_number(alloy::eips::BlockNumberOrTag::Finalized)
+            .await?
+            .context("Failed to get finalized block")?;
+        Ok((block.header.number, block.header.state_root.to_vec()))
+    }
+
+    /// Retrieves the height and state root of the block with the given hash.
+    ///
+    /// # Arguments
+    /// * `block_hash` - The hex-encoded hash of the block
+    ///
+    /// # Returns
+    /// A tuple of the block height and its state root
+    ///
+    /// # Errors
+    /// Returns an error if the RPC call fails or the node does not know the block
+    pub async fn block_root_at_hash(&self, block_hash: &str) -> Result<(u64, Vec<u8>)> {
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
+        let block = provider
+            .get_block_by_hash(B256::from_hex(block_hash)?)
+            .await?
+            .with_context(|| format!("Block {} not found", block_hash))?;
+        Ok((block.header.number, block.header.state_root.to_vec()))
+    }
+
+    /// Retrieves the state root of the block at a height.
+    ///
+    /// # Arguments
+    /// * `height` - The height of the block
+    ///
+    /// # Returns
+    /// The block's state root
+    ///
+    /// # Errors
+    /// Returns an error if the RPC call fails or the node does not know the block
+    pub async fn state_root_at(&self, height: u64) -> Result<Vec<u8>> {
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
+        let block = provider
+            .get_block_by_number(alloy::eips::BlockNumberOrTag::Number(height))
+            .await?
+            .with_context(|| format!("Block {} not found", height))?;
+        Ok(block.header.state_root.to_vec())
+    }
+
+    /// Retrieves an account proof pinned to a block hash rather than a height.
+    ///
+    /// Pinning by hash guarantees the proof is taken from exactly the intended
+    /// block, even if the chain reorganizes around that height. The block must
+    /// be canonical.
+    ///
+    /// # Arguments
+    /// * `key` - The storage key to prove
+    /// * `address` - The account address to prove
+    /// * `block_hash` - The hex-encoded hash of the block to prove at
+    ///
+    /// # Returns
+    /// The serialized proof, in the same format as `get_proof`, with the hash,
+    /// height and state root of the block it was taken at
+    ///
+    /// # Errors
+    /// Returns [`StatePruned`] if the node no longer holds the block's state, or
+    /// another error if the node does not know the block, the block is not
+    /// canonical or the RPC call fails
+    pub async fn get_proof_at_hash(
+        &self,
+        key: &str,
+        address: &str,
+        block_hash: &str,
+    ) -> Result<BlockProof> {
+        let address_object = Address::from_hex(address)?;
+        let hash = B256::from_hex(block_hash)?;
+        let (height, state_root) = self.block_root_at_hash(block_hash).await?;
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
+        let proof: EIP1186AccountProofResponse = provider
+            .get_proof(address_object, vec![FixedBytes::from_hex(key)?])
+            .block_id(BlockId::Hash(RpcBlockHash::from_hash(hash, Some(true))))
+            .await
+            .map_err(|e| proof_error(e, height))?;
+        Ok(BlockProof {
+            block_hash: hash.to_vec(),
+            height,
+            state_root,
+            proof: serde_json::to_vec(&proof)?,
+        })
+    }
+
     /// Retrieves both account and storage proofs for a given account and storage key.
     ///
     /// # Arguments
     /// * `key` - The storage key to prove
     /// * `address` - The account address to prove
-    /// * `height` - The block height to prove at
-    /// * `block_state_root` - The state root of the block
-    /// * `storage_hash` - The storage root hash of the account
+    /// * `height` - The block height to prove at, or `None` for the latest finalized block
+    ///
+    /// # Note
+    /// When `height` is `None` the finalized height is read once, through
+    /// [`Self::latest_finalized_root`], and returned with the proof, so the proof
+    /// can be verified against that block's root even if the finalized block
+    /// advances in between.
     ///
     /// # Returns
-    /// A tuple containing the account proof and storage proof
+    /// A tuple of the height the proof was taken at and the combined proof
     ///
-    /// # Panics
-    /// Panics if the proofs cannot be retrieved or deserialized
+    /// # Errors
+    /// Returns an error if the finalized height or the proofs cannot be
+    /// retrieved, or the response cannot be decoded into a combined proof
     pub async fn get_account_and_storage_proof(
         &self,
         key: &str,
         address: &str,
-        height: u64,
-    ) -> Result<EthereumCombinedProof> {
+        height: Option<u64>,
+    ) -> Result<(u64, EthereumCombinedProof)> {
+        let height = match height {
+            Some(height) => height,
+            None => self.latest_finalized_root().await?.0,
+        };
         let proof = self.get_proof(key, address, height).await?;
-        let proof_deserialized: EIP1186AccountProofResponse = serde_json::from_slice(&proof)?;
-        let account_proof: Vec<Vec<u8>> = proof_deserialized
-            .account_proof
-            .iter()
-            .map(|b| b.to_vec())
-            .collect();
-        let leaf_node_decoded: Vec<crate::timewave_rlp::Bytes> = rlp_decode_bytes(
-            proof_deserialized
+        Ok((height, combined_proof_from_response(&proof, address)?))
+    }
+
+    /// Retrieves an account proof and storage proofs for every slot of an access list.
+    ///
+    /// All slots are fetched with a single `eth_getProof` call, so every proof is
+    /// taken from the same block. Slots that were never written read as zero and
+    /// are returned as exclusion proofs with an empty value.
+    ///
+    /// # Arguments
+    /// * `address` - The account address to prove
+    /// * `slots` - The storage slots to prove
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// The account proof bundled with one storage proof per slot, in the order of `slots`
+    ///
+    /// # Errors
+    /// Returns an error if the RPC call fails or a proof cannot be decoded
+    pub async fn get_access_list_proof(
+        &self,
+        address: &str,
+        slots: &[B256],
+        height: u64,
+    ) -> Result<EthereumAccessListProof> {
+        let address_object = Address::from_hex(address)?;
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
+        let proof: EIP1186AccountProofResponse = provider
+            .get_proof(address_object, slots.to_vec())
+            .block_id(height.into())
+            .await
+            .map_err(|e| proof_error(e, height))?;
+        let account_proof: Vec<Vec<u8>> = proof.account_proof.iter().map(|b| b.to_vec()).collect();
+        let leaf_node_decoded = rlp_decode_bytes(
+            proof
                 .account_proof
                 .last()
-                .context("Failed to get last account proof")?,
+                .context("Failed to get leaf from account proof")?,
         )?;
         let stored_account = leaf_node_decoded
             .last()
-            .context("Failed to extract leaf from account proof")?
+            .context("Failed to extract account root from leaf")?
             .to_vec();
         let account_proof =
-            EthereumAccountProof::new(account_proof.clone(), hex::decode(address)?, stored_account);
-        let raw_storage_proofs: Vec<(Vec<Vec<u8>>, JsonStorageKey)> = proof_deserialized
+            EthereumAccountProof::new(account_proof, address_object.to_vec(), stored_account);
+
+        let storage_proofs = proof
             .storage_proof
             .iter()
-            .cloned()
-            .map(|p| (p.proof.into_iter().map(|b| b.to_vec()).collect(), p.key))
-            .collect();
-        let first_storage_proof = raw_storage_proofs
-            .first()
-            .context("Failed to get first storage proof");
-        let first_storage_proof = first_storage_proof?;
-        let leaf_node_decoded: Vec<crate::timewave_rlp::Bytes> = rlp_decode_bytes(
-            &first_storage_proof
-                .0
-                .to_vec()
-                .last()
-                .context("Failed to get last storage proof")?
-                .to_vec(),
-        )?;
-        let stored_value = leaf_node_decoded
-            .last()
-            .context("Failed to extract leaf from storage proof");
-        let storage_proof = EthereumStorageProof::new(
-            first_storage_proof.0.clone(),
-            first_storage_proof.1.as_b256().to_vec(),
-            stored_value
-                .context("Failed to extract leaf from storage proof")?
-                .to_vec(),
-        );
-        Ok(EthereumCombinedProof::new(account_proof, storage_proof))
+            .map(storage_proof_from_entry)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(EthereumAccessListProof::new(account_proof, storage_proofs))
     }
 
     /// Retrieves an account proof for a given address.
@@ -141,8 +486,9 @@ impl EvmMerkleRpcClient {
     /// # Returns
     /// An account proof for the given address
     ///
-    /// # Panics
-    /// Panics if the proof cannot be retrieved or deserialized
+    /// # Errors
+    /// Returns an error if the address is not valid hex, the RPC call fails, or
+    /// the leaf of the account proof cannot be decoded
     pub async fn get_account_proof(
         &self,
         address: &str,
@@ -153,7 +499,8 @@ impl EvmMerkleRpcClient {
         let proof: EIP1186AccountProofResponse = provider
             .get_proof(address_object, vec![])
             .block_id(height.into())
-            .await?;
+            .await
+            .map_err(|e| proof_error(e, height))?;
 
         let account_proof: Vec<Vec<u8>> = proof.account_proof.iter().map(|b| b.to_vec()).collect();
 
@@ -188,8 +535,8 @@ impl EvmMerkleRpcClient {
     /// # Returns
     /// A storage proof for the given account and storage key
     ///
-    /// # Panics
-    /// Panics if the proof cannot be retrieved or deserialized
+    /// # Errors
+    /// Returns an error if the RPC call fails or the response holds no storage proof
     pub async fn get_storage_proof(
         &self,
         key: &str,
@@ -197,92 +544,351 @@ impl EvmMerkleRpcClient {
         height: u64,
     ) -> Result<EthereumStorageProof> {
         let proof = self.get_proof(key, address, height).await?;
-        let proof_deserialized: EIP1186AccountProofResponse = serde_json::from_slice(&proof)?;
-        let raw_storage_proofs: Vec<(Vec<Vec<u8>>, JsonStorageKey)> = proof_deserialized
-            .storage_proof
-            .iter()
-            .cloned()
-            .map(|p| (p.proof.into_iter().map(|b| b.to_vec()).collect(), p.key))
-            .collect();
-        let first_storage_proof = raw_storage_proofs
-            .first()
-            .context("Failed to get first storage proof")?;
-        let leaf_node_decoded: Vec<crate::timewave_rlp::Bytes> =
-            match crate::timewave_rlp::decode_exact(
-                first_storage_proof
-                    .0
-                    .to_vec()
-                    .last()
-                    .context("Failed to extract leaf from storage proof")?,
-            ) {
-                Ok(decoded) => decoded,
-                Err(e) => {
-                    return Err(anyhow::anyhow!("Failed to decode RLP bytes: {:?}", e));
-                }
-            };
-        let stored_value = leaf_node_decoded
-            .last()
-            .context("Failed to extract value from leaf")?
-            .to_vec();
-        Ok(EthereumStorageProof::new(
-            first_storage_proof.0.clone(),
-            first_storage_proof.1.as_b256().to_vec(),
-            stored_value,
-        ))
+        storage_proof_from_response(&proof)
+    }
+
+    /// Retrieves an account and storage proof and verifies it against the state
+    /// root of its block.
+    ///
+    /// # Arguments
+    /// * `key` - The storage key to prove
+    /// * `address` - The account address to prove
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// The combined proof, verified against the state root at `height`
+    ///
+    /// # Errors
+    /// Returns an error if the proof or block cannot be retrieved, or the proof
+    /// does not verify against the block's state root
+    pub async fn get_verified_storage_proof(
+        &self,
+        key: &str,
+        address: &str,
+        height: u64,
+    ) -> Result<EthereumCombinedProof> {
+        let ((_, proof), state_root) = futures::try_join!(
+            self.get_account_and_storage_proof(key, address, Some(height)),
+            self.state_root_at(height)
+        )?;
+        if !proof.verify(&state_root)? {
+            anyhow::bail!(
+                "Proof at height {} does not verify against its state root",
+                height
+            );
+        }
+        Ok(proof)
+    }
+
+    /// Retrieves a storage slot's proof at each of several heights, e.g. to
+    /// average a proven balance over a window of blocks.
+    ///
+    /// The heights are fetched concurrently, and every proof is verified against
+    /// the state root of its own block. Use
+    /// [`FallbackEvmMerkleRpcClient::get_storage_proofs_over_heights`](crate::ethereum_rpc::fallback::FallbackEvmMerkleRpcClient::get_storage_proofs_over_heights)
+    /// to retry failed heights with backoff.
+    ///
+    /// # Arguments
+    /// * `key` - The storage key to prove
+    /// * `address` - The account address to prove
+    /// * `heights` - The block heights to prove at
+    ///
+    /// # Returns
+    /// Each height paired with its proof, in the order of `heights`
+    ///
+    /// # Errors
+    /// Returns the first error of any height, see [`Self::get_verified_storage_proof`]
+    pub async fn get_storage_proofs_over_heights(
+        &self,
+        key: &str,
+        address: &str,
+        heights: &[u64],
+    ) -> Result<Vec<(u64, EthereumCombinedProof)>> {
+        try_join_all(heights.iter().map(|&height| async move {
+            let proof = self
+                .get_verified_storage_proof(key, address, height)
+                .await?;
+            Ok((height, proof))
+        }))
+        .await
+    }
+
+    /// Retrieves a storage proof for the EIP-1967 implementation slot of a proxy.
+    ///
+    /// # Arguments
+    /// * `proxy_address` - The address of the proxy contract
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// A storage proof for [`IMPLEMENTATION_SLOT`], whose value decodes with
+    /// [`decode_address_slot`]
+    ///
+    /// # Errors
+    /// Returns an error if the proof cannot be retrieved or the slot does not hold
+    /// an address
+    pub async fn get_proxy_implementation_proof(
+        &self,
+        proxy_address: &str,
+        height: u64,
+    ) -> Result<EthereumStorageProof> {
+        let proof = self
+            .get_storage_proof(&hex::encode(IMPLEMENTATION_SLOT), proxy_address, height)
+            .await?;
+        decode_address_slot(&proof.encoded_value())
+            .with_context(|| format!("{} is not an EIP-1967 proxy", proxy_address))?;
+        Ok(proof)
+    }
+
+    /// Retrieves a proof of an ERC20 token balance.
+    ///
+    /// The balance slot is derived from the token's [`TokenStorageLayout`], looked
+    /// up by address for well-known tokens or given explicitly for any other.
+    /// The storage proof is checked against the token's proven storage root
+    /// before it is returned.
+    ///
+    /// # Arguments
+    /// * `token` - The hex-encoded address of the token contract
+    /// * `holder` - The hex-encoded address whose balance to prove
+    /// * `layout` - The token's layout, or `None` to look it up
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// The token's account proof with a storage proof of the balance, whose value
+    /// decodes with [`TokenStorageLayout::decode_balance`]
+    ///
+    /// # Errors
+    /// Returns an error if no layout is given and the token is not registered, or
+    /// if the proof cannot be retrieved or is inconsistent
+    pub async fn get_token_balance_proof(
+        &self,
+        token: &str,
+        holder: &str,
+        layout: Option<TokenStorageLayout>,
+        height: u64,
+    ) -> Result<EthereumCombinedProof> {
+        let token_address = Address::from_hex(token)?;
+        let layout = match layout {
+            Some(layout) => layout,
+            None => TokenStorageLayout::for_token(&token_address.0 .0).with_context(|| {
+                format!(
+                    "No storage layout registered for token {}, pass its balances slot",
+                    token
+                )
+            })?,
+        };
+        let slot = layout.balance_slot(&Address::from_hex(holder)?.0 .0);
+        let (_, proof) = self
+            .get_account_and_storage_proof(&hex::encode(slot), token, Some(height))
+            .await?;
+        Ok(proof)
+    }
+
+    /// Retrieves a proof of an ERC20 token's total supply.
+    ///
+    /// The supply slot is taken from the token's [`TokenStorageLayout`], looked
+    /// up by address for well-known tokens, or given explicitly for any other.
+    /// The storage proof is checked against the token's proven storage root
+    /// before it is returned.
+    ///
+    /// # Arguments
+    /// * `token` - The hex-encoded address of the token contract
+    /// * `total_supply_slot` - The slot of the total supply, or `None` to look it up
+    /// * `height` - The block height to prove at
+    ///
+    /// # Returns
+    /// The storage proof of the total supply, whose value decodes with
+    /// [`TokenStorageLayout::decode_total_supply`]. A token that never minted
+    /// has an empty value, proven with [`EthereumStorageProof::verify_nonexistence`]
+    ///
+    /// # Errors
+    /// Returns an error if no slot is given and the token is not registered, or
+    /// if the proof cannot be retrieved or is inconsistent
+    pub async fn get_total_supply_proof(
+        &self,
+        token: &str,
+        total_supply_slot: Option<u64>,
+        height: u64,
+    ) -> Result<EthereumStorageProof> {
+        let token_address = Address::from_hex(token)?;
+        let slot = match total_supply_slot {
+            Some(slot) => B256::left_padding_from(&slot.to_be_bytes()),
+            None => B256::from(
+                TokenStorageLayout::for_token(&token_address.0 .0)
+                    .with_context(|| {
+                        format!(
+                            "No storage layout registered for token {}, pass its total supply slot",
+                            token
+                        )
+                    })?
+                    .supply_slot(),
+            ),
+        };
+        let (_, proof) = self
+            .get_account_and_storage_proof(&hex::encode(slot), token, Some(height))
+            .await?;
+        Ok(proof.storage_proof)
+    }
+
+    /// Retrieves a proof of the EIP-4788 ring buffer entry for a timestamp.
+    ///
+    /// # Arguments
+    /// * `timestamp` - The timestamp of the execution block whose beacon root to prove
+    /// * `height` - The block height to prove at, no more than
+    ///   `HISTORY_BUFFER_LENGTH` slots after the block with `timestamp`
+    ///
+    /// # Returns
+    /// The beacon roots contract's account proof with storage proofs for the
+    /// entry's timestamp and root slots, to be checked with `verify_beacon_root`
+    ///
+    /// # Errors
+    /// Returns an error if the proof cannot be retrieved
+    pub async fn get_beacon_root_proof(
+        &self,
+        timestamp: u64,
+        height: u64,
+    ) -> Result<EthereumAccessListProof> {
+        self.get_access_list_proof(
+            &hex::encode(BEACON_ROOTS_ADDRESS),
+            &[
+                B256::from(timestamp_slot(timestamp)),
+                B256::from(root_slot(timestamp)),
+            ],
+            height,
+        )
+        .await
     }
 
     /// Retrieves a receipt proof for a specific transaction in a block.
     ///
     /// # Arguments
-    /// * `block_hash` - The hash of the block containing the receipt
-    /// * `target_index` - The index of the receipt in the block
+    /// * `height` - The height of the block containing the receipt
+    /// * `index` - The index of the receipt in the block
     ///
     /// # Returns
-    /// A Merkle proof for the receipt
+    /// A Merkle proof for the receipt against the block's receipts root
     ///
-    /// # Panics
-    /// Panics if the block or receipts cannot be retrieved, or if the proof cannot be constructed
+    /// # Errors
+    /// Returns an error if the block or receipts cannot be retrieved, the index is
+    /// out of range, or the proof does not verify against the receipts root
     pub async fn get_receipt_proof(&self, height: u64, index: u32) -> Result<EthereumReceiptProof> {
+        let mut proofs = self.get_receipt_proofs(height, &[index]).await?;
+        proofs.pop().context("Failed to build receipt proof")
+    }
+
+    /// Retrieves proofs for several receipts of one block.
+    ///
+    /// The receipts trie is built once, retaining the nodes of every requested
+    /// index, so proving many receipts of a block is linear rather than quadratic
+    /// in the number of receipts. Every proof is verified against the block's
+    /// receipts root before it is returned.
+    ///
+    /// # Arguments
+    /// * `height` - The height of the block containing the receipts
+    /// * `indices` - The indices of the receipts in the block
+    ///
+    /// # Returns
+    /// A Merkle proof for each receipt, in the order of `indices`
+    ///
+    /// # Errors
+    /// Returns an error if the block or receipts cannot be retrieved, an index is
+    /// out of range, or a proof does not verify against the receipts root
+    pub async fn get_receipt_proofs(
+        &self,
+        height: u64,
+        indices: &[u32],
+    ) -> Result<Vec<EthereumReceiptProof>> {
         let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
+        let block_id = alloy::eips::BlockNumberOrTag::Number(height);
         let receipts: Vec<TransactionReceipt> = provider
-            .get_block_receipts(alloy::eips::BlockId::Number(
-                alloy::eips::BlockNumberOrTag::Number(height),
-            ))
+            .get_block_receipts(alloy::eips::BlockId::Number(block_id))
             .await?
             .context("Failed to get block receipts")?;
-        let retainer = ProofRetainer::new(vec![Nibbles::unpack(
-            crate::timewave_rlp::encode_fixed_size(&index),
-        )]);
-        let mut hb: HashBuilder = HashBuilder::default().with_proof_retainer(retainer);
-        for i in 0..receipts.len() {
-            let index = adjust_index_for_rlp(i, receipts.len());
-            let index_buffer = crate::timewave_rlp::encode_fixed_size(&index);
-            hb.add_leaf(
-                Nibbles::unpack(&index_buffer),
-                encode_receipt(&receipts[index])?.as_slice(),
+        let block = provider
+            .get_block_by_number(block_id)
+            .await?
+            .context("Failed to get block")?;
+        if let Some(index) = indices
+            .iter()
+            .find(|index| **index as usize >= receipts.len())
+        {
+            anyhow::bail!(
+                "Receipt index {} out of range for block with {} receipts",
+                index,
+                receipts.len()
             );
         }
-        let receipt_key: Vec<u8> = crate::timewave_rlp::encode(index);
-        hb.root();
-        let proof = hb
-            .take_proof_nodes()
-            .into_nodes_sorted()
-            .into_iter()
-            .map(|n| n.1)
-            .collect::<Vec<_>>()
+        let encoded_receipts = receipts
             .iter()
-            .map(|n| n.to_vec())
-            .collect::<Vec<_>>();
-        let leaf_node_decoded: Vec<crate::timewave_rlp::Bytes> = rlp_decode_bytes(
-            proof
-                .to_vec()
-                .last()
-                .context("Failed to extract leaf from receipt proof")?,
-        )?;
-        let receipt_rlp = leaf_node_decoded
-            .last()
-            .context("Failed to extract value from leaf")?
-            .to_vec();
-        Ok(EthereumReceiptProof::new(proof, receipt_key, receipt_rlp).into())
+            .map(encode_receipt)
+            .collect::<Result<Vec<_>>>()?;
+        let (receipts_root, proofs) = index_trie_proofs(&encoded_receipts, indices);
+        if receipts_root != block.header.receipts_root {
+            anyhow::bail!("Rebuilt receipts root does not match block {}", height);
+        }
+        indices
+            .iter()
+            .zip(proofs)
+            .map(|(index, proof)| {
+                let receipt_proof = EthereumReceiptProof::new(
+                    proof,
+                    crate::timewave_rlp::encode(index),
+                    encoded_receipts[*index as usize].clone(),
+                );
+                if !receipt_proof.verify(receipts_root.as_slice())? {
+                    anyhow::bail!("Receipt proof for index {} does not verify", index);
+                }
+                Ok(receipt_proof)
+            })
+            .collect()
+    }
+
+    /// Retrieves a transaction proof for a specific transaction in a block.
+    ///
+    /// # Arguments
+    /// * `height` - The height of the block containing the transaction
+    /// * `index` - The index of the transaction in the block
+    ///
+    /// # Returns
+    /// A Merkle proof for the EIP-2718 encoded transaction against the block's
+    /// transactions root
+    ///
+    /// # Errors
+    /// Returns an error if the block cannot be retrieved, the index is out of
+    /// range, or the rebuilt trie does not match the block's transactions root
+    pub async fn get_transaction_proof(
+        &self,
+        height: u64,
+        index: u32,
+    ) -> Result<EthereumTransactionProof> {
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&self.rpc_url)?);
+        let block = provider
+            .get_block_by_number(alloy::eips::BlockNumberOrTag::Number(height))
+            .full()
+            .await?
+            .context("Failed to get block")?;
+        let transactions = block
+            .transactions
+            .as_transactions()
+            .context("Failed to get full block transactions")?;
+        if index as usize >= transactions.len() {
+            anyhow::bail!(
+                "Transaction index {} out of range for block with {} transactions",
+                index,
+                transactions.len()
+            );
+        }
+        let encoded_transactions: Vec<Vec<u8>> = transactions
+            .iter()
+            .map(|transaction| transaction.inner.encoded_2718())
+            .collect();
+        let (transactions_root, mut proofs) = index_trie_proofs(&encoded_transactions, &[index]);
+        if transactions_root != block.header.transactions_root {
+            anyhow::bail!("Rebuilt transactions root does not match block {}", height);
+        }
+        Ok(EthereumTransactionProof::new(
+            proofs.pop().context("Failed to build transaction proof")?,
+            crate::timewave_rlp::encode(index),
+            encoded_transactions[index as usize].clone(),
+        ))
     }
 }
@@ -3,5 +3,12 @@
 #[cfg(feature = "no-zkvm")]
 pub mod ethereum_rpc;
 pub mod merkle_lib;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub mod timewave_rlp;
 pub mod timewave_trie;
+
+pub use merkle_lib::{
+    header::{verify_event_proof, verify_header_chain},
+    verify_raw_trie_proof,
+};
@@ -0,0 +1,183 @@
+//! Size bounds for untrusted proofs.
+//!
+//! Verification walks a proof only until the key is resolved, but a proof padded
+//! with thousands of bogus nodes has already been deserialized, and paid for, by
+//! then. [`BoundedProof::validate_bounds`] is a cheap check to run on proofs from
+//! untrusted sources before verifying them.
+//!
+//! The bounds apply to each trie a proof walks separately, so a combined proof
+//! may hold up to `max_nodes` account nodes and `max_nodes` storage nodes.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+
+use super::types::{
+    EthereumAccessListProof, EthereumAccountProof, EthereumCombinedProof, EthereumProofType,
+    EthereumReceiptProof, EthereumSimpleProof, EthereumStorageProof, EthereumTransactionProof,
+};
+
+/// A recommended node limit for state and storage proofs.
+///
+/// Keys in these tries are keccak256 hashes, so paths stay shallow: proofs for
+/// mainnet state rarely exceed 10 nodes, and this leaves headroom for growth.
+pub const MAX_STATE_PROOF_NODES: usize = 16;
+
+/// The size of the largest possible branch node: 16 child hashes of 33 bytes
+/// each, an empty value and a 3-byte list header.
+///
+/// This bounds every node of a state or storage proof, as their leaves are
+/// smaller. Receipt and transaction leaves hold the whole receipt or
+/// transaction, so proofs from those tries need a limit sized for the largest
+/// expected leaf.
+pub const MAX_TRIE_NODE_BYTES: usize = 532;
+
+/// Proofs whose size can be checked before verification.
+pub trait BoundedProof {
+    /// Rejects proofs with too many nodes or oversized nodes.
+    ///
+    /// # Arguments
+    /// * `max_nodes` - The maximum number of nodes per trie, e.g. [`MAX_STATE_PROOF_NODES`]
+    /// * `max_node_bytes` - The maximum size of a single node, e.g. [`MAX_TRIE_NODE_BYTES`]
+    ///
+    /// # Errors
+    /// Returns an error if a trie's proof exceeds either bound
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()>;
+}
+
+impl BoundedProof for EthereumAccountProof {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        validate_nodes(&self.proof, max_nodes, max_node_bytes)
+    }
+}
+
+impl BoundedProof for EthereumStorageProof {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        validate_nodes(&self.proof, max_nodes, max_node_bytes)
+    }
+}
+
+impl BoundedProof for EthereumReceiptProof {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        validate_nodes(&self.proof, max_nodes, max_node_bytes)
+    }
+}
+
+impl BoundedProof for EthereumTransactionProof {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        validate_nodes(&self.proof, max_nodes, max_node_bytes)
+    }
+}
+
+impl BoundedProof for EthereumCombinedProof {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        self.account_proof
+            .validate_bounds(max_nodes, max_node_bytes)?;
+        self.storage_proof
+            .validate_bounds(max_nodes, max_node_bytes)
+    }
+}
+
+impl BoundedProof for EthereumAccessListProof {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        self.account_proof
+            .validate_bounds(max_nodes, max_node_bytes)?;
+        for storage_proof in &self.storage_proofs {
+            storage_proof.validate_bounds(max_nodes, max_node_bytes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the account and storage nodes separately, after the 2-byte prefix
+/// holding the number of account nodes.
+impl BoundedProof for EthereumSimpleProof {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        let (account_proof, storage_proof) = split_simple_proof(self)?;
+        validate_nodes(account_proof, max_nodes, max_node_bytes)?;
+        validate_nodes(storage_proof, max_nodes, max_node_bytes)
+    }
+}
+
+impl BoundedProof for EthereumProofType {
+    fn validate_bounds(&self, max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+        match self {
+            EthereumProofType::Account(proof) => proof.validate_bounds(max_nodes, max_node_bytes),
+            EthereumProofType::Storage(proof) => proof.validate_bounds(max_nodes, max_node_bytes),
+            EthereumProofType::Combined(proof) => proof.validate_bounds(max_nodes, max_node_bytes),
+            EthereumProofType::Receipt(proof) => proof.validate_bounds(max_nodes, max_node_bytes),
+            EthereumProofType::Simple(proof) => proof.validate_bounds(max_nodes, max_node_bytes),
+            EthereumProofType::Transaction(proof) => {
+                proof.validate_bounds(max_nodes, max_node_bytes)
+            }
+        }
+    }
+}
+
+fn validate_nodes(nodes: &[Vec<u8>], max_nodes: usize, max_node_bytes: usize) -> Result<()> {
+    if nodes.len() > max_nodes {
+        anyhow::bail!(
+            "Proof has {} nodes, more than the limit of {}",
+            nodes.len(),
+            max_nodes
+        );
+    }
+    if let Some((index, node)) = nodes
+        .iter()
+        .enumerate()
+        .find(|(_, node)| node.len() > max_node_bytes)
+    {
+        anyhow::bail!(
+            "Proof node {} is {} bytes, more than the limit of {}",
+            index,
+            node.len(),
+            max_node_bytes
+        );
+    }
+    Ok(())
+}
+
+/// The account and storage nodes of a simple proof.
+pub(crate) type SimpleProofParts<'a> = (&'a [Vec<u8>], &'a [Vec<u8>]);
+
+/// Splits the nodes of a simple proof into its account and storage nodes, after
+/// the 2-byte prefix holding the number of account nodes.
+pub(crate) fn split_simple_proof(proof: &EthereumSimpleProof) -> Result<SimpleProofParts<'_>> {
+    let Some((prefix, nodes)) = proof.proof.split_first() else {
+        anyhow::bail!("Simple proof has no account node count");
+    };
+    let prefix: [u8; 2] = prefix
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Simple proof account node count is not 2 bytes"))?;
+    let account_nodes = u16::from_be_bytes(prefix) as usize;
+    if account_nodes > nodes.len() {
+        anyhow::bail!(
+            "Simple proof claims {} account nodes but holds {} nodes",
+            account_nodes,
+            nodes.len()
+        );
+    }
+    Ok(nodes.split_at(account_nodes))
+}
+
+/// Splits a simple proof's key or value into its account and storage parts, after
+/// the 2-byte prefix holding the length of the account part.
+pub(crate) fn split_length_prefixed<'a>(
+    bytes: &'a [u8],
+    what: &str,
+) -> Result<(&'a [u8], &'a [u8])> {
+    let Some((prefix, rest)) = bytes.split_first_chunk::<2>() else {
+        anyhow::bail!("Simple proof {} has no length prefix", what);
+    };
+    let account_len = u16::from_be_bytes(*prefix) as usize;
+    if account_len > rest.len() {
+        anyhow::bail!(
+            "Simple proof {} claims an account part of {} bytes but holds {} bytes",
+            what,
+            account_len,
+            rest.len()
+        );
+    }
+    Ok(rest.split_at(account_len))
+}
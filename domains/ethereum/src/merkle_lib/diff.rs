@@ -0,0 +1,143 @@
+//! Comparing proofs of the same key at two roots.
+//!
+//! When a value changes between two blocks, every node on the path from the
+//! root to the value changes with it. [`diff_proofs`] reports the old and new
+//! values and the nodes only one of the proofs holds, which shows where the
+//! two tries diverge. This is a debugging aid and is not used during
+//! verification.
+extern crate alloc;
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use anyhow::Result;
+
+use super::{
+    digest_keccak,
+    types::{EthereumMerkleProof, EthereumProofType},
+};
+
+/// The difference between two proofs of the same key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProofDiff {
+    /// The value proven by the old proof
+    pub old_value: Vec<u8>,
+    /// The value proven by the new proof
+    pub new_value: Vec<u8>,
+    /// The hashes of the nodes only the old proof holds
+    pub removed_nodes: Vec<[u8; 32]>,
+    /// The hashes of the nodes only the new proof holds
+    pub added_nodes: Vec<[u8; 32]>,
+}
+
+impl ProofDiff {
+    /// Whether the proven value differs between the two proofs.
+    ///
+    /// # Returns
+    /// `true` if the old and new values differ
+    pub fn value_changed(&self) -> bool {
+        self.old_value != self.new_value
+    }
+}
+
+/// Compares two proofs of the same key, typically taken at consecutive blocks.
+///
+/// Nodes are compared by their keccak256 hash, so nodes present in both proofs,
+/// e.g. untouched subtries referenced from a changed branch, are not reported.
+/// Combined and simple proofs compare the nodes of both their tries. Combined
+/// proofs compare the storage value, simple proofs their flattened values.
+///
+/// # Arguments
+/// * `old` - The proof at the earlier root
+/// * `new` - The proof at the later root
+///
+/// # Returns
+/// The old and new values and the hashes of the differing nodes, each in the
+/// order they appear in their proof
+///
+/// # Errors
+/// Returns an error if the proofs are of different types or prove different keys,
+/// as the nodes of two unrelated paths have no meaningful difference
+pub fn diff_proofs(old: &EthereumMerkleProof, new: &EthereumMerkleProof) -> Result<ProofDiff> {
+    if core::mem::discriminant(old) != core::mem::discriminant(new) {
+        anyhow::bail!("Cannot diff proofs of different types");
+    }
+    let (old_key, old_value, old_nodes) = proof_parts(old);
+    let (new_key, new_value, new_nodes) = proof_parts(new);
+    if old_key != new_key {
+        anyhow::bail!(
+            "Cannot diff proofs of different keys 0x{} and 0x{}",
+            hex::encode(&old_key),
+            hex::encode(&new_key)
+        );
+    }
+    let old_hashes = node_hashes(&old_nodes);
+    let new_hashes = node_hashes(&new_nodes);
+    let old_set: BTreeSet<&[u8; 32]> = old_hashes.iter().collect();
+    let new_set: BTreeSet<&[u8; 32]> = new_hashes.iter().collect();
+    Ok(ProofDiff {
+        old_value,
+        new_value,
+        removed_nodes: old_hashes
+            .iter()
+            .filter(|hash| !new_set.contains(hash))
+            .copied()
+            .collect(),
+        added_nodes: new_hashes
+            .iter()
+            .filter(|hash| !old_set.contains(hash))
+            .copied()
+            .collect(),
+    })
+}
+
+/// Splits a proof into its key, proven value and proof nodes.
+fn proof_parts(proof: &EthereumProofType) -> (Vec<u8>, Vec<u8>, Vec<&[u8]>) {
+    fn nodes(proof: &[Vec<u8>]) -> Vec<&[u8]> {
+        proof.iter().map(Vec::as_slice).collect()
+    }
+    match proof {
+        EthereumProofType::Account(proof) => (
+            proof.address.clone(),
+            proof.value.clone(),
+            nodes(&proof.proof),
+        ),
+        EthereumProofType::Storage(proof) => (
+            proof.key.clone(),
+            proof.encoded_value(),
+            nodes(&proof.proof),
+        ),
+        EthereumProofType::Receipt(proof) => {
+            (proof.key.clone(), proof.value.clone(), nodes(&proof.proof))
+        }
+        EthereumProofType::Transaction(proof) => {
+            (proof.key.clone(), proof.value.clone(), nodes(&proof.proof))
+        }
+        EthereumProofType::Combined(proof) => (
+            [
+                proof.account_proof.address.as_slice(),
+                &proof.storage_proof.key,
+            ]
+            .concat(),
+            proof.storage_proof.encoded_value(),
+            nodes(&proof.account_proof.proof)
+                .into_iter()
+                .chain(nodes(&proof.storage_proof.proof))
+                .collect(),
+        ),
+        // the first entry of a simple proof is the length prefix, not a node
+        EthereumProofType::Simple(proof) => (
+            proof.key.clone(),
+            proof.value.clone(),
+            nodes(proof.proof.get(1..).unwrap_or_default()),
+        ),
+    }
+}
+
+/// Hashes nodes, keeping the first occurrence of each hash.
+fn node_hashes(nodes: &[&[u8]]) -> Vec<[u8; 32]> {
+    let mut seen = BTreeSet::new();
+    nodes
+        .iter()
+        .map(|node| digest_keccak(node))
+        .filter(|hash| seen.insert(*hash))
+        .collect()
+}
@@ -0,0 +1,49 @@
+//! EIP-1967 proxy storage slots.
+//!
+//! Upgradeable proxies following EIP-1967 keep the addresses they delegate to in
+//! fixed storage slots, each derived as `keccak256(label) - 1` so that they cannot
+//! collide with the slots of the implementation's own variables.
+use anyhow::Result;
+
+use crate::timewave_rlp;
+
+/// The slot holding the implementation address,
+/// `keccak256("eip1967.proxy.implementation") - 1`.
+pub const IMPLEMENTATION_SLOT: [u8; 32] =
+    hex_literal::hex!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// The slot holding the admin address, `keccak256("eip1967.proxy.admin") - 1`.
+pub const ADMIN_SLOT: [u8; 32] =
+    hex_literal::hex!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
+
+/// The slot holding the beacon address, `keccak256("eip1967.proxy.beacon") - 1`.
+pub const BEACON_SLOT: [u8; 32] =
+    hex_literal::hex!("a3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+/// Decodes the address stored in an EIP-1967 slot from a proven storage value.
+///
+/// Storage values are RLP-encoded with leading zero bytes stripped, so the decoded
+/// value is left-padded back to 20 bytes.
+///
+/// # Arguments
+/// * `value` - The RLP-encoded value of the slot, as held by a storage proof
+///
+/// # Returns
+/// The 20-byte address stored in the slot
+///
+/// # Errors
+/// Returns an error if the value is not RLP, is empty (no address was ever set)
+/// or is longer than 20 bytes
+pub fn decode_address_slot(mut value: &[u8]) -> Result<[u8; 20]> {
+    let bytes = timewave_rlp::Header::decode_bytes(&mut value, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode slot value: {:?}", e))?;
+    if bytes.is_empty() {
+        anyhow::bail!("Slot holds no address");
+    }
+    if bytes.len() > 20 {
+        anyhow::bail!("Slot value of {} bytes is not an address", bytes.len());
+    }
+    let mut address = [0u8; 20];
+    address[20 - bytes.len()..].copy_from_slice(bytes);
+    Ok(address)
+}
@@ -0,0 +1,115 @@
+//! EIP-4788 beacon block roots.
+//!
+//! Since Dencun, the beacon roots system contract stores the parent beacon block
+//! root of every execution block in a ring buffer of [`HISTORY_BUFFER_LENGTH`]
+//! entries. The block timestamp is kept at slot `timestamp % HISTORY_BUFFER_LENGTH`
+//! and the root at that slot plus `HISTORY_BUFFER_LENGTH`. Proving both slots
+//! against an execution state root therefore proves a consensus-layer root.
+use anyhow::{Context, Result};
+use common::merkle::types::MerkleVerifiable;
+
+use crate::{
+    merkle_lib::types::{pad_storage_slot, EthereumAccessListProof},
+    timewave_rlp,
+};
+
+/// The address of the beacon roots system contract.
+pub const BEACON_ROOTS_ADDRESS: [u8; 20] =
+    hex_literal::hex!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02");
+
+/// The number of entries in the contract's ring buffer.
+pub const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+/// Returns the slot holding the timestamp of the ring buffer entry for `timestamp`.
+///
+/// # Arguments
+/// * `timestamp` - The timestamp of the execution block
+///
+/// # Returns
+/// The 32-byte storage slot
+pub fn timestamp_slot(timestamp: u64) -> [u8; 32] {
+    slot_at(timestamp % HISTORY_BUFFER_LENGTH)
+}
+
+/// Returns the slot holding the beacon root of the ring buffer entry for `timestamp`.
+///
+/// # Arguments
+/// * `timestamp` - The timestamp of the execution block
+///
+/// # Returns
+/// The 32-byte storage slot
+pub fn root_slot(timestamp: u64) -> [u8; 32] {
+    slot_at(timestamp % HISTORY_BUFFER_LENGTH + HISTORY_BUFFER_LENGTH)
+}
+
+/// Verifies a proof of the ring buffer entry for `timestamp` and returns its beacon root.
+///
+/// The proof must hold the beacon roots contract's account proof and storage
+/// proofs for [`timestamp_slot`] and [`root_slot`], in that order. The entry is
+/// only accepted if it still holds `timestamp`, since older entries are
+/// overwritten once the buffer wraps around.
+///
+/// # Arguments
+/// * `proof` - The account and storage proofs of the beacon roots contract
+/// * `timestamp` - The timestamp of the execution block whose beacon root is proven
+/// * `state_root` - The state root to verify against
+///
+/// # Returns
+/// The proven parent beacon block root
+///
+/// # Errors
+/// Returns an error if the proof is not for the beacon roots contract and the
+/// entry's slots, does not verify against `state_root`, either slot is empty, or
+/// the entry holds another timestamp
+pub fn verify_beacon_root(
+    proof: &EthereumAccessListProof,
+    timestamp: u64,
+    state_root: &[u8],
+) -> Result<[u8; 32]> {
+    if proof.account_proof.address != BEACON_ROOTS_ADDRESS {
+        anyhow::bail!("Account proof is not for the beacon roots contract");
+    }
+    let [timestamp_proof, root_proof] = proof.storage_proofs.as_slice() else {
+        anyhow::bail!(
+            "Expected storage proofs for 2 slots, got {}",
+            proof.storage_proofs.len()
+        );
+    };
+    if pad_storage_slot(&timestamp_proof.key)? != timestamp_slot(timestamp)
+        || pad_storage_slot(&root_proof.key)? != root_slot(timestamp)
+    {
+        anyhow::bail!(
+            "Storage proofs are not for the entry of timestamp {}",
+            timestamp
+        );
+    }
+    if !proof.verify(state_root)? {
+        anyhow::bail!("Beacon roots proof does not verify against the state root");
+    }
+
+    let stored_timestamp = decode_word(&timestamp_proof.encoded_value())?;
+    if stored_timestamp != slot_at(timestamp) {
+        anyhow::bail!(
+            "Ring buffer entry does not hold timestamp {}, it was overwritten or never set",
+            timestamp
+        );
+    }
+    decode_word(&root_proof.encoded_value()).context("Failed to decode beacon root")
+}
+
+fn slot_at(index: u64) -> [u8; 32] {
+    let mut slot = [0u8; 32];
+    slot[24..].copy_from_slice(&index.to_be_bytes());
+    slot
+}
+
+/// Decodes an RLP-encoded storage value, left-padded back to 32 bytes. An empty
+/// value, as carried by an exclusion proof, is rejected: the entry was never set.
+fn decode_word(mut value: &[u8]) -> Result<[u8; 32]> {
+    if value.is_empty() {
+        anyhow::bail!("Ring buffer slot is empty");
+    }
+    let bytes = timewave_rlp::Header::decode_bytes(&mut value, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode slot value: {:?}", e))?;
+    pad_storage_slot(bytes)
+}
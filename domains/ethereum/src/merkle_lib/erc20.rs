@@ -0,0 +1,160 @@
+//! Storage layouts of ERC20 token balances and total supply.
+//!
+//! A holder's balance lives at `keccak256(abi.encode(holder, balances_slot))`,
+//! where `balances_slot` is the slot of the token's balances mapping, and the
+//! total supply lives directly in `total_supply_slot`. These slots depend on how
+//! the token contract declares its state variables, so they have to be known
+//! per token: [`TokenStorageLayout::for_token`] holds them for a few well-known
+//! tokens, and any other token needs its layout spelled out, e.g. from
+//! `solc --storage-layout`.
+use anyhow::Result;
+use num_bigint::BigUint;
+
+use super::{digest_keccak, slot::SlotDecoder};
+
+/// Where and how a token stores its balances and total supply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenStorageLayout {
+    /// The slot of the `mapping(address => uint256)` holding balances
+    pub balances_slot: u64,
+    /// The slot of the `uint256` holding the total supply
+    pub total_supply_slot: u64,
+    /// The width of the balance within its slot, less than 256 if the token
+    /// packs flags into the high bits
+    pub balance_bits: usize,
+}
+
+/// Mainnet USDT (Tether USD).
+pub const MAINNET_USDT: [u8; 20] = hex_literal::hex!("dAC17F958D2ee523a2206206994597C13D831ec7");
+/// Mainnet USDC (Circle FiatToken).
+pub const MAINNET_USDC: [u8; 20] = hex_literal::hex!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+/// Mainnet DAI.
+pub const MAINNET_DAI: [u8; 20] = hex_literal::hex!("6B175474E89094C44Da98b954EedeAC495271d0F");
+/// Sepolia USDC (Circle FiatToken).
+pub const SEPOLIA_USDC: [u8; 20] = hex_literal::hex!("1c7D4B196Cb0C7B01d743Fbc6116a902379C7238");
+/// Sepolia USDT (Aave v3 faucet token).
+pub const SEPOLIA_USDT: [u8; 20] = hex_literal::hex!("aA8E23Fb1079EA71e0a56F48a2aA51851D8433D0");
+/// Sepolia DAI (Aave v3 faucet token).
+pub const SEPOLIA_DAI: [u8; 20] = hex_literal::hex!("FF34B3d4Aee8ddCd6F9AFFFB6Fe49bD371b8a357");
+
+/// FiatToken v2.2 keeps balances in `balanceAndBlacklistStates` at slot 9, with
+/// the blacklist flag in the highest bit, and `totalSupply_` at slot 11.
+const FIAT_TOKEN_LAYOUT: TokenStorageLayout = TokenStorageLayout {
+    balances_slot: 9,
+    total_supply_slot: 11,
+    balance_bits: 255,
+};
+
+/// Aave's `TestnetERC20` inherits its state from an OpenZeppelin-style `ERC20`,
+/// declaring `_balances` at slot 0 and `_totalSupply` at slot 2.
+const AAVE_TESTNET_LAYOUT: TokenStorageLayout = TokenStorageLayout::plain(0, 2);
+
+/// The tokens whose layout is known, by contract address.
+const KNOWN_LAYOUTS: [([u8; 20], TokenStorageLayout); 6] = [
+    (MAINNET_USDT, TokenStorageLayout::plain(2, 1)),
+    (MAINNET_USDC, FIAT_TOKEN_LAYOUT),
+    (MAINNET_DAI, TokenStorageLayout::plain(2, 1)),
+    (SEPOLIA_USDT, AAVE_TESTNET_LAYOUT),
+    (SEPOLIA_USDC, FIAT_TOKEN_LAYOUT),
+    (SEPOLIA_DAI, AAVE_TESTNET_LAYOUT),
+];
+
+impl TokenStorageLayout {
+    /// Creates the layout of a token storing full 256-bit balances.
+    ///
+    /// # Arguments
+    /// * `balances_slot` - The slot of the balances mapping
+    /// * `total_supply_slot` - The slot of the total supply
+    ///
+    /// # Returns
+    /// A new `TokenStorageLayout` instance
+    pub const fn plain(balances_slot: u64, total_supply_slot: u64) -> Self {
+        Self {
+            balances_slot,
+            total_supply_slot,
+            balance_bits: 256,
+        }
+    }
+
+    /// Looks up the layout of a well-known token.
+    ///
+    /// # Arguments
+    /// * `token` - The address of the token contract
+    ///
+    /// # Returns
+    /// The token's layout, or `None` if the token is not registered
+    pub fn for_token(token: &[u8; 20]) -> Option<Self> {
+        KNOWN_LAYOUTS
+            .iter()
+            .find(|(address, _)| address == token)
+            .map(|(_, layout)| *layout)
+    }
+
+    /// Returns the storage slot holding a holder's balance.
+    ///
+    /// # Arguments
+    /// * `holder` - The address whose balance to locate
+    ///
+    /// # Returns
+    /// The unhashed 32-byte storage slot
+    pub fn balance_slot(&self, holder: &[u8; 20]) -> [u8; 32] {
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(holder);
+        preimage[56..].copy_from_slice(&self.balances_slot.to_be_bytes());
+        digest_keccak(&preimage)
+    }
+
+    /// Returns the storage slot holding the total supply.
+    ///
+    /// # Returns
+    /// The unhashed 32-byte storage slot
+    pub fn supply_slot(&self) -> [u8; 32] {
+        let mut slot = [0u8; 32];
+        slot[24..].copy_from_slice(&self.total_supply_slot.to_be_bytes());
+        slot
+    }
+
+    /// Decodes the total supply from the proven value of its slot.
+    ///
+    /// # Arguments
+    /// * `value` - The RLP-encoded slot value, as held by a storage proof
+    ///
+    /// # Returns
+    /// The total supply
+    ///
+    /// # Errors
+    /// Returns an error if the value is not a slot value
+    pub fn decode_total_supply(&self, value: &[u8]) -> Result<BigUint> {
+        if value.is_empty() {
+            return Ok(BigUint::from(0u8));
+        }
+        SlotDecoder::from_rlp(value)?.read_uint(0, 256)
+    }
+
+    /// Decodes a balance from the proven value of its slot.
+    ///
+    /// # Arguments
+    /// * `value` - The RLP-encoded slot value, as held by a storage proof
+    ///
+    /// # Returns
+    /// The balance, without any flags packed above it, or zero for the empty
+    /// value of an exclusion proof
+    ///
+    /// # Errors
+    /// Returns an error if the value is not a slot value or the layout's balance
+    /// width is not between 1 and 256 bits
+    pub fn decode_balance(&self, value: &[u8]) -> Result<BigUint> {
+        if self.balance_bits == 0 || self.balance_bits > 256 {
+            anyhow::bail!("Balance width of {} bits is invalid", self.balance_bits);
+        }
+        // an exclusion proof: the holder's slot was never written
+        if value.is_empty() {
+            return Ok(BigUint::from(0u8));
+        }
+        let balance = SlotDecoder::from_rlp(value)?.read_uint(0, 256)?;
+        if self.balance_bits == 256 {
+            return Ok(balance);
+        }
+        Ok(balance & ((BigUint::from(1u8) << self.balance_bits) - 1u8))
+    }
+}
@@ -0,0 +1,175 @@
+//! Decoding of block headers, verification of header chains and of events
+//! against a verified header.
+//!
+//! A block header is an RLP list whose first 15 fields are common to every fork:
+//! `[parent_hash, ommers_hash, beneficiary, state_root, transactions_root,
+//! receipts_root, logs_bloom, difficulty, number, gas_limit, gas_used, timestamp,
+//! extra_data, mix_hash, nonce]`. Later forks append fields such as the base fee
+//! and the parent beacon block root, which are not needed to chain headers.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+
+use super::{digest_keccak, receipt::ReceiptLog, types::EthereumReceiptProof};
+use crate::timewave_rlp;
+
+/// The fields of a decoded block header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeaderFields {
+    /// The keccak256 hash of the encoded header
+    pub hash: [u8; 32],
+    /// The hash of the parent block
+    pub parent_hash: [u8; 32],
+    /// The root of the state trie after this block
+    pub state_root: [u8; 32],
+    /// The root of the transactions trie
+    pub transactions_root: [u8; 32],
+    /// The root of the receipts trie
+    pub receipts_root: [u8; 32],
+    /// The block number
+    pub number: u64,
+    /// The block timestamp, in seconds
+    pub timestamp: u64,
+}
+
+impl BlockHeaderFields {
+    /// Decodes a block header from its RLP encoding.
+    ///
+    /// # Arguments
+    /// * `header` - The RLP-encoded block header
+    ///
+    /// # Returns
+    /// The decoded header fields
+    ///
+    /// # Errors
+    /// Returns an error if the header is malformed or has fewer than 15 fields
+    pub fn decode(header: &[u8]) -> Result<Self> {
+        let fields = timewave_rlp::decode_list(header, "block header")?;
+        if fields.len() < 15 {
+            anyhow::bail!(
+                "Block header has {} fields, expected at least 15",
+                fields.len()
+            );
+        }
+        Ok(Self {
+            hash: digest_keccak(header),
+            parent_hash: rlp_hash(fields[0], "parent hash")?,
+            state_root: rlp_hash(fields[3], "state root")?,
+            transactions_root: rlp_hash(fields[4], "transactions root")?,
+            receipts_root: rlp_hash(fields[5], "receipts root")?,
+            number: rlp_u64(fields[8], "block number")?,
+            timestamp: rlp_u64(fields[11], "timestamp")?,
+        })
+    }
+}
+
+/// Verifies that a sequence of block headers chains from a trusted parent.
+///
+/// Each header's parent hash must equal the keccak256 hash of the header before
+/// it, and the first header's parent hash must equal `trusted_parent_hash`.
+/// This anchors the state root of the last header to the trusted block.
+///
+/// # Arguments
+/// * `headers` - The RLP-encoded headers, from the oldest to the newest
+/// * `trusted_parent_hash` - The hash of the trusted parent of the first header
+///
+/// # Returns
+/// The decoded fields of the last header
+///
+/// # Errors
+/// Returns an error if no headers are given, a header is malformed, a parent
+/// hash does not match or the block numbers are not consecutive
+pub fn verify_header_chain(
+    headers: &[Vec<u8>],
+    trusted_parent_hash: &[u8],
+) -> Result<BlockHeaderFields> {
+    let trusted_parent_hash: [u8; 32] = trusted_parent_hash.try_into().map_err(|_| {
+        anyhow::anyhow!(
+            "Trusted parent hash is {} bytes, expected 32",
+            trusted_parent_hash.len()
+        )
+    })?;
+    let mut parent: Option<BlockHeaderFields> = None;
+    for (index, header) in headers.iter().enumerate() {
+        let fields = BlockHeaderFields::decode(header)
+            .map_err(|e| anyhow::anyhow!("Failed to decode header {}: {}", index, e))?;
+        let expected_parent_hash = parent.as_ref().map_or(trusted_parent_hash, |p| p.hash);
+        if fields.parent_hash != expected_parent_hash {
+            anyhow::bail!(
+                "Header {} does not chain from its parent: parent hash 0x{}, expected 0x{}",
+                index,
+                hex::encode(fields.parent_hash),
+                hex::encode(expected_parent_hash)
+            );
+        }
+        if let Some(parent) = &parent {
+            if parent.number.checked_add(1) != Some(fields.number) {
+                anyhow::bail!(
+                    "Header {} has block number {}, expected {}",
+                    index,
+                    fields.number,
+                    parent.number.wrapping_add(1)
+                );
+            }
+        }
+        parent = Some(fields);
+    }
+    parent.ok_or_else(|| anyhow::anyhow!("No headers to verify"))
+}
+
+/// Verifies that a transaction's receipt belongs to a block and returns its events.
+///
+/// The header must hash to `block_hash`, and the receipt proof must verify
+/// against the header's receipts root under the key of `expected_index`. This
+/// ties the returned events to the block, e.g. one whose hash was anchored with
+/// [`verify_header_chain`].
+///
+/// # Arguments
+/// * `block_hash` - The trusted hash of the block
+/// * `header_rlp` - The RLP-encoded header of the block
+/// * `receipt_proof` - The proof of the receipt in the block's receipts trie
+/// * `expected_index` - The index of the transaction in the block
+///
+/// # Returns
+/// The events emitted by the transaction
+///
+/// # Errors
+/// Returns an error if the header does not hash to `block_hash` or is malformed,
+/// or if the receipt proof is for another index, does not verify against the
+/// receipts root or holds a malformed receipt
+pub fn verify_event_proof(
+    block_hash: &[u8],
+    header_rlp: &[u8],
+    receipt_proof: EthereumReceiptProof,
+    expected_index: u32,
+) -> Result<Vec<ReceiptLog>> {
+    let header = BlockHeaderFields::decode(header_rlp)?;
+    if header.hash.as_slice() != block_hash {
+        anyhow::bail!(
+            "Header hashes to 0x{}, expected block hash 0x{}",
+            hex::encode(header.hash),
+            hex::encode(block_hash)
+        );
+    }
+    let receipt = receipt_proof.verify_for_index(&header.receipts_root, expected_index)?;
+    Ok(receipt.logs)
+}
+
+fn rlp_hash(mut item: &[u8], what: &str) -> Result<[u8; 32]> {
+    timewave_rlp::Header::decode_bytes(&mut item, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode {}: {:?}", what, e))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Header {} is not 32 bytes", what))
+}
+
+fn rlp_u64(mut item: &[u8], what: &str) -> Result<u64> {
+    let bytes = timewave_rlp::Header::decode_bytes(&mut item, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode {}: {:?}", what, e))?;
+    if bytes.len() > 8 {
+        anyhow::bail!("Header {} does not fit in 64 bits", what);
+    }
+    let mut padded = [0u8; 8];
+    padded[8 - bytes.len()..].copy_from_slice(bytes);
+    Ok(u64::from_be_bytes(padded))
+}
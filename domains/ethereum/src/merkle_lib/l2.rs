@@ -0,0 +1,101 @@
+//! OP-stack output roots.
+//!
+//! OP-stack chains post an output root to L1 for each proposed L2 block, rather
+//! than the L2 state root itself. Version 0 of the output root is
+//! `keccak256(version || state_root || withdrawal_storage_root || block_hash)`,
+//! where the withdrawal storage root is the storage root of the
+//! `L2ToL1MessagePasser` contract. Verifying the preimage against an output root
+//! read from L1 yields an L2 state root that storage proofs can be verified
+//! against.
+extern crate alloc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{digest_keccak, root::EthStateRoot};
+
+/// The output root version this crate can verify.
+pub const OUTPUT_ROOT_VERSION_V0: [u8; 32] = [0u8; 32];
+
+/// The preimage of an OP-stack output root.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct L2OutputRoot {
+    /// The output root version, [`OUTPUT_ROOT_VERSION_V0`]
+    pub version: [u8; 32],
+    /// The state root of the L2 block
+    pub state_root: [u8; 32],
+    /// The storage root of the `L2ToL1MessagePasser` contract in the L2 block
+    pub withdrawal_storage_root: [u8; 32],
+    /// The hash of the L2 block
+    pub block_hash: [u8; 32],
+}
+
+impl L2OutputRoot {
+    /// Creates the preimage of a version 0 output root.
+    ///
+    /// # Arguments
+    /// * `state_root` - The state root of the L2 block
+    /// * `withdrawal_storage_root` - The storage root of the `L2ToL1MessagePasser` contract
+    /// * `block_hash` - The hash of the L2 block
+    ///
+    /// # Returns
+    /// A new `L2OutputRoot` instance
+    pub fn new_v0(
+        state_root: [u8; 32],
+        withdrawal_storage_root: [u8; 32],
+        block_hash: [u8; 32],
+    ) -> Self {
+        Self {
+            version: OUTPUT_ROOT_VERSION_V0,
+            state_root,
+            withdrawal_storage_root,
+            block_hash,
+        }
+    }
+
+    /// Computes the output root committed to by this preimage.
+    ///
+    /// # Returns
+    /// The keccak256 hash of the version and the three roots
+    pub fn output_root(&self) -> [u8; 32] {
+        digest_keccak(
+            &[
+                self.version,
+                self.state_root,
+                self.withdrawal_storage_root,
+                self.block_hash,
+            ]
+            .concat(),
+        )
+    }
+
+    /// Verifies the preimage against an output root posted to L1 and returns the
+    /// L2 state root.
+    ///
+    /// # Arguments
+    /// * `output_root` - The trusted output root, e.g. as read from the L1 output oracle
+    ///
+    /// # Returns
+    /// The state root of the L2 block
+    ///
+    /// # Errors
+    /// Returns an error if the version is not [`OUTPUT_ROOT_VERSION_V0`] or the
+    /// preimage does not hash to `output_root`
+    pub fn verify(&self, output_root: &[u8]) -> Result<EthStateRoot> {
+        if self.version != OUTPUT_ROOT_VERSION_V0 {
+            anyhow::bail!(
+                "Unsupported output root version 0x{}",
+                hex::encode(self.version)
+            );
+        }
+        let computed = self.output_root();
+        if computed.as_slice() != output_root {
+            anyhow::bail!(
+                "Output root mismatch: preimage hashes to 0x{}, expected 0x{}",
+                hex::encode(computed),
+                hex::encode(output_root)
+            );
+        }
+        Ok(EthStateRoot(self.state_root))
+    }
+}
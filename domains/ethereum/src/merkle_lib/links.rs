@@ -0,0 +1,152 @@
+//! A pre-pass over the node links of untrusted proofs.
+//!
+//! Verification walks a proof from the root and only notices a corrupt node once
+//! it reaches it. [`LinkedProof::verify_node_hashes`] instead checks up front that
+//! every node but the root is referenced by another node of the proof, by its
+//! keccak256 hash or, for nodes under 32 bytes, in place. Orphaned or corrupted
+//! nodes are rejected before any trie walk, which makes this a cheap filter for
+//! proofs from untrusted sources. It does not replace verification: the nodes
+//! may still link up without proving the claimed key and value.
+//!
+//! As with [`BoundedProof`](super::bounds::BoundedProof), each trie of a proof is
+//! checked separately.
+extern crate alloc;
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use anyhow::Result;
+
+use super::{
+    bounds::split_simple_proof,
+    types::{
+        EthereumAccessListProof, EthereumAccountProof, EthereumCombinedProof, EthereumProofType,
+        EthereumReceiptProof, EthereumSimpleProof, EthereumStorageProof, EthereumTransactionProof,
+    },
+};
+use crate::{
+    timewave_rlp::{self, PayloadView},
+    timewave_trie::{
+        constants::{EXTENSION_EVEN_FLAG, EXTENSION_ODD_FLAG},
+        types::RlpNode,
+    },
+};
+
+/// Proofs whose nodes can be checked to link up before verification.
+pub trait LinkedProof {
+    /// Checks that every node but the root is referenced by another node.
+    ///
+    /// # Errors
+    /// Returns an error if a node cannot be decoded or is not referenced by any
+    /// other node of its trie
+    fn verify_node_hashes(&self) -> Result<()>;
+}
+
+impl LinkedProof for EthereumAccountProof {
+    fn verify_node_hashes(&self) -> Result<()> {
+        verify_links(&self.proof)
+    }
+}
+
+impl LinkedProof for EthereumStorageProof {
+    fn verify_node_hashes(&self) -> Result<()> {
+        verify_links(&self.proof)
+    }
+}
+
+impl LinkedProof for EthereumReceiptProof {
+    fn verify_node_hashes(&self) -> Result<()> {
+        verify_links(&self.proof)
+    }
+}
+
+impl LinkedProof for EthereumTransactionProof {
+    fn verify_node_hashes(&self) -> Result<()> {
+        verify_links(&self.proof)
+    }
+}
+
+impl LinkedProof for EthereumCombinedProof {
+    fn verify_node_hashes(&self) -> Result<()> {
+        self.account_proof.verify_node_hashes()?;
+        self.storage_proof.verify_node_hashes()
+    }
+}
+
+impl LinkedProof for EthereumAccessListProof {
+    fn verify_node_hashes(&self) -> Result<()> {
+        self.account_proof.verify_node_hashes()?;
+        for storage_proof in &self.storage_proofs {
+            storage_proof.verify_node_hashes()?;
+        }
+        Ok(())
+    }
+}
+
+impl LinkedProof for EthereumSimpleProof {
+    fn verify_node_hashes(&self) -> Result<()> {
+        let (account_proof, storage_proof) = split_simple_proof(self)?;
+        verify_links(account_proof)?;
+        verify_links(storage_proof)
+    }
+}
+
+impl LinkedProof for EthereumProofType {
+    fn verify_node_hashes(&self) -> Result<()> {
+        match self {
+            EthereumProofType::Account(proof) => proof.verify_node_hashes(),
+            EthereumProofType::Storage(proof) => proof.verify_node_hashes(),
+            EthereumProofType::Combined(proof) => proof.verify_node_hashes(),
+            EthereumProofType::Receipt(proof) => proof.verify_node_hashes(),
+            EthereumProofType::Simple(proof) => proof.verify_node_hashes(),
+            EthereumProofType::Transaction(proof) => proof.verify_node_hashes(),
+        }
+    }
+}
+
+fn verify_links(nodes: &[Vec<u8>]) -> Result<()> {
+    let mut references = BTreeSet::new();
+    for node in nodes {
+        collect_references(node, &mut references)?;
+    }
+    for (index, node) in nodes.iter().enumerate().skip(1) {
+        if !references.contains(<RlpNode>::from_rlp(node).as_slice()) {
+            anyhow::bail!(
+                "Proof node {} is not referenced by any other node of the proof",
+                index
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Collects the child references of a node, including those of children held in place.
+fn collect_references<'a>(mut node: &'a [u8], references: &mut BTreeSet<&'a [u8]>) -> Result<()> {
+    let PayloadView::List(items) = timewave_rlp::Header::decode_raw(&mut node)
+        .map_err(|e| anyhow::anyhow!("Failed to decode proof node: {:?}", e))?
+    else {
+        anyhow::bail!("Proof node is not an RLP list");
+    };
+    let children = match items.len() {
+        17 => &items[..16],
+        2 if is_extension(items[0])? => &items[1..],
+        2 => &[][..],
+        _ => anyhow::bail!("Proof node has {} items", items.len()),
+    };
+    for child in children {
+        // children under 32 bytes are nodes held in place rather than hashes
+        if child
+            .first()
+            .is_some_and(|&b| b >= timewave_rlp::EMPTY_LIST_CODE)
+        {
+            collect_references(child, references)?;
+        }
+        references.insert(*child);
+    }
+    Ok(())
+}
+
+fn is_extension(mut path: &[u8]) -> Result<bool> {
+    let path = timewave_rlp::Header::decode_bytes(&mut path, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode node path: {:?}", e))?;
+    let flag = path.first().map(|b| b & 0xf0);
+    Ok(flag == Some(EXTENSION_EVEN_FLAG) || flag == Some(EXTENSION_ODD_FLAG))
+}
@@ -2,12 +2,31 @@
 extern crate alloc;
 use alloc::vec::Vec;
 
-use crate::timewave_rlp;
+use crate::{
+    timewave_rlp,
+    timewave_trie::verify::{verify_proof, ProofVerificationError},
+};
 use anyhow::Result;
 
+#[cfg(feature = "rayon")]
+pub mod batch;
+pub mod bounds;
+pub mod diff;
+pub mod eip1967;
+pub mod eip4788;
+pub mod erc20;
+pub mod header;
+pub mod l2;
+pub mod links;
+pub mod multiproof;
+pub mod receipt;
+pub mod root;
+pub mod slot;
 mod tests;
+pub mod transport;
 pub mod types;
 
+use types::KeyEncoding;
 pub use types::RlpDecodable;
 
 // Keccak-256 hash function implementation.
@@ -54,3 +73,48 @@ pub fn rlp_decode_bytes(bytes: &[u8]) -> Result<Vec<timewave_rlp::Bytes>> {
         .map_err(|e| anyhow::anyhow!("Failed to decode RLP bytes: {:?}", e))?;
     Ok(decoded)
 }
+
+/// Verifies a key and value against the root of an arbitrary Merkle-Patricia trie.
+///
+/// The proof-type-specific `verify` methods fix how their key maps to a trie
+/// path. This is the underlying primitive, for tries with other key layouts,
+/// e.g. tries keyed by unhashed strings.
+///
+/// # Arguments
+/// * `root` - The 32-byte root of the trie
+/// * `key` - The key to verify
+/// * `value` - The expected value, or `None` to prove that the key is absent
+/// * `nodes` - The RLP-encoded proof nodes, from the root towards the key
+/// * `hash_key` - Whether the trie path is the keccak256 hash of `key` rather than `key` itself
+///
+/// # Returns
+/// `true` if the proof verifies, `false` if it leads to another root or value
+///
+/// # Errors
+/// Returns an error if the root is not 32 bytes or the proof is malformed, which
+/// includes an exclusion proof that stops at a hashed node the key's path still
+/// leads through, since the key may be stored below it
+pub fn verify_raw_trie_proof(
+    root: &[u8],
+    key: &[u8],
+    value: Option<&[u8]>,
+    nodes: &[Vec<u8>],
+    hash_key: bool,
+) -> Result<bool> {
+    let encoding = if hash_key {
+        KeyEncoding::Keccak256
+    } else {
+        KeyEncoding::Raw
+    };
+    let root: &[u8; 32] = root
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Trie root is {} bytes, expected 32", root.len()))?;
+    match verify_proof(root, encoding.path(key), value.map(<[u8]>::to_vec), nodes) {
+        Ok(()) => Ok(true),
+        Err(
+            ProofVerificationError::RootMismatch { .. }
+            | ProofVerificationError::ValueMismatch { .. },
+        ) => Ok(false),
+        Err(e) => anyhow::bail!("Malformed trie proof: {:?}", e),
+    }
+}
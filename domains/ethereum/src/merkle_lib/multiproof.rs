@@ -0,0 +1,151 @@
+//! Compact multiproofs over a storage trie.
+//!
+//! Proofs of several slots of one contract repeat the nodes their paths share,
+//! at least the root and usually the upper branches. [`CompactStorageMultiProof`]
+//! holds every distinct node once and describes each slot's proof as a list of
+//! indices into that node set, which for adjacent or numerous slots is a fraction
+//! of the size of the separate proofs.
+extern crate alloc;
+use alloc::{collections::BTreeMap, format, vec::Vec};
+
+use anyhow::{Context, Result};
+use nybbles::Nibbles;
+use serde::{Deserialize, Serialize};
+
+use common::merkle::versioned::{deserialize_version, PROOF_FORMAT_VERSION};
+
+use super::{
+    digest_keccak,
+    types::{pad_storage_slot, EthereumStorageProof},
+};
+use crate::timewave_trie::verify::{verify_proof, ProofVerificationError};
+
+/// Proofs of several slots of one storage trie, sharing their common nodes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CompactStorageMultiProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
+    /// The distinct RLP-encoded proof nodes of all slots
+    pub nodes: Vec<Vec<u8>>,
+    /// The proven storage slots, unhashed
+    pub keys: Vec<[u8; 32]>,
+    /// For each slot in `keys`, the indices into `nodes` of its proof, from the
+    /// root towards the slot
+    pub paths: Vec<Vec<u32>>,
+}
+
+common::impl_proof_eq!(CompactStorageMultiProof, nodes, keys, paths);
+
+impl CompactStorageMultiProof {
+    /// Merges storage proofs of one storage trie, storing shared nodes once.
+    ///
+    /// # Arguments
+    /// * `proofs` - Storage proofs of distinct slots, all taken against the same
+    ///   storage root
+    ///
+    /// # Returns
+    /// The multiproof of every slot of `proofs`
+    ///
+    /// # Errors
+    /// Returns an error if a slot is longer than 32 bytes or proven twice
+    pub fn from_storage_proofs(proofs: &[EthereumStorageProof]) -> Result<Self> {
+        let mut multiproof = Self {
+            version: PROOF_FORMAT_VERSION,
+            nodes: Vec::new(),
+            keys: Vec::with_capacity(proofs.len()),
+            paths: Vec::with_capacity(proofs.len()),
+        };
+        let mut node_indices: BTreeMap<&[u8], u32> = BTreeMap::new();
+        for proof in proofs {
+            let key = pad_storage_slot(&proof.key)?;
+            if multiproof.keys.contains(&key) {
+                anyhow::bail!("Storage slot 0x{} is proven twice", hex::encode(key));
+            }
+            let path = proof
+                .proof
+                .iter()
+                .map(|node| {
+                    *node_indices.entry(node).or_insert_with(|| {
+                        multiproof.nodes.push(node.clone());
+                        (multiproof.nodes.len() - 1) as u32
+                    })
+                })
+                .collect();
+            multiproof.keys.push(key);
+            multiproof.paths.push(path);
+        }
+        Ok(multiproof)
+    }
+
+    /// Verifies slots and their values against a storage root.
+    ///
+    /// Each slot is verified along its path through the shared node set. An empty
+    /// value proves that the slot was never written, as with
+    /// [`EthereumStorageProof::verify_nonexistence`]: its path must end at an empty
+    /// branch slot or at a leaf or extension that diverges from the slot, not at a
+    /// node that is only referenced by its hash.
+    ///
+    /// # Arguments
+    /// * `storage_root` - The storage root of the account holding the slots
+    /// * `keys_values` - The unhashed slots and their RLP-encoded values
+    ///
+    /// # Returns
+    /// `true` if every slot holds its value under `storage_root`, `false` if any
+    /// proof leads to another root or value
+    ///
+    /// # Errors
+    /// Returns an error if the root is not 32 bytes, no slots are given, a slot is
+    /// not part of the multiproof, or a path is malformed or ends short of its slot
+    pub fn verify_all(
+        &self,
+        storage_root: &[u8],
+        keys_values: &[([u8; 32], Vec<u8>)],
+    ) -> Result<bool> {
+        let root: &[u8; 32] = storage_root.try_into().map_err(|_| {
+            anyhow::anyhow!("Storage root is {} bytes, expected 32", storage_root.len())
+        })?;
+        if keys_values.is_empty() {
+            anyhow::bail!("No storage slots to verify");
+        }
+        if self.keys.len() != self.paths.len() {
+            anyhow::bail!(
+                "Multiproof holds {} slots but {} paths",
+                self.keys.len(),
+                self.paths.len()
+            );
+        }
+        for (key, value) in keys_values {
+            let position = self
+                .keys
+                .iter()
+                .position(|proven| proven == key)
+                .with_context(|| {
+                    format!(
+                        "Storage slot 0x{} is not in the multiproof",
+                        hex::encode(key)
+                    )
+                })?;
+            let nodes = self.paths[position]
+                .iter()
+                .map(|&index| {
+                    self.nodes
+                        .get(index as usize)
+                        .map(Vec::as_slice)
+                        .with_context(|| format!("Multiproof has no node {}", index))
+                })
+                .collect::<Result<Vec<&[u8]>>>()?;
+            let expected = (!value.is_empty()).then(|| value.clone());
+            match verify_proof(root, Nibbles::unpack(digest_keccak(key)), expected, nodes) {
+                Ok(()) => {}
+                Err(
+                    ProofVerificationError::RootMismatch { .. }
+                    | ProofVerificationError::ValueMismatch { .. },
+                ) => return Ok(false),
+                Err(e) => anyhow::bail!("Malformed storage proof: {:?}", e),
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,270 @@
+//! Decoding of transaction receipts.
+//!
+//! The receipts trie stores each receipt as its consensus encoding: an RLP list
+//! `[status, cumulative_gas_used, logs_bloom, logs]` for legacy transactions, and
+//! the same list prefixed with the transaction type byte for EIP-2718 typed
+//! transactions.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use anyhow::{Context, Result};
+use common::merkle::types::{HasRoot, MerkleVerifiable};
+
+use super::types::EthereumReceiptProof;
+use crate::{
+    timewave_rlp,
+    timewave_trie::{builder::TrieBuilder, verify::collect_proven_entries},
+};
+
+/// The type of the transaction a receipt belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReceiptType {
+    /// A legacy transaction, whose receipt has no type prefix
+    Legacy,
+    /// An EIP-2930 access list transaction, type `0x01`
+    Eip2930,
+    /// An EIP-1559 dynamic fee transaction, type `0x02`
+    Eip1559,
+    /// An EIP-4844 blob transaction, type `0x03`
+    Eip4844,
+    /// An EIP-7702 set code transaction, type `0x04`
+    Eip7702,
+}
+
+impl ReceiptType {
+    /// Splits an encoded receipt into its type and its RLP-encoded fields.
+    ///
+    /// # Arguments
+    /// * `value` - The encoded receipt
+    ///
+    /// # Returns
+    /// The receipt type and the receipt without its type prefix
+    ///
+    /// # Errors
+    /// Returns an error if the receipt is empty or has an unknown type byte
+    pub fn split_envelope(value: &[u8]) -> Result<(Self, &[u8])> {
+        let first = *value.first().context("Empty receipt")?;
+        if first >= timewave_rlp::EMPTY_LIST_CODE {
+            return Ok((Self::Legacy, value));
+        }
+        let receipt_type = match first {
+            0x01 => Self::Eip2930,
+            0x02 => Self::Eip1559,
+            0x03 => Self::Eip4844,
+            0x04 => Self::Eip7702,
+            _ => anyhow::bail!("Unsupported transaction type 0x{:02x}", first),
+        };
+        Ok((receipt_type, &value[1..]))
+    }
+
+    /// Returns the EIP-2718 type byte, 0 for legacy transactions.
+    pub fn type_byte(&self) -> u8 {
+        match self {
+            Self::Legacy => 0x00,
+            Self::Eip2930 => 0x01,
+            Self::Eip1559 => 0x02,
+            Self::Eip4844 => 0x03,
+            Self::Eip7702 => 0x04,
+        }
+    }
+}
+
+/// An event emitted by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptLog {
+    /// The address of the contract that emitted the event
+    pub address: [u8; 20],
+    /// The indexed topics, starting with the event signature for non-anonymous events
+    pub topics: Vec<[u8; 32]>,
+    /// The ABI-encoded non-indexed event data
+    pub data: Vec<u8>,
+}
+
+/// The fields of a decoded transaction receipt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptFields {
+    /// The type of the transaction
+    pub tx_type: ReceiptType,
+    /// Whether the transaction succeeded
+    pub status: bool,
+    /// The gas used in the block up to and including this transaction
+    pub cumulative_gas_used: u64,
+    /// The bloom filter over the receipt's logs
+    pub logs_bloom: Vec<u8>,
+    /// The events emitted by the transaction
+    pub logs: Vec<ReceiptLog>,
+}
+
+impl ReceiptFields {
+    /// Decodes a receipt from its consensus encoding, as held by a receipt proof.
+    ///
+    /// # Arguments
+    /// * `value` - The encoded receipt
+    ///
+    /// # Returns
+    /// The decoded receipt fields
+    ///
+    /// # Errors
+    /// Returns an error if the receipt is malformed, has an unknown transaction
+    /// type or predates Byzantium, when receipts held a state root instead of a status
+    pub fn decode(value: &[u8]) -> Result<Self> {
+        let (tx_type, payload) = ReceiptType::split_envelope(value)?;
+        let [status, cumulative_gas_used, logs_bloom, logs] =
+            timewave_rlp::decode_list(payload, "receipt")?[..]
+        else {
+            anyhow::bail!("Receipt does not have 4 fields");
+        };
+
+        let status = decode_status(status)?;
+        let cumulative_gas_used = rlp_bytes(cumulative_gas_used)?;
+        if cumulative_gas_used.len() > 8 {
+            anyhow::bail!("Cumulative gas used does not fit in 64 bits");
+        }
+        let mut padded = [0u8; 8];
+        padded[8 - cumulative_gas_used.len()..].copy_from_slice(cumulative_gas_used);
+        let logs_bloom = rlp_bytes(logs_bloom)?;
+        if logs_bloom.len() != 256 {
+            anyhow::bail!("Logs bloom is {} bytes, expected 256", logs_bloom.len());
+        }
+        let logs = timewave_rlp::decode_list(logs, "logs")?
+            .into_iter()
+            .map(decode_log)
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            tx_type,
+            status,
+            cumulative_gas_used: u64::from_be_bytes(padded),
+            logs_bloom: logs_bloom.to_vec(),
+            logs,
+        })
+    }
+}
+
+/// Decodes the status of a receipt without decoding its logs.
+///
+/// # Arguments
+/// * `value` - The encoded receipt
+///
+/// # Returns
+/// Whether the transaction succeeded
+///
+/// # Errors
+/// Returns an error if the receipt is malformed, has an unknown transaction
+/// type or predates Byzantium
+pub fn receipt_status(value: &[u8]) -> Result<bool> {
+    let (_, payload) = ReceiptType::split_envelope(value)?;
+    let fields = timewave_rlp::decode_list(payload, "receipt")?;
+    decode_status(fields.first().context("Receipt has no status")?)
+}
+
+/// The receipt proofs do not cover every receipt of their trie.
+///
+/// [`recompute_receipts_root`] can only rebuild the root from the receipts the
+/// proofs reach. It returns this error, wrapped in an [`anyhow::Error`], when a
+/// subtrie is referenced that no proof includes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialCoverage {
+    /// The number of receipts the proofs reach
+    pub proven_receipts: usize,
+}
+
+impl core::fmt::Display for PartialCoverage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Receipt proofs cover only {} receipts of their trie",
+            self.proven_receipts
+        )
+    }
+}
+
+impl core::error::Error for PartialCoverage {}
+
+/// Rebuilds the receipts root from receipt proofs that together cover the
+/// whole receipts trie.
+///
+/// Every proof is verified against the root its first node commits to, and all
+/// must commit to the same root. The receipts reachable through the union of
+/// their nodes are then inserted into a fresh trie, whose root is returned. If
+/// the proofs leave out a subtrie, the rebuilt root differs from the committed
+/// one and [`PartialCoverage`] is returned, so a bundle of event proofs can be
+/// checked for internal consistency before it is used.
+///
+/// # Arguments
+/// * `proofs` - Receipt proofs of one block, covering all of its receipts
+///
+/// # Returns
+/// The recomputed receipts root
+///
+/// # Errors
+/// Returns an error if no proof is given, the proofs commit to different roots
+/// or do not verify, or [`PartialCoverage`] if they do not cover the trie
+pub fn recompute_receipts_root(proofs: &[EthereumReceiptProof]) -> Result<[u8; 32]> {
+    let first = proofs.first().context("No receipt proofs given")?;
+    let root: [u8; 32] = first
+        .root()?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Receipts root is not 32 bytes"))?;
+    for (index, proof) in proofs.iter().enumerate() {
+        if proof.root()? != root {
+            anyhow::bail!("Receipt proof at index {} commits to another root", index);
+        }
+        if !proof.verify(&root)? {
+            anyhow::bail!("Receipt proof at index {} does not verify", index);
+        }
+    }
+
+    let entries = collect_proven_entries(&root, proofs.iter().flat_map(|proof| &proof.proof))
+        .map_err(|e| anyhow::anyhow!("Failed to walk the receipts trie: {:?}", e))?;
+    let mut trie = TrieBuilder::new();
+    for (path, receipt) in &entries {
+        trie.insert(&path.pack(), receipt.clone())?;
+    }
+    let recomputed = trie.root();
+    if recomputed != root {
+        return Err(PartialCoverage {
+            proven_receipts: entries.len(),
+        }
+        .into());
+    }
+    Ok(recomputed)
+}
+
+fn decode_status(status: &[u8]) -> Result<bool> {
+    match rlp_bytes(status)? {
+        [] => Ok(false),
+        [1] => Ok(true),
+        bytes if bytes.len() == 32 => {
+            anyhow::bail!("Pre-Byzantium receipts hold a state root instead of a status")
+        }
+        _ => anyhow::bail!("Invalid receipt status"),
+    }
+}
+
+fn decode_log(log: &[u8]) -> Result<ReceiptLog> {
+    let [address, topics, data] = timewave_rlp::decode_list(log, "log")?[..] else {
+        anyhow::bail!("Log does not have 3 fields");
+    };
+    let address = rlp_bytes(address)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Log address is not 20 bytes"))?;
+    let topics = timewave_rlp::decode_list(topics, "topics")?
+        .into_iter()
+        .map(|topic| {
+            rlp_bytes(topic)?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Log topic is not 32 bytes"))
+        })
+        .collect::<Result<_>>()?;
+    Ok(ReceiptLog {
+        address,
+        topics,
+        data: rlp_bytes(data)?.to_vec(),
+    })
+}
+
+fn rlp_bytes(mut item: &[u8]) -> Result<&[u8]> {
+    timewave_rlp::Header::decode_bytes(&mut item, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode receipt field: {:?}", e))
+}
@@ -0,0 +1,72 @@
+//! A typed state root for Ethereum proof verification.
+//!
+//! `MerkleVerifiable::verify` accepts any byte slice as a root. Verifying through
+//! [`EthStateRoot`] instead makes it a type error to pass a root of another
+//! domain, such as a Cosmos app hash, or to verify a storage or receipt proof
+//! against a state root.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+use common::merkle::types::MerkleVerifiable;
+use serde::{Deserialize, Serialize};
+
+use super::types::{
+    EthereumAccessListProof, EthereumAccountProof, EthereumCombinedProof, EthereumSimpleProof,
+};
+
+/// The state root of an Ethereum block.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EthStateRoot(pub [u8; 32]);
+
+impl EthStateRoot {
+    /// Returns the state root as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for EthStateRoot {
+    fn from(root: [u8; 32]) -> Self {
+        Self(root)
+    }
+}
+
+impl TryFrom<&[u8]> for EthStateRoot {
+    type Error = anyhow::Error;
+
+    fn try_from(root: &[u8]) -> Result<Self> {
+        let root: [u8; 32] = root
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("State root must be 32 bytes, got {}", root.len()))?;
+        Ok(Self(root))
+    }
+}
+
+impl From<EthStateRoot> for Vec<u8> {
+    fn from(root: EthStateRoot) -> Self {
+        root.0.to_vec()
+    }
+}
+
+/// Proofs that are verified against a block's state root.
+///
+/// Storage and receipt proofs verify against other roots (an account's storage
+/// root and a block's receipts root) and deliberately do not implement this trait.
+pub trait StateRootVerifiable: MerkleVerifiable {
+    /// Verifies the proof against a state root.
+    ///
+    /// # Arguments
+    /// * `root` - The state root to verify against
+    ///
+    /// # Returns
+    /// A boolean indicating whether the proof is valid for the given state root
+    fn verify_state_root(&self, root: &EthStateRoot) -> Result<bool> {
+        self.verify(root.as_bytes())
+    }
+}
+
+impl StateRootVerifiable for EthereumAccountProof {}
+impl StateRootVerifiable for EthereumCombinedProof {}
+impl StateRootVerifiable for EthereumAccessListProof {}
+impl StateRootVerifiable for EthereumSimpleProof {}
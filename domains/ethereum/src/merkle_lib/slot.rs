@@ -0,0 +1,120 @@
+//! Decoding of packed Solidity storage slots.
+//!
+//! Solidity packs consecutive state variables smaller than 32 bytes into a single
+//! slot, placing the first variable in the lowest-order bytes. Offsets passed to
+//! [`SlotDecoder`] are therefore counted in bits from the right (least significant)
+//! end of the slot, as reported by `solc --storage-layout` (`offset * 8`).
+use anyhow::Result;
+use num_bigint::BigUint;
+
+use crate::timewave_rlp;
+
+/// Reads packed fields out of a proven 32-byte storage slot value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotDecoder {
+    slot: [u8; 32],
+}
+
+impl SlotDecoder {
+    /// Creates a decoder over a raw 32-byte slot value.
+    ///
+    /// # Arguments
+    /// * `slot` - The big-endian slot value
+    ///
+    /// # Returns
+    /// A new `SlotDecoder` instance
+    pub fn new(slot: [u8; 32]) -> Self {
+        Self { slot }
+    }
+
+    /// Creates a decoder from the RLP-encoded value held by a storage proof.
+    ///
+    /// # Arguments
+    /// * `value` - The RLP-encoded slot value, with leading zero bytes stripped
+    ///
+    /// # Returns
+    /// A decoder over the value left-padded to 32 bytes
+    ///
+    /// # Errors
+    /// Returns an error if the value is not RLP or is longer than 32 bytes
+    pub fn from_rlp(mut value: &[u8]) -> Result<Self> {
+        let bytes = timewave_rlp::Header::decode_bytes(&mut value, false)
+            .map_err(|e| anyhow::anyhow!("Failed to decode slot value: {:?}", e))?;
+        if bytes.len() > 32 {
+            anyhow::bail!("Slot value of {} bytes exceeds 32 bytes", bytes.len());
+        }
+        let mut slot = [0u8; 32];
+        slot[32 - bytes.len()..].copy_from_slice(bytes);
+        Ok(Self::new(slot))
+    }
+
+    /// Reads an unsigned integer field.
+    ///
+    /// # Arguments
+    /// * `offset_bits` - The offset of the field from the right end of the slot
+    /// * `width_bits` - The width of the field, e.g. 128 for a `uint128`
+    ///
+    /// # Returns
+    /// The value of the field
+    ///
+    /// # Errors
+    /// Returns an error if the field is not byte-aligned, is empty or extends past the slot
+    pub fn read_uint(&self, offset_bits: usize, width_bits: usize) -> Result<BigUint> {
+        Ok(BigUint::from_bytes_be(self.field(offset_bits, width_bits)?))
+    }
+
+    /// Reads an `address` field.
+    ///
+    /// # Arguments
+    /// * `offset_bits` - The offset of the field from the right end of the slot
+    ///
+    /// # Returns
+    /// The 20-byte address
+    ///
+    /// # Errors
+    /// Returns an error if the field is not byte-aligned or extends past the slot
+    pub fn read_address(&self, offset_bits: usize) -> Result<[u8; 20]> {
+        let mut address = [0u8; 20];
+        address.copy_from_slice(self.field(offset_bits, 160)?);
+        Ok(address)
+    }
+
+    /// Reads a `bool` field.
+    ///
+    /// # Arguments
+    /// * `offset_bits` - The offset of the field from the right end of the slot
+    ///
+    /// # Returns
+    /// The value of the field
+    ///
+    /// # Errors
+    /// Returns an error if the field is not byte-aligned, extends past the slot or
+    /// holds a byte other than 0 or 1
+    pub fn read_bool(&self, offset_bits: usize) -> Result<bool> {
+        match self.field(offset_bits, 8)? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [byte] => anyhow::bail!("Byte 0x{:02x} is not a bool", byte),
+            _ => unreachable!("an 8-bit field is one byte"),
+        }
+    }
+
+    /// Returns the big-endian bytes of a field.
+    fn field(&self, offset_bits: usize, width_bits: usize) -> Result<&[u8]> {
+        if offset_bits % 8 != 0 || width_bits % 8 != 0 {
+            anyhow::bail!("Solidity packs fields at byte boundaries");
+        }
+        let fits = offset_bits
+            .checked_add(width_bits)
+            .is_some_and(|end_bits| end_bits <= 256);
+        if width_bits == 0 || !fits {
+            anyhow::bail!(
+                "Field of {} bits at offset {} does not fit in a slot",
+                width_bits,
+                offset_bits
+            );
+        }
+        let end = 32 - offset_bits / 8;
+        Ok(&self.slot[end - width_bits / 8..end])
+    }
+}
@@ -41,6 +41,90 @@ mod tests {
 
         let state_root = block.header.state_root.as_slice();
         //let account_decoded = EthereumAccount::rlp_decode(&account_proof.value).unwrap();
+        assert!(account_proof.verify(state_root).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_account_proof_at_block_hash() {
+        let sepolia_height = read_sepolia_height().await.unwrap();
+        let url = read_sepolia_url();
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&url).unwrap());
+        let block = provider
+            .get_block_by_number(alloy::eips::BlockNumberOrTag::Number(sepolia_height))
+            .await
+            .unwrap()
+            .unwrap();
+        let block_hash = block.header.hash.to_string();
+
+        let merkle_prover = EvmMerkleRpcClient {
+            rpc_url: url.to_string(),
+        };
+        let (height, state_root) = merkle_prover.block_root_at_hash(&block_hash).await.unwrap();
+        assert_eq!(height, sepolia_height);
+        assert_eq!(state_root, block.header.state_root.to_vec());
+
+        let block_proof = merkle_prover
+            .get_proof_at_hash(
+                "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "0x89efEA02Dc92FD8CcCEefabb59a1104759dF352d",
+                &block_hash,
+            )
+            .await
+            .unwrap();
+        assert_eq!(block_proof.block_hash, block.header.hash.to_vec());
+        assert_eq!(block_proof.height, sepolia_height);
+        assert_eq!(block_proof.state_root, state_root);
+        let proof: alloy::rpc::types::EIP1186AccountProofResponse =
+            serde_json::from_slice(&block_proof.proof).unwrap();
+        let account_proof = crate::merkle_lib::types::EthereumAccountProof::new(
+            proof.account_proof.iter().map(|b| b.to_vec()).collect(),
+            proof.address.to_vec(),
+            crate::merkle_lib::rlp_decode_bytes(proof.account_proof.last().unwrap())
+                .unwrap()
+                .last()
+                .unwrap()
+                .to_vec(),
+        );
         assert!(account_proof.verify(&state_root).unwrap());
+
+        let unknown_hash = format!("0x{}", "11".repeat(32));
+        assert!(merkle_prover
+            .get_proof_at_hash(
+                "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "0x89efEA02Dc92FD8CcCEefabb59a1104759dF352d",
+                &unknown_hash,
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unused_account_nonexistence_proof() {
+        let sepolia_height = read_sepolia_height().await.unwrap();
+        let url = read_sepolia_url();
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&url).unwrap());
+        // an address derived from a fixed phrase, never used on-chain
+        let address = alloy_primitives::Address::from_slice(
+            &crate::merkle_lib::digest_keccak(b"zk-merkle-proofs unused account")[12..],
+        );
+        let proof = provider
+            .get_proof(address, vec![])
+            .block_id(sepolia_height.into())
+            .await
+            .unwrap();
+        let block = provider
+            .get_block_by_number(alloy::eips::BlockNumberOrTag::Number(sepolia_height))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let account_proof = crate::merkle_lib::types::EthereumAccountProof::new(
+            proof.account_proof.iter().map(|b| b.to_vec()).collect(),
+            address.to_vec(),
+            vec![],
+        );
+        assert!(account_proof
+            .verify_nonexistence(block.header.state_root.as_slice())
+            .unwrap());
     }
 }
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    use crate::merkle_lib::{
+        batch::verify_batch_parallel,
+        tests::fixtures::{account_rlp, single_leaf_trie},
+        types::{EthereumAccountProof, EthereumProofType},
+    };
+
+    #[test]
+    fn test_verify_batch_parallel() {
+        let mut proofs = Vec::new();
+        let mut roots = Vec::new();
+        for index in 0..100u8 {
+            let address = [index; 20];
+            let account = account_rlp(index as u64, 1, &[0u8; 32], &[0u8; 32]);
+            let (nodes, root) = single_leaf_trie(&address, &account);
+            proofs.push(EthereumProofType::Account(EthereumAccountProof::new(
+                nodes,
+                address.to_vec(),
+                account,
+            )));
+            roots.push(root.to_vec());
+        }
+        // swap two roots so exactly those proofs fail
+        roots.swap(3, 4);
+
+        let results = verify_batch_parallel(&proofs, &roots).unwrap();
+        assert_eq!(results.len(), 100);
+        for (index, result) in results.iter().enumerate() {
+            match index {
+                3 | 4 => assert!(!matches!(result, Ok(true))),
+                _ => assert!(result.as_ref().unwrap()),
+            }
+        }
+
+        // a missing or extra root rejects the whole batch
+        roots.pop();
+        assert!(verify_batch_parallel(&proofs, &roots).is_err());
+        roots.extend([[0u8; 32].to_vec(), [0u8; 32].to_vec()]);
+        assert!(verify_batch_parallel(&proofs, &roots).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use crate::{
+        merkle_lib::{
+            bounds::{BoundedProof, MAX_STATE_PROOF_NODES, MAX_TRIE_NODE_BYTES},
+            types::{EthereumProofType, EthereumSimpleProof},
+        },
+        mock::combined_proof_for,
+    };
+    use common::merkle::types::MerkleVerifiable;
+
+    const VALUE: [u8; 3] = [0x82, 0x04, 0xd2];
+
+    #[test]
+    fn test_validate_bounds() {
+        let (proof, _) = combined_proof_for(&[0xAA; 20], &[0x01; 32], &VALUE);
+        proof
+            .validate_bounds(MAX_STATE_PROOF_NODES, MAX_TRIE_NODE_BYTES)
+            .unwrap();
+        // each mock trie holds a single node of a few dozen bytes
+        assert!(proof.validate_bounds(0, MAX_TRIE_NODE_BYTES).is_err());
+        assert!(proof.validate_bounds(MAX_STATE_PROOF_NODES, 16).is_err());
+
+        let mut padded = proof.clone();
+        padded
+            .storage_proof
+            .proof
+            .extend(core::iter::repeat_n(alloc::vec![0x80], 100));
+        assert!(padded
+            .validate_bounds(MAX_STATE_PROOF_NODES, MAX_TRIE_NODE_BYTES)
+            .is_err());
+        let wrapped = EthereumProofType::Combined(padded);
+        assert!(wrapped
+            .validate_bounds(MAX_STATE_PROOF_NODES, MAX_TRIE_NODE_BYTES)
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_simple_proof_bounds() {
+        let (proof, _) = combined_proof_for(&[0xAA; 20], &[0x01; 32], &VALUE);
+        let simple_proof = EthereumSimpleProof::from_combined_proof(proof);
+        // one account node and one storage node, so a limit of one node per trie holds
+        simple_proof
+            .validate_bounds(1, MAX_TRIE_NODE_BYTES)
+            .unwrap();
+
+        let mut truncated = simple_proof.clone();
+        truncated.proof[0] = 5u16.to_be_bytes().to_vec();
+        assert!(truncated.validate_bounds(1, MAX_TRIE_NODE_BYTES).is_err());
+        truncated.proof.clear();
+        assert!(truncated.validate_bounds(1, MAX_TRIE_NODE_BYTES).is_err());
+    }
+
+    #[test]
+    fn test_simple_proof_rejects_corrupt_length_prefixes() {
+        let (proof, state_root) = combined_proof_for(&[0xAA; 20], &[0x01; 32], &VALUE);
+        let simple_proof = EthereumSimpleProof::from_combined_proof(proof);
+        assert!(simple_proof.verify(&state_root).unwrap());
+
+        let corruptions: [fn(&mut EthereumSimpleProof); 8] = [
+            |p| p.proof[0] = u16::MAX.to_be_bytes().to_vec(),
+            |p| p.proof[0] = alloc::vec![0x01],
+            |p| p.proof.clear(),
+            |p| p.key[..2].copy_from_slice(&u16::MAX.to_be_bytes()),
+            |p| p.key.truncate(1),
+            |p| p.value[..2].copy_from_slice(&u16::MAX.to_be_bytes()),
+            |p| p.value.truncate(2 + 10),
+            |p| p.value.clear(),
+        ];
+        for corrupt in corruptions {
+            let mut corrupted = simple_proof.clone();
+            corrupt(&mut corrupted);
+            // a clean failure rather than a panic
+            assert!(!matches!(corrupted.verify(&state_root), Ok(true)));
+        }
+    }
+}
@@ -74,4 +74,33 @@ mod tests {
             .verify(block.header.receipts_root.as_slice())
             .unwrap());
     }
+
+    #[tokio::test]
+    async fn test_get_receipt_proofs() {
+        use common::merkle::types::MerkleVerifiable;
+        let rpc_url = read_sepolia_url();
+        let prover = EvmMerkleRpcClient { rpc_url };
+        let sepolia_height = read_sepolia_height().await.unwrap();
+        let receipt_proofs = prover
+            .get_receipt_proofs(sepolia_height, &[0, 1, 2])
+            .await
+            .unwrap();
+        assert_eq!(receipt_proofs.len(), 3);
+
+        let provider = ProviderBuilder::new().on_http(Url::from_str(&read_sepolia_url()).unwrap());
+        let block = provider
+            .get_block_by_number(alloy::eips::BlockNumberOrTag::Number(sepolia_height))
+            .await
+            .expect("Failed to get Block!")
+            .expect("Block not found!");
+        for receipt_proof in &receipt_proofs {
+            assert!(receipt_proof
+                .verify(block.header.receipts_root.as_slice())
+                .unwrap());
+        }
+        assert_eq!(
+            receipt_proofs[1],
+            prover.get_receipt_proof(sepolia_height, 1).await.unwrap()
+        );
+    }
 }
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        merkle_lib::{
+            diff::diff_proofs,
+            digest_keccak,
+            tests::fixtures::branch_trie,
+            types::{
+                EthereumMerkleProof, EthereumProofType, EthereumReceiptProof, EthereumStorageProof,
+            },
+        },
+        mock::storage_proof_for,
+    };
+
+    #[test]
+    fn test_diff_reports_changed_path() {
+        let (slot_a, slot_b) = ([0x01; 32], [0x02; 32]);
+        let (old_proofs, _) = branch_trie(&[(&slot_a, &[0x01]), (&slot_b, &[0x05])]);
+        let (new_proofs, _) = branch_trie(&[(&slot_a, &[0x01]), (&slot_b, &[0x06])]);
+        let proof = |proofs: &[Vec<Vec<u8>>], index: usize, value: u8| {
+            EthereumMerkleProof::Storage(EthereumStorageProof::new(
+                proofs[index].clone(),
+                [slot_a, slot_b][index].to_vec(),
+                vec![value],
+            ))
+        };
+
+        // the branch and the leaf of the written slot change
+        let diff = diff_proofs(&proof(&old_proofs, 1, 0x05), &proof(&new_proofs, 1, 0x06)).unwrap();
+        assert!(diff.value_changed());
+        assert_eq!((diff.old_value, diff.new_value), (vec![0x05], vec![0x06]));
+        let hashes = |nodes: &[Vec<u8>]| -> Vec<[u8; 32]> {
+            nodes.iter().map(|node| digest_keccak(node)).collect()
+        };
+        assert_eq!(diff.removed_nodes, hashes(&old_proofs[1]));
+        assert_eq!(diff.added_nodes, hashes(&new_proofs[1]));
+
+        // the untouched slot keeps its value and leaf, only the branch moved
+        let diff = diff_proofs(&proof(&old_proofs, 0, 0x01), &proof(&new_proofs, 0, 0x01)).unwrap();
+        assert!(!diff.value_changed());
+        assert_eq!(diff.removed_nodes, hashes(&old_proofs[0][..1]));
+        assert_eq!(diff.added_nodes, hashes(&new_proofs[0][..1]));
+    }
+
+    #[test]
+    fn test_diff_rejects_unrelated_proofs() {
+        let (proof, _) = storage_proof_for(&[0x01; 32], &[0x2a]);
+        let (other_slot, _) = storage_proof_for(&[0x02; 32], &[0x2a]);
+        let storage = EthereumProofType::Storage(proof.clone());
+        assert!(diff_proofs(&storage, &EthereumProofType::Storage(other_slot)).is_err());
+        assert!(diff_proofs(
+            &storage,
+            &EthereumProofType::Receipt(EthereumReceiptProof::new(
+                proof.proof,
+                proof.key,
+                proof.value,
+            ))
+        )
+        .is_err());
+
+        let diff = diff_proofs(&storage, &storage).unwrap();
+        assert!(!diff.value_changed());
+        assert!(diff.removed_nodes.is_empty() && diff.added_nodes.is_empty());
+    }
+}
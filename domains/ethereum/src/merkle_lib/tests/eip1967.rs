@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use crate::{
+        merkle_lib::{
+            digest_keccak,
+            eip1967::{decode_address_slot, ADMIN_SLOT, BEACON_SLOT, IMPLEMENTATION_SLOT},
+        },
+        mock::storage_proof_for,
+        timewave_rlp,
+    };
+
+    fn slot_for(label: &str) -> [u8; 32] {
+        let slot = BigUint::from_bytes_be(&digest_keccak(label.as_bytes())) - 1u32;
+        let mut out = [0u8; 32];
+        let bytes = slot.to_bytes_be();
+        out[32 - bytes.len()..].copy_from_slice(&bytes);
+        out
+    }
+
+    #[test]
+    fn test_eip1967_slots() {
+        assert_eq!(
+            IMPLEMENTATION_SLOT,
+            slot_for("eip1967.proxy.implementation")
+        );
+        assert_eq!(ADMIN_SLOT, slot_for("eip1967.proxy.admin"));
+        assert_eq!(BEACON_SLOT, slot_for("eip1967.proxy.beacon"));
+    }
+
+    #[test]
+    fn test_proven_implementation_address() {
+        // an address with a leading zero byte, which the stored value strips
+        let mut implementation = [0x42u8; 20];
+        implementation[0] = 0;
+        let value = timewave_rlp::encode(&implementation[1..]);
+        let (proof, storage_root) = storage_proof_for(&IMPLEMENTATION_SLOT, &value);
+        let proven = proof
+            .verify_for_slot(&storage_root, &IMPLEMENTATION_SLOT)
+            .unwrap();
+        assert_eq!(decode_address_slot(&proven).unwrap(), implementation);
+
+        assert!(decode_address_slot(&[0x80]).is_err());
+        assert!(decode_address_slot(&timewave_rlp::encode([0x42u8; 32].as_slice())).is_err());
+    }
+}
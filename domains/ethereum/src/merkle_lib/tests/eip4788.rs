@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::{vec, vec::Vec};
+
+    use common::merkle::types::MerkleVerifiable;
+
+    use crate::{
+        merkle_lib::{
+            digest_keccak,
+            eip4788::{
+                root_slot, timestamp_slot, verify_beacon_root, BEACON_ROOTS_ADDRESS,
+                HISTORY_BUFFER_LENGTH,
+            },
+            tests::fixtures::{account_rlp, branch_trie, single_leaf_trie},
+            types::{
+                EthereumAccessListProof, EthereumAccountProof, EthereumStorageProof, ValueEncoding,
+            },
+        },
+        mock::EMPTY_CODE_HASH,
+        timewave_rlp,
+        timewave_trie::builder::TrieBuilder,
+    };
+
+    const TIMESTAMP: u64 = 1_718_000_004;
+    const BEACON_ROOT: [u8; 32] = [0x5b; 32];
+
+    /// Builds the beacon roots contract's state holding a single ring buffer entry.
+    fn beacon_roots_proof(timestamp: u64) -> (EthereumAccessListProof, [u8; 32]) {
+        let slots = [timestamp_slot(timestamp), root_slot(timestamp)];
+        let values = [
+            timewave_rlp::encode(timestamp),
+            timewave_rlp::encode(BEACON_ROOT.as_slice()),
+        ];
+        let (storage_nodes, storage_root) = branch_trie(&[
+            (slots[0].as_slice(), values[0].as_slice()),
+            (slots[1].as_slice(), values[1].as_slice()),
+        ]);
+        let storage_proofs: Vec<EthereumStorageProof> = storage_nodes
+            .into_iter()
+            .zip(slots.iter().zip(values))
+            .map(|(nodes, (slot, value))| EthereumStorageProof::new(nodes, slot.to_vec(), value))
+            .collect();
+        let account = account_rlp(1, 0, &storage_root, &EMPTY_CODE_HASH);
+        let (account_nodes, state_root) = single_leaf_trie(&BEACON_ROOTS_ADDRESS, &account);
+        let account_proof =
+            EthereumAccountProof::new(account_nodes, BEACON_ROOTS_ADDRESS.to_vec(), account);
+        (
+            EthereumAccessListProof::new(account_proof, storage_proofs),
+            state_root,
+        )
+    }
+
+    #[test]
+    fn test_eip4788_slots() {
+        let mut slot = [0u8; 32];
+        slot[31] = 5;
+        assert_eq!(timestamp_slot(HISTORY_BUFFER_LENGTH + 5), slot);
+        slot[30..].copy_from_slice(&(HISTORY_BUFFER_LENGTH as u16 + 5).to_be_bytes());
+        assert_eq!(root_slot(5), slot);
+    }
+
+    #[test]
+    fn test_verify_beacon_root() {
+        let (proof, state_root) = beacon_roots_proof(TIMESTAMP);
+        assert_eq!(
+            verify_beacon_root(&proof, TIMESTAMP, &state_root).unwrap(),
+            BEACON_ROOT
+        );
+        // a timestamp sharing the entry's slots, whose root was overwritten
+        assert!(
+            verify_beacon_root(&proof, TIMESTAMP - HISTORY_BUFFER_LENGTH, &state_root).is_err()
+        );
+        assert!(verify_beacon_root(&proof, TIMESTAMP + 1, &state_root).is_err());
+        assert!(verify_beacon_root(&proof, TIMESTAMP, &[0u8; 32]).is_err());
+
+        // raw words are decoded from the leaf encoding that was verified
+        let mut raw = proof.clone();
+        let mut timestamp_word = [0u8; 32];
+        timestamp_word[24..].copy_from_slice(&TIMESTAMP.to_be_bytes());
+        let words = [timestamp_word, BEACON_ROOT].map(|word| word.to_vec());
+        raw.storage_proofs = raw
+            .storage_proofs
+            .into_iter()
+            .zip(words)
+            .map(|(storage_proof, word)| {
+                EthereumStorageProof::new(storage_proof.proof, storage_proof.key, word)
+                    .with_value_encoding(ValueEncoding::Raw)
+            })
+            .collect();
+        assert_eq!(
+            verify_beacon_root(&raw, TIMESTAMP, &state_root).unwrap(),
+            BEACON_ROOT
+        );
+
+        let mut swapped = proof.clone();
+        swapped.storage_proofs.reverse();
+        assert!(verify_beacon_root(&swapped, TIMESTAMP, &state_root).is_err());
+    }
+
+    #[test]
+    fn test_verify_beacon_root_rejects_absent_root() {
+        // the entry's timestamp was written, but its root slot was not
+        let timestamp = timewave_rlp::encode(TIMESTAMP);
+        let mut trie = TrieBuilder::new();
+        trie.insert(
+            &digest_keccak(&timestamp_slot(TIMESTAMP)),
+            timestamp.clone(),
+        )
+        .unwrap();
+        let storage_root = trie.root();
+        let storage_proofs = vec![
+            EthereumStorageProof::new(
+                trie.proof(&digest_keccak(&timestamp_slot(TIMESTAMP))),
+                timestamp_slot(TIMESTAMP).to_vec(),
+                timestamp,
+            ),
+            EthereumStorageProof::new(
+                trie.proof(&digest_keccak(&root_slot(TIMESTAMP))),
+                root_slot(TIMESTAMP).to_vec(),
+                vec![],
+            ),
+        ];
+        let account = account_rlp(1, 0, &storage_root, &EMPTY_CODE_HASH);
+        let (account_nodes, state_root) = single_leaf_trie(&BEACON_ROOTS_ADDRESS, &account);
+        let account_proof =
+            EthereumAccountProof::new(account_nodes, BEACON_ROOTS_ADDRESS.to_vec(), account);
+        let proof = EthereumAccessListProof::new(account_proof, storage_proofs);
+
+        // the absence proof verifies, but must not be read as a zero root
+        assert!(proof.verify(&state_root).unwrap());
+        assert!(verify_beacon_root(&proof, TIMESTAMP, &state_root).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec;
+    use num_bigint::BigUint;
+
+    use crate::{
+        merkle_lib::{
+            digest_keccak,
+            erc20::{
+                TokenStorageLayout, MAINNET_DAI, MAINNET_USDC, MAINNET_USDT, SEPOLIA_DAI,
+                SEPOLIA_USDC, SEPOLIA_USDT,
+            },
+            tests::fixtures::{account_rlp, single_leaf_trie},
+            types::{EthereumCombinedProof, EthereumStorageProof},
+        },
+        mock::{account_proof_for, combined_proof_for, EMPTY_CODE_HASH},
+    };
+    use common::merkle::types::MerkleVerifiable;
+
+    const HOLDER: [u8; 20] = [0xAB; 20];
+
+    #[test]
+    fn test_registered_layouts() {
+        assert_eq!(
+            TokenStorageLayout::for_token(&MAINNET_USDT),
+            Some(TokenStorageLayout::plain(2, 1))
+        );
+        assert_eq!(
+            TokenStorageLayout::for_token(&MAINNET_DAI),
+            Some(TokenStorageLayout::plain(2, 1))
+        );
+        let usdc = TokenStorageLayout::for_token(&MAINNET_USDC).unwrap();
+        assert_eq!(usdc.balances_slot, 9);
+        assert_eq!(usdc.balance_bits, 255);
+        assert_eq!(TokenStorageLayout::for_token(&SEPOLIA_USDC), Some(usdc));
+        for token in [SEPOLIA_USDT, SEPOLIA_DAI] {
+            assert_eq!(
+                TokenStorageLayout::for_token(&token),
+                Some(TokenStorageLayout::plain(0, 2))
+            );
+        }
+        assert_eq!(TokenStorageLayout::for_token(&[0x01; 20]), None);
+    }
+
+    #[test]
+    fn test_balance_slot() {
+        // keccak256(abi.encode(holder, 2))
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(&HOLDER);
+        preimage[63] = 2;
+        assert_eq!(
+            TokenStorageLayout::plain(2, 1).balance_slot(&HOLDER),
+            digest_keccak(&preimage)
+        );
+        assert_ne!(
+            TokenStorageLayout::plain(2, 1).balance_slot(&HOLDER),
+            TokenStorageLayout::plain(3, 1).balance_slot(&HOLDER)
+        );
+    }
+
+    #[test]
+    fn test_decode_balance() {
+        let plain = TokenStorageLayout::plain(2, 1);
+        assert_eq!(plain.decode_balance(&[]).unwrap(), BigUint::from(0u8));
+        assert_eq!(
+            plain.decode_balance(&[0x82, 0x04, 0xd2]).unwrap(),
+            BigUint::from(1234u32)
+        );
+
+        // a blacklisted FiatToken holder has the highest bit set above the balance
+        let mut value = [0u8; 33];
+        value[0] = 0xa0;
+        value[1] = 0x80;
+        value[32] = 0x07;
+        let usdc = TokenStorageLayout::for_token(&MAINNET_USDC).unwrap();
+        assert_eq!(usdc.decode_balance(&value).unwrap(), BigUint::from(7u8));
+        assert!(plain.decode_balance(&value).unwrap() > BigUint::from(7u8));
+
+        // an invalid width is rejected even for an unwritten slot
+        let invalid = TokenStorageLayout {
+            balance_bits: 0,
+            ..plain
+        };
+        assert!(invalid.decode_balance(&[]).is_err());
+    }
+
+    #[test]
+    fn test_balance_proof() {
+        let layout = TokenStorageLayout::for_token(&MAINNET_USDT).unwrap();
+        let slot = layout.balance_slot(&HOLDER);
+        let (proof, state_root) = combined_proof_for(&MAINNET_USDT, &slot, &[0x82, 0x04, 0xd2]);
+        assert!(proof.verify(&state_root).unwrap());
+        assert!(proof.assert_consistent().is_ok());
+        assert_eq!(
+            layout.decode_balance(&proof.storage_proof.value).unwrap(),
+            BigUint::from(1234u32)
+        );
+    }
+
+    #[test]
+    fn test_zero_balance_proof() {
+        let layout = TokenStorageLayout::for_token(&MAINNET_USDT).unwrap();
+        let slot = layout.balance_slot(&HOLDER);
+        // the token's storage only holds the balance of another holder
+        let (storage_nodes, storage_root) =
+            single_leaf_trie(&layout.balance_slot(&[0xCD; 20]), &[0x82, 0x04, 0xd2]);
+        let account = account_rlp(0, 0, &storage_root, &EMPTY_CODE_HASH);
+        let (account_proof, state_root) = account_proof_for(&MAINNET_USDT, &account);
+        let proof = EthereumCombinedProof::new(
+            account_proof,
+            EthereumStorageProof::new(storage_nodes, slot.to_vec(), vec![]),
+        );
+        assert!(proof.assert_consistent().is_ok());
+        assert!(proof.verify(&state_root).unwrap());
+        assert_eq!(
+            layout.decode_balance(&proof.storage_proof.value).unwrap(),
+            BigUint::from(0u8)
+        );
+    }
+
+    #[test]
+    fn test_total_supply_proof() {
+        let usdc = TokenStorageLayout::for_token(&MAINNET_USDC).unwrap();
+        let mut slot = [0u8; 32];
+        slot[31] = 11;
+        assert_eq!(usdc.supply_slot(), slot);
+
+        let (proof, state_root) = combined_proof_for(&MAINNET_USDC, &slot, &[0x82, 0x04, 0xd2]);
+        assert!(proof.verify(&state_root).unwrap());
+        assert_eq!(
+            usdc.decode_total_supply(&proof.storage_proof.value)
+                .unwrap(),
+            BigUint::from(1234u32)
+        );
+        assert_eq!(usdc.decode_total_supply(&[]).unwrap(), BigUint::from(0u8));
+    }
+
+    #[test]
+    fn test_zero_total_supply_proof() {
+        let usdc = TokenStorageLayout::for_token(&MAINNET_USDC).unwrap();
+        // the token's storage only holds a balance, its supply slot was never written
+        let (storage_nodes, storage_root) =
+            single_leaf_trie(&usdc.balance_slot(&HOLDER), &[0x82, 0x04, 0xd2]);
+        let proof = EthereumStorageProof::new(storage_nodes, usdc.supply_slot().to_vec(), vec![]);
+        assert!(proof.verify_nonexistence(&storage_root).unwrap());
+        assert_eq!(
+            usdc.decode_total_supply(&proof.value).unwrap(),
+            BigUint::from(0u8)
+        );
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec;
+
+    use crate::{
+        merkle_lib::{
+            digest_keccak,
+            tests::fixtures::{account_rlp, branch_trie, single_leaf_trie},
+            types::{EthereumAccountProof, EthereumStorageProof},
+        },
+        timewave_rlp,
+        timewave_trie::{builder::TrieBuilder, constants::EMPTY_ROOT_HASH_BYTES},
+    };
+
+    const ADDRESS_A: [u8; 20] = [0x11; 20];
+    const ADDRESS_B: [u8; 20] = [0x22; 20];
+
+    fn first_nibble(address: &[u8]) -> u8 {
+        digest_keccak(address)[0] >> 4
+    }
+
+    /// Returns an address whose hashed path diverges from both test accounts at the
+    /// first nibble.
+    fn absent_address() -> [u8; 20] {
+        let used = [first_nibble(&ADDRESS_A), first_nibble(&ADDRESS_B)];
+        (0u8..=255)
+            .map(|byte| [byte; 20])
+            .find(|address| !used.contains(&first_nibble(address)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_nonexistence_at_empty_branch_slot() {
+        let account = account_rlp(1, 100, &EMPTY_ROOT_HASH_BYTES, &[0u8; 32]);
+        let (proofs, root) = branch_trie(&[(&ADDRESS_A, &account), (&ADDRESS_B, &account)]);
+
+        // the exclusion proof for an absent address stops at the root branch
+        let absent = EthereumAccountProof::new(
+            vec![proofs[0][0].clone()],
+            absent_address().to_vec(),
+            vec![],
+        );
+        assert!(absent.verify_nonexistence(&root).unwrap());
+
+        let present = EthereumAccountProof::new(proofs[0].clone(), ADDRESS_A.to_vec(), vec![]);
+        assert!(!present.verify_nonexistence(&root).unwrap());
+    }
+
+    #[test]
+    fn test_verify_nonexistence_at_diverging_leaf() {
+        let account = account_rlp(1, 100, &EMPTY_ROOT_HASH_BYTES, &[0u8; 32]);
+        let (nodes, root) = single_leaf_trie(&ADDRESS_A, &account);
+        let absent = EthereumAccountProof::new(nodes, ADDRESS_B.to_vec(), vec![]);
+        assert!(absent.verify_nonexistence(&root).unwrap());
+        assert!(absent.verify_nonexistence(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_verify_get_absent_values() {
+        let account = account_rlp(1, 100, &EMPTY_ROOT_HASH_BYTES, &[0u8; 32]);
+        let (nodes, root) = single_leaf_trie(&ADDRESS_A, &account);
+        let absent = EthereumAccountProof::new(nodes.clone(), ADDRESS_B.to_vec(), vec![]);
+        assert!(absent.verify_get(&root).unwrap().is_empty());
+        // an account that exists cannot be read as absent
+        let hidden = EthereumAccountProof::new(nodes, ADDRESS_A.to_vec(), vec![]);
+        assert!(hidden.verify_get(&root).is_err());
+        assert!(hidden.verify_and_decode(&root).is_err());
+
+        let (slot_a, slot_b) = ([0x01u8; 32], [0x02u8; 32]);
+        let (nodes, storage_root) = single_leaf_trie(&slot_a, &[0x2a]);
+        let absent = EthereumStorageProof::new(nodes.clone(), slot_b.to_vec(), vec![]);
+        assert!(absent.verify_get(&storage_root).unwrap().is_empty());
+        let hidden = EthereumStorageProof::new(nodes, slot_a.to_vec(), vec![]);
+        assert!(hidden.verify_get(&storage_root).is_err());
+    }
+
+    #[test]
+    fn test_verify_nonexistence_in_empty_trie() {
+        let absent = EthereumAccountProof::new(vec![], ADDRESS_A.to_vec(), vec![]);
+        assert!(absent.verify_nonexistence(&EMPTY_ROOT_HASH_BYTES).unwrap());
+    }
+
+    #[test]
+    fn test_verify_nonexistence_rejects_truncated_proof() {
+        let slots: alloc::vec::Vec<[u8; 32]> = (0u8..30)
+            .map(|i| {
+                let mut slot = [0u8; 32];
+                slot[31] = i;
+                slot
+            })
+            .collect();
+        let mut trie = TrieBuilder::new();
+        for (i, slot) in slots.iter().enumerate() {
+            trie.insert(&digest_keccak(slot), timewave_rlp::encode(i as u64 + 1))
+                .unwrap();
+        }
+        let root = trie.root();
+
+        // the root branch references the subtrie holding the slot by its hash, so a
+        // proof cut after the root shows neither the slot's value nor its absence
+        let proof = trie.proof(&digest_keccak(&slots[7]));
+        assert!(proof.len() > 1);
+        let full = EthereumStorageProof::new(proof.clone(), slots[7].to_vec(), vec![]);
+        assert!(!full.verify_nonexistence(&root).unwrap());
+        let truncated =
+            EthereumStorageProof::new(vec![proof[0].clone()], slots[7].to_vec(), vec![]);
+        assert!(truncated.verify_nonexistence(&root).is_err());
+
+        // the same holds for a key that is absent, but shares the slot's subtrie
+        let nibble = digest_keccak(&slots[7])[0] >> 4;
+        let address = (0u8..=255)
+            .map(|byte| [byte; 20])
+            .find(|address| first_nibble(address) == nibble)
+            .unwrap();
+        let account = EthereumAccountProof::new(vec![proof[0].clone()], address.to_vec(), vec![]);
+        assert!(account.verify_nonexistence(&root).is_err());
+    }
+}
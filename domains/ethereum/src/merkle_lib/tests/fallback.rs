@@ -0,0 +1,92 @@
+#[cfg(feature = "no-zkvm")]
+#[cfg(test)]
+mod tests {
+    use alloy::transports::TransportErrorKind;
+    use common::merkle::types::MerkleClient;
+    use core::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use crate::{
+        ethereum_rpc::{
+            fallback::FallbackEvmMerkleRpcClient,
+            rpc::{EvmMerkleRpcClient, StatePruned},
+        },
+        merkle_lib::tests::defaults::constants::{
+            read_sepolia_default_account_address, read_sepolia_height, read_sepolia_url,
+        },
+    };
+
+    fn client(rpc_urls: &[&str]) -> FallbackEvmMerkleRpcClient {
+        FallbackEvmMerkleRpcClient::new(rpc_urls.iter().map(|url| url.to_string()).collect())
+            .with_retries(2, Duration::ZERO)
+    }
+
+    async fn respond(client: &EvmMerkleRpcClient, attempts: &AtomicUsize) -> anyhow::Result<u64> {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        match client.rpc_url.as_str() {
+            "pruned" => Err(StatePruned { height: 1 }.into()),
+            "good" => Ok(1),
+            "invalid" => anyhow::bail!("invalid address"),
+            _ => Err(TransportErrorKind::custom_str("connection refused").into()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_retries_before_failing_over() {
+        let attempts = AtomicUsize::new(0);
+        let value = client(&["bad", "good"])
+            .call(|c| respond(c, &attempts))
+            .await
+            .unwrap();
+        assert_eq!(value, 1);
+        // three attempts against the primary, one against the secondary
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_skips_retries_for_pruned_state() {
+        let attempts = AtomicUsize::new(0);
+        let error = client(&["pruned", "pruned"])
+            .call(|c| respond(c, &attempts))
+            .await
+            .unwrap_err();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(error.downcast_ref::<StatePruned>().is_some());
+        assert!(client(&[]).call(|c| respond(c, &attempts)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_returns_deterministic_errors() {
+        let attempts = AtomicUsize::new(0);
+        let error = client(&["invalid", "good"])
+            .call(|c| respond(c, &attempts))
+            .await
+            .unwrap_err();
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(error.to_string(), "invalid address");
+    }
+
+    // needs a live Sepolia endpoint, run with
+    // `cargo test --features no-zkvm test_fallback_to_secondary_endpoint -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_fallback_to_secondary_endpoint() {
+        let prover = FallbackEvmMerkleRpcClient::new(vec![
+            "http://127.0.0.1:1".to_string(),
+            read_sepolia_url(),
+        ])
+        .with_retries(1, Duration::from_millis(10));
+        let height = read_sepolia_height().await.unwrap();
+        let proof = prover
+            .get_proof(
+                &hex::encode([0u8; 32]),
+                &read_sepolia_default_account_address(),
+                height,
+            )
+            .await
+            .unwrap();
+        assert!(!proof.is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+//! Offline trie fixtures for proof verification tests.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::{merkle_lib::digest_keccak, timewave_rlp};
+
+pub(crate) use crate::mock::{account_rlp, leaf_node};
+
+/// Builds a trie holding a single `(keccak256(key), value)` entry.
+///
+/// # Returns
+/// The single proof node (the root leaf) and the trie root
+pub(crate) fn single_leaf_trie(key: &[u8], value: &[u8]) -> (Vec<Vec<u8>>, [u8; 32]) {
+    let leaf = leaf_node(&digest_keccak(key), value);
+    let root = digest_keccak(&leaf);
+    (Vec::from([leaf]), root)
+}
+
+/// Builds a trie whose root is a branch node with one hashed leaf per entry.
+///
+/// The keccak256 hashes of all keys must differ in their first nibble.
+///
+/// # Returns
+/// The proof nodes (branch, leaf) for every entry in input order, and the trie root
+pub(crate) fn branch_trie(entries: &[(&[u8], &[u8])]) -> (Vec<Vec<Vec<u8>>>, [u8; 32]) {
+    let mut children: [Option<[u8; 32]>; 16] = [None; 16];
+    let mut leaves = Vec::new();
+    for (key, value) in entries {
+        let path = digest_keccak(key);
+        let nibble = (path[0] >> 4) as usize;
+        assert!(children[nibble].is_none(), "keys share a first nibble");
+        // the branch consumes the first nibble, leaving an odd-length leaf path
+        let mut encoded_path = Vec::with_capacity(32);
+        encoded_path.push(0x30 | (path[0] & 0x0f));
+        encoded_path.extend_from_slice(&path[1..]);
+        let mut leaf = Vec::new();
+        timewave_rlp::encode_list::<&[u8], [u8]>(&[encoded_path.as_slice(), value], &mut leaf);
+        children[nibble] = Some(digest_keccak(&leaf));
+        leaves.push(leaf);
+    }
+    let items: Vec<&[u8]> = children
+        .iter()
+        .map(|child| child.as_ref().map(|hash| hash.as_slice()).unwrap_or(&[]))
+        .chain(core::iter::once(&[][..]))
+        .collect();
+    let mut branch = Vec::new();
+    timewave_rlp::encode_list::<&[u8], [u8]>(&items, &mut branch);
+    let root = digest_keccak(&branch);
+    let proofs = leaves
+        .into_iter()
+        .map(|leaf| Vec::from([branch.clone(), leaf]))
+        .collect();
+    (proofs, root)
+}
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        merkle_lib::{digest_keccak, header::BlockHeaderFields, types::EthereumReceiptProof},
+        timewave_rlp::{self, encode_raw_list},
+        timewave_trie::builder::TrieBuilder,
+        verify_event_proof, verify_header_chain,
+    };
+
+    /// Encodes a post-London header with the given parent, number and state root.
+    fn header(parent_hash: [u8; 32], number: u64, state_root: [u8; 32]) -> Vec<u8> {
+        header_with_receipts_root(parent_hash, number, state_root, [0x02; 32])
+    }
+
+    fn header_with_receipts_root(
+        parent_hash: [u8; 32],
+        number: u64,
+        state_root: [u8; 32],
+        receipts_root: [u8; 32],
+    ) -> Vec<u8> {
+        encode_raw_list(&[
+            timewave_rlp::encode(&parent_hash[..]),
+            timewave_rlp::encode(&[0x1d; 32][..]),
+            timewave_rlp::encode(&[0xbe; 20][..]),
+            timewave_rlp::encode(&state_root[..]),
+            timewave_rlp::encode(&[0x01; 32][..]),
+            timewave_rlp::encode(&receipts_root[..]),
+            timewave_rlp::encode(&[0u8; 256][..]),
+            timewave_rlp::encode(0u64),
+            timewave_rlp::encode(number),
+            timewave_rlp::encode(30_000_000u64),
+            timewave_rlp::encode(21_000u64),
+            timewave_rlp::encode(1_700_000_000u64 + 12 * number),
+            timewave_rlp::encode(&b"extra"[..]),
+            timewave_rlp::encode(&[0x4d; 32][..]),
+            timewave_rlp::encode(&[0u8; 8][..]),
+            timewave_rlp::encode(7u64),
+        ])
+    }
+
+    /// A chain of `len` headers whose first header is the child of `trusted`.
+    fn chain(trusted: [u8; 32], len: u64) -> Vec<Vec<u8>> {
+        let mut headers: Vec<Vec<u8>> = Vec::new();
+        for number in 100..100 + len {
+            let parent_hash = headers.last().map_or(trusted, |h| digest_keccak(h));
+            headers.push(header(parent_hash, number, [number as u8; 32]));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_decode_header() {
+        let encoded = header([0xaa; 32], 100, [0x5a; 32]);
+        let fields = BlockHeaderFields::decode(&encoded).unwrap();
+        assert_eq!(fields.hash, digest_keccak(&encoded));
+        assert_eq!(fields.parent_hash, [0xaa; 32]);
+        assert_eq!(fields.state_root, [0x5a; 32]);
+        assert_eq!(fields.receipts_root, [0x02; 32]);
+        assert_eq!((fields.number, fields.timestamp), (100, 1_700_001_200));
+
+        assert!(BlockHeaderFields::decode(&encoded[..encoded.len() - 1]).is_err());
+        assert!(
+            BlockHeaderFields::decode(&encode_raw_list(&[timewave_rlp::encode(1u64)])).is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_header_chain() {
+        let trusted = [0x77; 32];
+        let headers = chain(trusted, 4);
+        let tip = verify_header_chain(&headers, &trusted).unwrap();
+        assert_eq!(tip.number, 103);
+        assert_eq!(tip.state_root, [103; 32]);
+        assert_eq!(tip.hash, digest_keccak(&headers[3]));
+
+        let single = verify_header_chain(&headers[..1], &trusted).unwrap();
+        assert_eq!(single.number, 100);
+    }
+
+    #[test]
+    fn test_rejects_broken_chains() {
+        let trusted = [0x77; 32];
+        let headers = chain(trusted, 3);
+        assert!(verify_header_chain(&headers, &[0x78; 32]).is_err());
+        assert!(verify_header_chain(&headers, &trusted[..31]).is_err());
+        assert!(verify_header_chain(&[], &trusted).is_err());
+
+        // a header swapped for a sibling with another state root
+        let mut forked = headers.clone();
+        forked[1] = header(digest_keccak(&headers[0]), 101, [0xff; 32]);
+        assert!(verify_header_chain(&forked, &trusted).is_err());
+
+        // a correctly linked header that skips a block number
+        let mut skipped = headers[..1].to_vec();
+        skipped.push(header(digest_keccak(&headers[0]), 102, [0; 32]));
+        assert!(verify_header_chain(&skipped, &trusted).is_err());
+
+        let reordered = vec![headers[1].clone(), headers[0].clone()];
+        assert!(verify_header_chain(&reordered, &trusted).is_err());
+    }
+
+    /// Encodes a successful EIP-1559 receipt with one log whose data is `data`.
+    fn receipt(data: &[u8]) -> Vec<u8> {
+        let log = encode_raw_list(&[
+            timewave_rlp::encode(&[0xAA; 20][..]),
+            encode_raw_list(&[timewave_rlp::encode(&[0x11; 32][..])]),
+            timewave_rlp::encode(data),
+        ]);
+        let receipt = encode_raw_list(&[
+            timewave_rlp::encode(1u8),
+            timewave_rlp::encode(21_000u64),
+            timewave_rlp::encode(&[0u8; 256][..]),
+            encode_raw_list(&[log]),
+        ]);
+        [vec![0x02], receipt].concat()
+    }
+
+    #[test]
+    fn test_verify_event_proof() {
+        let mut trie = TrieBuilder::new();
+        for index in 0u32..5 {
+            trie.insert(&timewave_rlp::encode(index), receipt(&[index as u8; 4]))
+                .unwrap();
+        }
+        let header = header_with_receipts_root([0x77; 32], 100, [0x5a; 32], trie.root());
+        let block_hash = digest_keccak(&header);
+        let proof_for = |index: u32| {
+            let key = timewave_rlp::encode(index);
+            EthereumReceiptProof::new(trie.proof(&key), key, receipt(&[index as u8; 4]))
+        };
+
+        let logs = verify_event_proof(&block_hash, &header, proof_for(3), 3).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].address, [0xAA; 20]);
+        assert_eq!(logs[0].data, vec![3; 4]);
+
+        // another block, another index, or a header of another block
+        assert!(verify_event_proof(&[0u8; 32], &header, proof_for(3), 3).is_err());
+        assert!(verify_event_proof(&block_hash, &header, proof_for(3), 2).is_err());
+        let other = header_with_receipts_root([0x77; 32], 100, [0x5a; 32], [0x02; 32]);
+        assert!(verify_event_proof(&digest_keccak(&other), &other, proof_for(3), 3).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::format;
+
+    use crate::{
+        merkle_lib::types::{EthereumAccessListProof, EthereumProofType},
+        mock::{combined_proof_for, storage_proof_for},
+    };
+
+    #[test]
+    fn test_inspect_storage_proof() {
+        let (proof, _) = storage_proof_for(&[0x01; 32], b"\"100\"");
+        let description = proof.inspect();
+        assert!(description.starts_with("EthereumStorageProof\n"));
+        assert!(description.contains(&format!("  key: 0x{}\n", "01".repeat(32))));
+        assert!(description.contains(&format!("  nodes: 1 ({} bytes)\n", proof.proof[0].len())));
+        assert!(description.contains("  value: 0x2231303022 (\"\\\"100\\\"\")\n"));
+    }
+
+    #[test]
+    fn test_inspect_nested_proofs() {
+        let (combined_proof, _) = combined_proof_for(&[0xAA; 20], &[0x01; 32], &[0x82, 0x04, 0xd2]);
+        let description = EthereumProofType::from(combined_proof.clone()).inspect();
+        assert!(description.starts_with("EthereumCombinedProof\n  EthereumAccountProof\n"));
+        assert!(description.contains(&format!("    address: 0x{}\n", "aa".repeat(20))));
+        assert!(description.contains("  EthereumStorageProof\n"));
+        assert!(description.contains("    value: 0x8204d2\n"));
+
+        let access_list = EthereumAccessListProof::new(
+            combined_proof.account_proof,
+            alloc::vec![
+                combined_proof.storage_proof.clone(),
+                combined_proof.storage_proof
+            ],
+        );
+        assert_eq!(
+            access_list
+                .inspect()
+                .matches("  EthereumStorageProof\n")
+                .count(),
+            2
+        );
+    }
+}
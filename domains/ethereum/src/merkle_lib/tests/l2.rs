@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        merkle_lib::{
+            digest_keccak,
+            l2::{L2OutputRoot, OUTPUT_ROOT_VERSION_V0},
+            root::StateRootVerifiable,
+        },
+        mock::combined_proof_for,
+    };
+
+    #[test]
+    fn test_verify_output_root() {
+        let (proof, state_root) = combined_proof_for(&[0xAA; 20], &[0x01; 32], &[0x2a]);
+        let preimage = L2OutputRoot::new_v0(
+            state_root.clone().try_into().unwrap(),
+            [0x22; 32],
+            [0x33; 32],
+        );
+
+        let mut encoded = [0u8; 128];
+        encoded[32..64].copy_from_slice(&state_root);
+        encoded[64..96].copy_from_slice(&[0x22; 32]);
+        encoded[96..].copy_from_slice(&[0x33; 32]);
+        let output_root = digest_keccak(&encoded);
+        assert_eq!(preimage.output_root(), output_root);
+
+        // an L2 storage proof anchored to the output root
+        let l2_state_root = preimage.verify(&output_root).unwrap();
+        assert!(proof.verify_state_root(&l2_state_root).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_preimages() {
+        let preimage = L2OutputRoot::new_v0([0x11; 32], [0x22; 32], [0x33; 32]);
+        let output_root = preimage.output_root();
+
+        let mut other_block = preimage;
+        other_block.block_hash = [0x34; 32];
+        assert!(other_block.verify(&output_root).is_err());
+
+        let mut other_version = preimage;
+        other_version.version[31] = 1;
+        assert!(other_version.verify(&other_version.output_root()).is_err());
+        assert_eq!(preimage.version, OUTPUT_ROOT_VERSION_V0);
+        assert!(preimage.verify(&output_root[..31]).is_err());
+    }
+}
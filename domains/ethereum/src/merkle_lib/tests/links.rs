@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    use crate::{
+        merkle_lib::{
+            links::LinkedProof,
+            types::{EthereumProofType, EthereumStorageProof},
+        },
+        mock::combined_proof_for,
+        timewave_rlp,
+        timewave_trie::builder::TrieBuilder,
+    };
+
+    /// A trie deep enough for proofs to span several hashed nodes.
+    fn index_trie() -> TrieBuilder {
+        let mut trie = TrieBuilder::new();
+        for index in 0..130usize {
+            trie.insert(
+                &timewave_rlp::encode(index),
+                alloc::vec![index as u8 + 1; 40],
+            )
+            .unwrap();
+        }
+        trie
+    }
+
+    fn proof_of(trie: &TrieBuilder, index: usize) -> EthereumStorageProof {
+        let key = timewave_rlp::encode(index);
+        EthereumStorageProof::new(trie.proof(&key), key, alloc::vec![index as u8 + 1; 40])
+    }
+
+    #[test]
+    fn test_linked_proofs_pass() {
+        let trie = index_trie();
+        for index in [0, 5, 127, 128, 129] {
+            let proof = proof_of(&trie, index);
+            assert!(proof.proof.len() > 1);
+            proof.verify_node_hashes().unwrap();
+        }
+        let (combined, _) = combined_proof_for(&[0xAA; 20], &[0x01; 32], &[0x82, 0x04, 0xd2]);
+        combined.verify_node_hashes().unwrap();
+        EthereumProofType::Combined(combined)
+            .verify_node_hashes()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_rejects_corrupted_node() {
+        let trie = index_trie();
+        let mut proof = proof_of(&trie, 128);
+        let last = proof.proof.last_mut().unwrap();
+        *last.last_mut().unwrap() ^= 0x01;
+        assert!(proof.verify_node_hashes().is_err());
+    }
+
+    #[test]
+    fn test_rejects_orphaned_node() {
+        let trie = index_trie();
+        let mut proof = proof_of(&trie, 5);
+        let foreign: Vec<Vec<u8>> = proof_of(&trie, 128).proof;
+        proof.proof.push(foreign.last().unwrap().clone());
+        assert!(proof.verify_node_hashes().is_err());
+
+        let mut malformed = proof_of(&trie, 5);
+        malformed.proof.push(alloc::vec![0x80]);
+        assert!(malformed.verify_node_hashes().is_err());
+    }
+}
@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::{string::ToString, vec::Vec};
+    use common::merkle::types::MerkleVerifiable;
+
+    use crate::{
+        merkle_lib::types::{
+            EthereumAccount, EthereumCombinedProof, EthereumSimpleProof, RlpDecodable,
+        },
+        mock::{account_rlp, combined_proof_for, storage_proof_for, EMPTY_CODE_HASH},
+        timewave_rlp,
+    };
+
+    const SLOT: [u8; 32] = [0x01; 32];
+    const VALUE: [u8; 3] = [0x82, 0x04, 0xd2];
+
+    #[test]
+    fn test_mock_storage_proof_verifies() {
+        let (proof, root) = storage_proof_for(&SLOT, &VALUE);
+        assert!(proof.verify(&root).unwrap());
+        assert!(proof.verify(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_mock_combined_proof_verifies() {
+        let (proof, state_root) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        assert!(proof.verify(&state_root).unwrap());
+
+        let account = EthereumAccount::rlp_decode(&proof.account_proof.value).unwrap();
+        let (_, storage_root) = storage_proof_for(&SLOT, &VALUE);
+        assert_eq!(account.storage_root, storage_root);
+        assert_eq!(
+            proof.account_proof.storage_root().unwrap().to_vec(),
+            storage_root
+        );
+
+        let simple_proof = EthereumSimpleProof::from_combined_proof(proof);
+        assert!(simple_proof.verify(&state_root).unwrap());
+        assert_eq!(simple_proof.get_stored_value(), VALUE.to_vec());
+    }
+
+    #[test]
+    fn test_verify_get_returns_only_verified_values() {
+        let (proof, state_root) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let storage_root = proof.account_proof.storage_root().unwrap();
+        assert_eq!(
+            proof.storage_proof.verify_get(&storage_root).unwrap(),
+            VALUE.to_vec()
+        );
+        assert_eq!(
+            proof.account_proof.verify_get(&state_root).unwrap(),
+            proof.account_proof.value
+        );
+        assert!(proof.storage_proof.verify_get(&state_root).is_err());
+        assert!(proof.account_proof.verify_get(&storage_root).is_err());
+
+        let mut tampered = proof.storage_proof.clone();
+        tampered.value = timewave_rlp::encode(1u64);
+        assert!(tampered.verify_get(&storage_root).is_err());
+    }
+
+    #[test]
+    fn test_storage_root_rejects_malformed_accounts() {
+        let (mut proof, _) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let mut truncated = Vec::new();
+        timewave_rlp::encode_list::<&[u8], [u8]>(
+            &[&[], &[], &[0x11; 31], &EMPTY_CODE_HASH],
+            &mut truncated,
+        );
+        proof.account_proof.value = truncated;
+        assert!(proof.account_proof.storage_root().is_err());
+        proof.account_proof.value = alloc::vec![0xc0];
+        assert!(proof.account_proof.storage_root().is_err());
+    }
+
+    #[test]
+    fn test_account_rejects_truncated_hashes() {
+        let encode = |storage_root: &[u8], code_hash: &[u8]| {
+            let mut out = Vec::new();
+            timewave_rlp::encode_list::<&[u8], [u8]>(
+                &[&[], &[], storage_root, code_hash],
+                &mut out,
+            );
+            out
+        };
+        assert!(EthereumAccount::rlp_decode(&encode(&[0x11; 32], &EMPTY_CODE_HASH)).is_ok());
+        let error =
+            EthereumAccount::rlp_decode(&encode(&[0x11; 31], &EMPTY_CODE_HASH)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Account storage root is 31 bytes, expected 32"
+        );
+        assert!(EthereumAccount::rlp_decode(&encode(&[0x11; 32], &EMPTY_CODE_HASH[1..])).is_err());
+    }
+
+    #[test]
+    fn test_delegated_account() {
+        use crate::{
+            merkle_lib::{digest_keccak, types::DELEGATION_PREFIX},
+            mock::{account_proof_for, account_rlp},
+        };
+
+        let target = [0xCC; 20];
+        let designator = [DELEGATION_PREFIX.as_slice(), &target].concat();
+        let code_hash = digest_keccak(&designator);
+        let (proof, state_root) =
+            account_proof_for(&[0xAA; 20], &account_rlp(1, 0, &[0x11; 32], &code_hash));
+        let account = proof.verify_and_decode(&state_root).unwrap();
+        assert!(account.has_code());
+        assert_eq!(
+            account.delegation_target(&designator).unwrap(),
+            Some(target)
+        );
+        assert!(!account.is_contract(&designator).unwrap());
+        // code that does not match the proven code hash is rejected
+        assert!(account.delegation_target(&designator[..22]).is_err());
+
+        let bytecode = [0x60, 0x80, 0x60, 0x40, 0x52];
+        let contract =
+            EthereumAccount::rlp_decode(&account_rlp(1, 0, &[0x11; 32], &digest_keccak(&bytecode)))
+                .unwrap();
+        assert_eq!(contract.delegation_target(&bytecode).unwrap(), None);
+        assert!(contract.is_contract(&bytecode).unwrap());
+
+        let eoa =
+            EthereumAccount::rlp_decode(&account_rlp(1, 0, &[0x11; 32], &EMPTY_CODE_HASH)).unwrap();
+        assert!(!eoa.has_code());
+        assert!(!eoa.is_contract(&[]).unwrap());
+    }
+
+    #[test]
+    fn test_verify_constant_time() {
+        let (proof, root) = storage_proof_for(&SLOT, &VALUE);
+        assert!(proof.verify_constant_time(&root).unwrap());
+        assert!(!proof.verify_constant_time(&[0u8; 32]).unwrap());
+        assert!(!proof.verify_constant_time(&root[..31]).unwrap());
+
+        let mut wrong_value = proof.clone();
+        wrong_value.value = alloc::vec![0x82, 0x04, 0xd3];
+        assert!(!wrong_value.verify_constant_time(&root).unwrap());
+        wrong_value.value = VALUE[..2].to_vec();
+        assert!(!wrong_value.verify_constant_time(&root).unwrap());
+    }
+
+    #[test]
+    fn test_combined_proof_rejects_unrelated_storage_proof() {
+        let (proof_a, state_root_a) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let (proof_b, _) = combined_proof_for(&[0xBB; 20], &[0x02; 32], &[0x07]);
+        assert!(proof_a.assert_consistent().is_ok());
+
+        let mixed = EthereumCombinedProof::new(proof_a.account_proof, proof_b.storage_proof);
+        assert!(mixed.assert_consistent().is_err());
+        assert!(!matches!(mixed.verify(&state_root_a), Ok(true)));
+    }
+
+    #[test]
+    fn test_combined_proof_try_from_parts() {
+        let (proof_a, state_root_a) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let (proof_b, _) = combined_proof_for(&[0xBB; 20], &[0x02; 32], &[0x07]);
+
+        let combined = EthereumCombinedProof::try_from_parts(
+            proof_a.account_proof.clone(),
+            proof_a.storage_proof,
+        )
+        .unwrap();
+        assert!(combined.verify(&state_root_a).unwrap());
+
+        assert!(EthereumCombinedProof::try_from_parts(
+            proof_a.account_proof,
+            proof_b.storage_proof
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_verify_output() {
+        use common::merkle::types::{Domain, DomainProof};
+
+        let (proof, root) = storage_proof_for(&SLOT, &VALUE);
+        let output = proof.verify_output(&root).unwrap();
+        assert_eq!(output.root, root);
+        assert_eq!(output.key, SLOT.to_vec());
+        assert_eq!(output.value, VALUE.to_vec());
+        assert_eq!(output.domain, Domain::Ethereum);
+        assert!(proof.verify_output(&[0u8; 32]).is_err());
+    }
+
+    #[cfg(feature = "no-zkvm")]
+    #[test]
+    fn test_caching_verifier_keys_by_root() {
+        use common::cache::CachingVerifier;
+        use core::num::NonZeroUsize;
+
+        let verifier = CachingVerifier::new(NonZeroUsize::new(8).unwrap());
+        let (proof, root) = storage_proof_for(&SLOT, &VALUE);
+        assert!(verifier.verify(&proof, &root).unwrap());
+        assert!(verifier.verify(&proof, &root).unwrap());
+        assert_eq!(verifier.len(), 1);
+        // the cached entry is not served for another root
+        assert!(verifier.verify(&proof, &[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_assert_address() {
+        let (proof, state_root) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        assert!(proof.account_proof.assert_address(&[0xAA; 20]).is_ok());
+
+        // a proof of another contract verifies, but is not for the pinned address
+        let (other, other_root) = combined_proof_for(&[0xBB; 20], &SLOT, &VALUE);
+        assert!(other.verify(&other_root).unwrap());
+        assert!(other.account_proof.assert_address(&[0xAA; 20]).is_err());
+        assert!(proof.account_proof.assert_address(&[0xAA; 19]).is_err());
+        assert!(proof.verify(&state_root).unwrap());
+    }
+
+    #[test]
+    fn test_verify_and_decode() {
+        let (proof, state_root) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let account = proof.account_proof.verify_and_decode(&state_root).unwrap();
+        assert_eq!(
+            account.storage_root,
+            proof.account_proof.storage_root().unwrap()
+        );
+        assert!(!account.has_code());
+        assert!(proof.account_proof.verify_and_decode(&[0u8; 32]).is_err());
+
+        let mut tampered = proof.account_proof.clone();
+        tampered.value = account_rlp(1, 0, &[0x11; 32], &EMPTY_CODE_HASH);
+        assert!(tampered.verify_and_decode(&state_root).is_err());
+    }
+}
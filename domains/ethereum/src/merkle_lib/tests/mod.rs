@@ -1,6 +1,36 @@
 #[cfg(feature = "no-zkvm")]
 mod account;
+#[cfg(feature = "rayon")]
+mod batch;
+mod bounds;
 #[cfg(feature = "no-zkvm")]
 pub mod defaults;
+mod diff;
+mod eip1967;
+mod eip4788;
+mod erc20;
+mod exclusion;
+#[cfg(feature = "no-zkvm")]
+mod fallback;
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod header;
+mod inspect;
+mod l2;
+mod links;
+mod mock;
+mod multiproof;
+mod proof_type;
+mod receipt;
+#[cfg(feature = "no-zkvm")]
+mod replay;
+mod root;
+#[cfg(feature = "no-zkvm")]
+mod rpc;
+mod slot;
+mod storage;
+#[cfg(feature = "k256")]
+mod transaction;
+mod transport;
 #[cfg(feature = "no-zkvm")]
 pub mod vault;
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    use crate::{
+        merkle_lib::{
+            digest_keccak, multiproof::CompactStorageMultiProof, types::EthereumStorageProof,
+        },
+        timewave_rlp,
+        timewave_trie::builder::TrieBuilder,
+    };
+
+    fn slot(index: u8) -> [u8; 32] {
+        let mut slot = [0u8; 32];
+        slot[31] = index;
+        slot
+    }
+
+    fn value(index: u8) -> Vec<u8> {
+        timewave_rlp::encode(1000u64 + index as u64)
+    }
+
+    /// A storage trie holding slots 0 to 39, and proofs of `proven` slots.
+    fn storage_trie(proven: &[u8]) -> ([u8; 32], Vec<EthereumStorageProof>) {
+        let mut trie = TrieBuilder::new();
+        for index in 0..40 {
+            trie.insert(&digest_keccak(&slot(index)), value(index))
+                .unwrap();
+        }
+        let proofs = proven
+            .iter()
+            .map(|&index| {
+                let value = if index < 40 { value(index) } else { Vec::new() };
+                EthereumStorageProof::new(
+                    trie.proof(&digest_keccak(&slot(index))),
+                    slot(index).to_vec(),
+                    value,
+                )
+            })
+            .collect();
+        (trie.root(), proofs)
+    }
+
+    #[test]
+    fn test_verify_all() {
+        let proven = [0, 1, 2, 3, 17, 39, 200];
+        let (root, proofs) = storage_trie(&proven);
+        let multiproof = CompactStorageMultiProof::from_storage_proofs(&proofs).unwrap();
+        let separate: usize = proofs.iter().map(|p| p.proof.len()).sum();
+        assert!(multiproof.nodes.len() < separate);
+
+        let keys_values: Vec<([u8; 32], Vec<u8>)> = proofs
+            .iter()
+            .map(|p| (p.key.clone().try_into().unwrap(), p.value.clone()))
+            .collect();
+        assert!(multiproof.verify_all(&root, &keys_values).unwrap());
+        // a subset of the slots verifies on its own
+        assert!(multiproof.verify_all(&root, &keys_values[4..]).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_wrong_values_and_unknown_slots() {
+        let (root, proofs) = storage_trie(&[0, 1, 200]);
+        let multiproof = CompactStorageMultiProof::from_storage_proofs(&proofs).unwrap();
+
+        assert!(!multiproof
+            .verify_all(&root, &[(slot(1), value(2))])
+            .unwrap());
+        // the absent slot cannot be claimed to hold a value
+        assert!(!multiproof
+            .verify_all(&root, &[(slot(200), value(0))])
+            .unwrap());
+        assert!(!multiproof
+            .verify_all(&[0u8; 32], &[(slot(0), value(0))])
+            .unwrap());
+        assert!(multiproof
+            .verify_all(&root, &[(slot(5), value(5))])
+            .is_err());
+        assert!(multiproof.verify_all(&root[..31], &[]).is_err());
+
+        let mut dangling = multiproof.clone();
+        dangling.paths[2].push(multiproof.nodes.len() as u32);
+        assert!(dangling
+            .verify_all(&root, &[(slot(200), Vec::new())])
+            .is_err());
+    }
+
+    #[test]
+    fn test_rejects_truncated_exclusion_and_empty_input() {
+        let (root, proofs) = storage_trie(&[0, 1, 200]);
+        let multiproof = CompactStorageMultiProof::from_storage_proofs(&proofs).unwrap();
+
+        // a path holding only the root cannot prove that a written slot is zero
+        let mut truncated = multiproof.clone();
+        truncated.paths[1].truncate(1);
+        assert!(truncated
+            .verify_all(&root, &[(slot(1), Vec::new())])
+            .is_err());
+        assert!(multiproof.verify_all(&root, &[]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_slots() {
+        let (_, proofs) = storage_trie(&[3, 3]);
+        assert!(CompactStorageMultiProof::from_storage_proofs(&proofs).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::{vec, vec::Vec};
+    use common::merkle::types::MerkleVerifiable;
+
+    use crate::{
+        merkle_lib::{
+            digest_keccak,
+            types::{
+                EthereumAccountProof, EthereumProofType, EthereumReceiptProof, EthereumSimpleProof,
+                EthereumStorageProof, EthereumTransactionProof, KeyEncoding, TrieKeyEncoding,
+            },
+        },
+        mock::{combined_proof_for, storage_proof_for},
+    };
+
+    #[test]
+    fn test_verify_heterogeneous_proofs() {
+        let slot = [0x01; 32];
+        let value = [0x82, 0x04, 0xd2];
+        let (combined_proof, state_root) = combined_proof_for(&[0xAA; 20], &slot, &value);
+        let (storage_proof, storage_root) = storage_proof_for(&slot, &value);
+
+        let proofs: Vec<(EthereumProofType, Vec<u8>)> = vec![
+            (
+                combined_proof.account_proof.clone().into(),
+                state_root.clone(),
+            ),
+            (storage_proof.into(), storage_root),
+            (combined_proof.clone().into(), state_root.clone()),
+            (
+                EthereumSimpleProof::from_combined_proof(combined_proof).into(),
+                state_root,
+            ),
+        ];
+        for (proof, root) in &proofs {
+            assert!(proof.verify(root).unwrap());
+        }
+        assert!(matches!(proofs[1].0, EthereumProofType::Storage(_)));
+    }
+
+    #[test]
+    fn test_key_encodings() {
+        assert_eq!(EthereumAccountProof::KEY_ENCODING, KeyEncoding::Keccak256);
+        assert_eq!(EthereumStorageProof::KEY_ENCODING, KeyEncoding::Keccak256);
+        assert_eq!(EthereumReceiptProof::KEY_ENCODING, KeyEncoding::Raw);
+        assert_eq!(EthereumTransactionProof::KEY_ENCODING, KeyEncoding::Raw);
+
+        let key = [0x81, 0x80];
+        assert_eq!(
+            KeyEncoding::Keccak256.path(&key),
+            nybbles::Nibbles::unpack(digest_keccak(&key))
+        );
+        assert_eq!(
+            KeyEncoding::Raw.path(&key).to_vec(),
+            vec![0x8, 0x1, 0x8, 0x0]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        merkle_lib::{
+            receipt::{
+                recompute_receipts_root, PartialCoverage, ReceiptFields, ReceiptLog, ReceiptType,
+            },
+            types::EthereumReceiptProof,
+        },
+        timewave_rlp::{self, encode_raw_list},
+        timewave_trie::builder::TrieBuilder,
+    };
+
+    fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+        timewave_rlp::encode(bytes)
+    }
+
+    /// Encodes a successful receipt with one log, prefixed with `tx_type` unless it is 0.
+    fn receipt(tx_type: u8, cumulative_gas_used: u64) -> Vec<u8> {
+        let log = encode_raw_list(&[
+            encode_bytes(&[0xAA; 20]),
+            encode_raw_list(&[encode_bytes(&[0x11; 32]), encode_bytes(&[0x22; 32])]),
+            encode_bytes(&[0x01, 0x02, 0x03]),
+        ]);
+        let receipt = encode_raw_list(&[
+            timewave_rlp::encode(1u8),
+            timewave_rlp::encode(cumulative_gas_used),
+            encode_bytes(&[0u8; 256]),
+            encode_raw_list(&[log]),
+        ]);
+        match tx_type {
+            0 => receipt,
+            _ => [vec![tx_type], receipt].concat(),
+        }
+    }
+
+    fn receipts_trie(receipts: &[Vec<u8>]) -> TrieBuilder {
+        let mut trie = TrieBuilder::new();
+        for (index, receipt) in receipts.iter().enumerate() {
+            trie.insert(&timewave_rlp::encode(index), receipt.clone())
+                .unwrap();
+        }
+        trie
+    }
+
+    #[test]
+    fn test_decode_receipt() {
+        let legacy = ReceiptFields::decode(&receipt(0, 21_000)).unwrap();
+        assert_eq!(legacy.tx_type, ReceiptType::Legacy);
+        assert!(legacy.status);
+        assert_eq!(legacy.cumulative_gas_used, 21_000);
+        assert_eq!(
+            legacy.logs,
+            vec![ReceiptLog {
+                address: [0xAA; 20],
+                topics: vec![[0x11; 32], [0x22; 32]],
+                data: vec![0x01, 0x02, 0x03],
+            }]
+        );
+
+        let typed = ReceiptFields::decode(&receipt(2, 21_000)).unwrap();
+        assert_eq!(typed.tx_type, ReceiptType::Eip1559);
+        assert_eq!(typed.logs, legacy.logs);
+
+        assert!(ReceiptFields::decode(&receipt(0x05, 21_000)).is_err());
+        assert!(ReceiptFields::decode(&[]).is_err());
+    }
+
+    #[test]
+    fn test_verify_for_index() {
+        let receipts: Vec<Vec<u8>> = (0..20).map(|i| receipt(2, 21_000 * (i + 1))).collect();
+        let trie = receipts_trie(&receipts);
+        let root = trie.root();
+
+        let key = timewave_rlp::encode(7u32);
+        let proof = EthereumReceiptProof::new(trie.proof(&key), key, receipts[7].clone());
+        let fields = proof.verify_for_index(&root, 7).unwrap();
+        assert_eq!(fields.cumulative_gas_used, 21_000 * 8);
+
+        // the proof is valid, but not for the claimed index
+        assert!(proof.verify_for_index(&root, 8).is_err());
+        assert!(proof.verify_for_index(&[0u8; 32], 7).is_err());
+    }
+
+    #[test]
+    fn test_verify_get() {
+        let receipts: Vec<Vec<u8>> = (0..3).map(|i| receipt(0, 21_000 * (i + 1))).collect();
+        let trie = receipts_trie(&receipts);
+        let key = timewave_rlp::encode(1u32);
+        let proof = EthereumReceiptProof::new(trie.proof(&key), key, receipts[1].clone());
+        assert_eq!(proof.verify_get(&trie.root()).unwrap(), receipts[1]);
+
+        let mut tampered = proof.clone();
+        tampered.value = receipts[2].clone();
+        assert!(tampered.verify_get(&trie.root()).is_err());
+    }
+
+    #[test]
+    fn test_receipt_type_and_status() {
+        let proof = |value: Vec<u8>| EthereumReceiptProof::new(vec![], vec![0x80], value);
+        for (tx_type, expected) in [
+            (0x00, ReceiptType::Legacy),
+            (0x01, ReceiptType::Eip2930),
+            (0x02, ReceiptType::Eip1559),
+            (0x03, ReceiptType::Eip4844),
+            (0x04, ReceiptType::Eip7702),
+        ] {
+            let proof = proof(receipt(tx_type, 21_000));
+            assert_eq!(proof.receipt_type().unwrap(), expected);
+            assert_eq!(expected.type_byte(), tx_type);
+            assert!(proof.status().unwrap());
+        }
+
+        let failed = encode_raw_list(&[
+            encode_bytes(&[]),
+            timewave_rlp::encode(21_000u64),
+            encode_bytes(&[0u8; 256]),
+            encode_raw_list::<&[u8]>(&[]),
+        ]);
+        assert!(!proof([vec![0x02], failed].concat()).status().unwrap());
+
+        let unknown = proof(receipt(0x7f, 21_000));
+        assert!(unknown.receipt_type().is_err());
+        assert!(unknown.status().is_err());
+
+        let pre_byzantium = encode_raw_list(&[
+            encode_bytes(&[0x11; 32]),
+            timewave_rlp::encode(21_000u64),
+            encode_bytes(&[0u8; 256]),
+            encode_raw_list::<&[u8]>(&[]),
+        ]);
+        assert!(proof(pre_byzantium).status().is_err());
+    }
+
+    #[test]
+    fn test_recompute_receipts_root() {
+        let receipts: Vec<Vec<u8>> = (0..20).map(|i| receipt(2, 21_000 * (i + 1))).collect();
+        let trie = receipts_trie(&receipts);
+        let proof_of = |index: usize| {
+            let key = timewave_rlp::encode(index);
+            EthereumReceiptProof::new(trie.proof(&key), key, receipts[index].clone())
+        };
+        let proofs: Vec<EthereumReceiptProof> = (0..receipts.len()).map(proof_of).collect();
+        assert_eq!(recompute_receipts_root(&proofs).unwrap(), trie.root());
+
+        let partial = recompute_receipts_root(&proofs[..5]).unwrap_err();
+        assert!(partial.downcast_ref::<PartialCoverage>().is_some());
+
+        let other_block = receipts_trie(&receipts[..3]);
+        let key = timewave_rlp::encode(0usize);
+        let mixed = [
+            proofs[0].clone(),
+            EthereumReceiptProof::new(other_block.proof(&key), key, receipts[0].clone()),
+        ];
+        assert!(recompute_receipts_root(&mixed).is_err());
+        assert!(recompute_receipts_root(&[]).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(feature = "no-zkvm")]
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+    use alloy_sol_types::SolValue;
+    use common::merkle::types::{MerkleClient, MerkleVerifiable};
+
+    use crate::{
+        ethereum_rpc::{
+            replay::{ProofSource, RecordingClient, ReplayClient},
+            rpc::EvmMerkleRpcClient,
+        },
+        merkle_lib::{
+            digest_keccak,
+            tests::defaults::constants::{
+                read_ethereum_vault_balances_storage_key, read_ethereum_vault_contract_address,
+                read_sepolia_default_account_address, read_sepolia_height, read_sepolia_url,
+            },
+            types::{EthereumAccount, EthereumSimpleProof},
+            RlpDecodable,
+        },
+    };
+
+    // the stored fixture is synthetic: its responses are built from offline tries
+    // in the shape of a recording, not recorded from Sepolia, until it is
+    // re-recorded with `record_vault_fixture`
+    const VAULT_FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/merkle_lib/tests/data/vault_replay.json"
+    );
+
+    /// The storage key of `account`'s entry in the vault's balances mapping at slot 0.
+    fn balance_key(account: &str) -> String {
+        let address: Address = account.parse().unwrap();
+        hex::encode(digest_keccak(&(address, U256::from(0)).abi_encode()))
+    }
+
+    async fn assert_vault_balance_proof(
+        source: &impl ProofSource,
+        vault: &str,
+        account: &str,
+        height: u64,
+    ) {
+        let state_root = source.state_root_at(height).await.unwrap();
+        let combined_proof = source
+            .combined_proof(&balance_key(account), vault, height)
+            .await
+            .unwrap();
+        let account_decoded = combined_proof
+            .account_proof
+            .verify_and_decode(&state_root)
+            .unwrap();
+        assert!(combined_proof
+            .storage_proof
+            .verify(&account_decoded.storage_root)
+            .unwrap());
+    }
+
+    async fn assert_vault_shares_proof(
+        source: &impl ProofSource,
+        vault: &str,
+        shares_key: &str,
+        height: u64,
+    ) {
+        let state_root = source.state_root_at(height).await.unwrap();
+        let combined_proof = source
+            .combined_proof(shares_key, vault, height)
+            .await
+            .unwrap();
+        assert!(combined_proof.verify(&state_root).unwrap());
+        let account_decoded =
+            EthereumAccount::rlp_decode(&combined_proof.account_proof.value).unwrap();
+        let storage_proof = source
+            .storage_proof(shares_key, vault, height)
+            .await
+            .unwrap();
+        assert!(storage_proof.verify(&account_decoded.storage_root).unwrap());
+        let simple_proof = EthereumSimpleProof::from_combined_proof(combined_proof);
+        assert!(simple_proof.verify(&state_root).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_vault_proofs_from_synthetic_replay() {
+        let client = ReplayClient::from_file(VAULT_FIXTURE).unwrap();
+        let fixture = &client.fixture;
+        let vault = fixture.param("vault_address").unwrap();
+        let height = fixture.param("height").unwrap().parse().unwrap();
+        assert_vault_balance_proof(
+            &client,
+            vault,
+            fixture.param("account_address").unwrap(),
+            height,
+        )
+        .await;
+        assert_vault_shares_proof(
+            &client,
+            vault,
+            fixture.param("balances_storage_key").unwrap(),
+            height,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_without_recording() {
+        let client = ReplayClient::from_file(VAULT_FIXTURE).unwrap();
+        let vault = client.fixture.param("vault_address").unwrap();
+        let error = client
+            .get_proof(&hex::encode([0u8; 32]), vault, 1)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().starts_with("No recorded response for"));
+        assert!(client.fixture.param("missing").is_err());
+    }
+
+    // records the fixture replayed by `test_vault_proofs_from_synthetic_replay`
+    // from Sepolia, replacing the synthetic one, run with `cargo test --features no-zkvm record_vault_fixture -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn record_vault_fixture() {
+        let client = RecordingClient::new(EvmMerkleRpcClient {
+            rpc_url: read_sepolia_url(),
+        });
+        let vault = read_ethereum_vault_contract_address();
+        let account = read_sepolia_default_account_address();
+        let shares_key = read_ethereum_vault_balances_storage_key();
+        let height = read_sepolia_height().await.unwrap();
+        client.note("vault_address", &vault);
+        client.note("account_address", &account);
+        client.note("balances_storage_key", &shares_key);
+        client.note("height", &height.to_string());
+        assert_vault_balance_proof(&client, &vault, &account, height).await;
+        assert_vault_shares_proof(&client, &vault, &shares_key, height).await;
+        client.fixture().save(VAULT_FIXTURE).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        merkle_lib::root::{EthStateRoot, StateRootVerifiable},
+        mock::combined_proof_for,
+    };
+
+    #[test]
+    fn test_verify_state_root() {
+        let (combined_proof, state_root) =
+            combined_proof_for(&[0xAA; 20], &[0x01; 32], &[0x82, 0x04, 0xd2]);
+        let root = EthStateRoot::try_from(state_root.as_slice()).unwrap();
+        assert!(combined_proof.verify_state_root(&root).unwrap());
+        assert!(combined_proof
+            .account_proof
+            .verify_state_root(&root)
+            .unwrap());
+        assert!(combined_proof
+            .verify_state_root(&EthStateRoot::from([0u8; 32]))
+            .is_err());
+        assert!(EthStateRoot::try_from(&state_root[1..]).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(feature = "no-zkvm")]
+#[cfg(test)]
+mod tests {
+    use common::merkle::types::MerkleVerifiable;
+
+    use num_bigint::BigUint;
+
+    use crate::{
+        ethereum_rpc::rpc::{
+            combined_proof_from_response, index_trie_proofs, is_pruned_state_error,
+            EvmMerkleRpcClient, StatePruned,
+        },
+        merkle_lib::{
+            erc20::{TokenStorageLayout, MAINNET_USDT},
+            tests::{
+                defaults::constants::{
+                    read_sepolia_default_account_address, read_sepolia_height, read_sepolia_url,
+                },
+                fixtures::{account_rlp, single_leaf_trie},
+            },
+            types::EthereumReceiptProof,
+        },
+        mock::{account_proof_for, EMPTY_CODE_HASH},
+        timewave_rlp,
+    };
+
+    /// Serializes an `eth_getProof` response for the unwritten slot `key` of
+    /// `address`, whose storage only holds `other_key`.
+    ///
+    /// # Returns
+    /// The serialized response and the state root it verifies against
+    fn unwritten_slot_response(
+        address: &[u8; 20],
+        key: &[u8; 32],
+        other_key: &[u8; 32],
+    ) -> (Vec<u8>, Vec<u8>) {
+        let (storage_nodes, storage_root) = single_leaf_trie(other_key, &[0x82, 0x04, 0xd2]);
+        let account = account_rlp(0, 0, &storage_root, &EMPTY_CODE_HASH);
+        let (account_proof, state_root) = account_proof_for(address, &account);
+        let to_hex = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+        let response = serde_json::json!({
+            "address": to_hex(address),
+            "balance": "0x0",
+            "codeHash": to_hex(&EMPTY_CODE_HASH),
+            "nonce": "0x0",
+            "storageHash": to_hex(&storage_root),
+            "accountProof": account_proof.proof.iter().map(|node| to_hex(node)).collect::<Vec<_>>(),
+            "storageProof": [{
+                "key": to_hex(key),
+                "value": "0x0",
+                "proof": storage_nodes.iter().map(|node| to_hex(node)).collect::<Vec<_>>(),
+            }],
+        });
+        (serde_json::to_vec(&response).unwrap(), state_root)
+    }
+
+    #[test]
+    fn test_is_pruned_state_error() {
+        assert!(is_pruned_state_error(
+            "server returned an error response: error code -32000: missing trie node \
+             f1c2e5b0b6d1f4c3 (path ) state 0xf1c2e5b0b6d1f4c3 is not available"
+        ));
+        assert!(is_pruned_state_error(
+            "error code -32000: Historical state not available"
+        ));
+        assert!(!is_pruned_state_error(
+            "error code -32602: invalid argument 0: hex string has length 3"
+        ));
+        let error = anyhow::Error::from(StatePruned { height: 7 });
+        assert_eq!(error.downcast_ref::<StatePruned>().unwrap().height, 7);
+    }
+
+    #[test]
+    fn test_zero_balance_from_response() {
+        let layout = TokenStorageLayout::for_token(&MAINNET_USDT).unwrap();
+        let (response, state_root) = unwritten_slot_response(
+            &MAINNET_USDT,
+            &layout.balance_slot(&[0xAB; 20]),
+            &layout.balance_slot(&[0xCD; 20]),
+        );
+        let proof = combined_proof_from_response(&response, &hex::encode(MAINNET_USDT)).unwrap();
+        assert!(proof.storage_proof.value.is_empty());
+        assert!(proof.verify(&state_root).unwrap());
+        assert_eq!(
+            layout.decode_balance(&proof.storage_proof.value).unwrap(),
+            BigUint::from(0u8)
+        );
+    }
+
+    #[test]
+    fn test_zero_total_supply_from_response() {
+        let usdt = TokenStorageLayout::for_token(&MAINNET_USDT).unwrap();
+        let (response, state_root) = unwritten_slot_response(
+            &MAINNET_USDT,
+            &usdt.supply_slot(),
+            &usdt.balance_slot(&[0xCD; 20]),
+        );
+        let proof = combined_proof_from_response(&response, &hex::encode(MAINNET_USDT)).unwrap();
+        assert!(proof.storage_proof.value.is_empty());
+        assert!(proof.verify(&state_root).unwrap());
+        assert_eq!(
+            usdt.decode_total_supply(&proof.storage_proof.value)
+                .unwrap(),
+            BigUint::from(0u8)
+        );
+    }
+
+    #[test]
+    fn test_index_trie_proofs() {
+        let values: Vec<Vec<u8>> = (0..130u8).map(|i| vec![i; 40]).collect();
+        let indices = [129, 0, 5, 127, 128];
+        let (root, proofs) = index_trie_proofs(&values, &indices);
+        assert_eq!(proofs.len(), indices.len());
+        for (index, proof) in indices.iter().zip(proofs) {
+            let receipt_proof = EthereumReceiptProof::new(
+                proof,
+                timewave_rlp::encode(index),
+                values[*index as usize].clone(),
+            );
+            assert!(receipt_proof.verify(root.as_slice()).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_archive() {
+        let merkle_prover = EvmMerkleRpcClient {
+            rpc_url: read_sepolia_url(),
+        };
+        let sepolia_height = read_sepolia_height().await.unwrap();
+        // the latest state is available on every node
+        assert!(merkle_prover.probe_archive(sepolia_height).await.unwrap());
+        // genesis state is only available on archive nodes
+        merkle_prover.probe_archive(1).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_storage_proofs_over_heights() {
+        let merkle_prover = EvmMerkleRpcClient {
+            rpc_url: read_sepolia_url(),
+        };
+        let sepolia_height = read_sepolia_height().await.unwrap();
+        let heights = [sepolia_height - 2, sepolia_height - 1, sepolia_height];
+        let proofs = merkle_prover
+            .get_storage_proofs_over_heights(
+                &hex::encode([0u8; 32]),
+                &read_sepolia_default_account_address(),
+                &heights,
+            )
+            .await
+            .unwrap();
+        assert_eq!(proofs.len(), heights.len());
+        for ((height, proof), expected) in proofs.iter().zip(heights) {
+            assert_eq!(*height, expected);
+            let state_root = merkle_prover.state_root_at(*height).await.unwrap();
+            assert!(proof.verify(&state_root).unwrap());
+        }
+    }
+}
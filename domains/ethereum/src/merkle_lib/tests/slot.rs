@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use crate::{merkle_lib::slot::SlotDecoder, mock::storage_proof_for, timewave_rlp};
+
+    #[test]
+    fn test_decode_packed_struct() {
+        // struct { uint128 amount; uint64 timestamp; bool flag; } packed into one slot:
+        // amount in bytes 16..32, timestamp in bytes 8..16, flag in byte 7
+        let mut slot = [0u8; 32];
+        slot[16..].copy_from_slice(&1_000_000_000_000_000_000u128.to_be_bytes());
+        slot[8..16].copy_from_slice(&1_700_000_000u64.to_be_bytes());
+        slot[7] = 1;
+
+        // the stored value strips the leading zero bytes
+        let value = timewave_rlp::encode(&slot[7..]);
+        let (proof, storage_root) = storage_proof_for(&[0x05; 32], &value);
+        let proven = proof.verify_for_slot(&storage_root, &[0x05; 32]).unwrap();
+        let decoder = SlotDecoder::from_rlp(&proven).unwrap();
+        assert_eq!(decoder, SlotDecoder::new(slot));
+
+        assert_eq!(
+            decoder.read_uint(0, 128).unwrap(),
+            BigUint::from(1_000_000_000_000_000_000u128)
+        );
+        assert_eq!(
+            decoder.read_uint(128, 64).unwrap(),
+            BigUint::from(1_700_000_000u64)
+        );
+        assert!(decoder.read_bool(192).unwrap());
+        assert!(!decoder.read_bool(200).unwrap());
+    }
+
+    #[test]
+    fn test_decode_address_and_bounds() {
+        // struct { address owner; uint96 nonce; }
+        let mut slot = [0u8; 32];
+        slot[12..].copy_from_slice(&[0xAA; 20]);
+        slot[..12].copy_from_slice(&[0x01; 12]);
+        let decoder = SlotDecoder::new(slot);
+        assert_eq!(decoder.read_address(0).unwrap(), [0xAA; 20]);
+        assert_eq!(
+            decoder.read_uint(160, 96).unwrap(),
+            BigUint::from_bytes_be(&[0x01; 12])
+        );
+
+        assert!(decoder.read_bool(248).unwrap());
+        assert!(decoder.read_bool(0).is_err());
+        assert!(decoder.read_address(160).is_err());
+        assert!(decoder.read_uint(4, 8).is_err());
+        assert!(decoder.read_uint(0, 0).is_err());
+        assert!(decoder.read_uint(0, 264).is_err());
+        assert!(decoder.read_uint(usize::MAX - 7, 16).is_err());
+        assert!(decoder.read_address(usize::MAX - 7).is_err());
+    }
+}
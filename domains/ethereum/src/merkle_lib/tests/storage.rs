@@ -0,0 +1,441 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::{string::ToString, vec};
+
+    use common::merkle::types::MerkleVerifiable;
+
+    use crate::{
+        merkle_lib::{
+            digest_keccak,
+            tests::fixtures::{account_rlp, branch_trie, single_leaf_trie},
+            types::{
+                EthereumAccessListProof, EthereumAccountProof, EthereumCombinedProof,
+                EthereumSimpleProof, EthereumStorageProof,
+            },
+        },
+        mock::combined_proof_for,
+    };
+
+    #[test]
+    fn test_verify_for_slot_returns_value() {
+        let slot = [0u8; 32];
+        let (nodes, root) = single_leaf_trie(&slot, &[0x2a]);
+        let proof = EthereumStorageProof::new(nodes, slot.to_vec(), vec![0x2a]);
+        assert_eq!(proof.verify_for_slot(&root, &[0x00]).unwrap(), vec![0x2a]);
+    }
+
+    #[test]
+    fn test_verify_for_slot_rejects_other_slot() {
+        let slot = [0u8; 32];
+        let (nodes, root) = single_leaf_trie(&slot, &[0x2a]);
+        let proof = EthereumStorageProof::new(nodes, slot.to_vec(), vec![0x2a]);
+        assert!(proof.verify_for_slot(&root, &[0x01]).is_err());
+        assert!(proof.verify_for_slot(&root, &[0u8; 33]).is_err());
+    }
+
+    #[test]
+    fn test_short_slot_key_verifies_like_padded_key() {
+        let slot = [0u8; 32];
+        let (nodes, root) = single_leaf_trie(&slot, &[0x2a]);
+        let short = EthereumStorageProof::new(nodes.clone(), vec![0x00], vec![0x2a]);
+        let padded = EthereumStorageProof::new(nodes, slot.to_vec(), vec![0x2a]);
+        assert!(short.verify(&root).unwrap());
+        assert!(padded.verify(&root).unwrap());
+        assert_eq!(short.verify_for_slot(&root, &slot).unwrap(), vec![0x2a]);
+        assert_eq!(padded.verify_for_slot(&root, &[0x00]).unwrap(), vec![0x2a]);
+    }
+
+    #[test]
+    fn test_verify_storage_proofs_shares_account() {
+        let (slot_a, slot_b) = ([0u8; 32], {
+            let mut slot = [0u8; 32];
+            slot[31] = 1;
+            slot
+        });
+        let (storage_nodes, storage_root) = branch_trie(&[(&slot_a, &[0x2a]), (&slot_b, &[0x07])]);
+        let address = [0x11u8; 20];
+        let account = account_rlp(1, 100, &storage_root, &[0u8; 32]);
+        let (account_nodes, state_root) = single_leaf_trie(&address, &account);
+
+        let account_proof = EthereumAccountProof::new(account_nodes, address.to_vec(), account);
+        let storage_proofs = [
+            EthereumStorageProof::new(storage_nodes[0].clone(), slot_a.to_vec(), vec![0x2a]),
+            EthereumStorageProof::new(storage_nodes[1].clone(), slot_b.to_vec(), vec![0x07]),
+        ];
+        assert!(storage_proofs[0]
+            .verify_against_storage_root(&storage_root)
+            .unwrap());
+        assert!(account_proof
+            .verify_storage_proofs(&state_root, &storage_proofs)
+            .unwrap());
+
+        let wrong_value =
+            EthereumStorageProof::new(storage_nodes[1].clone(), slot_b.to_vec(), vec![0x08]);
+        assert!(!account_proof
+            .verify_storage_proofs(&state_root, &[storage_proofs[0].clone(), wrong_value])
+            .unwrap());
+    }
+
+    #[test]
+    fn test_verify_slots_through_one_decoded_account() {
+        use alloc::vec::Vec;
+
+        // ten slots whose hashed paths leave the root branch at distinct children
+        let mut slots: Vec<[u8; 32]> = Vec::new();
+        for byte in 0u8..=255 {
+            let nibble = digest_keccak(&[byte; 32])[0] >> 4;
+            if slots.len() < 10
+                && slots
+                    .iter()
+                    .all(|slot| digest_keccak(slot)[0] >> 4 != nibble)
+            {
+                slots.push([byte; 32]);
+            }
+        }
+        assert_eq!(slots.len(), 10);
+        let entries: Vec<(&[u8], &[u8])> = slots
+            .iter()
+            .map(|slot| (slot.as_slice(), [0x2a].as_slice()))
+            .collect();
+        let (storage_nodes, storage_root) = branch_trie(&entries);
+        let address = [0x11u8; 20];
+        let account = account_rlp(1, 100, &storage_root, &[0u8; 32]);
+        let (account_nodes, state_root) = single_leaf_trie(&address, &account);
+        let account_proof = EthereumAccountProof::new(account_nodes, address.to_vec(), account);
+        let storage_proofs: Vec<EthereumStorageProof> = slots
+            .iter()
+            .zip(storage_nodes)
+            .map(|(slot, nodes)| EthereumStorageProof::new(nodes, slot.to_vec(), vec![0x2a]))
+            .collect();
+
+        // the account is verified and decoded once, every slot checks against its storage root
+        let account = account_proof.verify_and_decode(&state_root).unwrap();
+        for storage_proof in &storage_proofs {
+            assert!(storage_proof
+                .verify_against_storage_root(&account.storage_root)
+                .unwrap());
+        }
+        assert!(account_proof
+            .verify_storage_proofs(&state_root, &storage_proofs)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_identical_proofs_dedup() {
+        extern crate std;
+        use std::collections::HashSet;
+
+        let slot = [0u8; 32];
+        let (nodes, _) = single_leaf_trie(&slot, &[0x2a]);
+        let proof = EthereumStorageProof::new(nodes.clone(), slot.to_vec(), vec![0x2a]);
+        let duplicate = EthereumStorageProof::new(nodes.clone(), slot.to_vec(), vec![0x2a]);
+        let other_value = EthereumStorageProof::new(nodes, slot.to_vec(), vec![0x2b]);
+        assert_eq!(proof, duplicate);
+        // the same proof read from a legacy, unversioned serialization
+        let mut legacy = proof.clone();
+        legacy.version = 0;
+        assert_eq!(proof, legacy);
+
+        let unique: HashSet<_> = [proof, duplicate, legacy, other_value]
+            .into_iter()
+            .collect();
+        assert_eq!(unique.len(), 2);
+    }
+
+    #[test]
+    fn test_simple_proof_root() {
+        use common::merkle::types::HasRoot;
+
+        let (combined_proof, state_root) =
+            combined_proof_for(&[0xAA; 20], &[0x01; 32], &[0x82, 0x04, 0xd2]);
+        let simple_proof = EthereumSimpleProof::from_combined_proof(combined_proof);
+        assert_eq!(simple_proof.root().unwrap(), state_root);
+
+        // without account nodes there is no state root, even though storage nodes follow
+        let mut storage_only = simple_proof.clone();
+        storage_only.proof[0] = 0u16.to_be_bytes().to_vec();
+        assert!(storage_only.root().is_err());
+    }
+
+    #[test]
+    fn test_assert_same_root() {
+        use common::merkle::types::assert_same_root;
+
+        let (slot_a, slot_b) = ([0u8; 32], [1u8; 32]);
+        let (nodes, _) = branch_trie(&[(&slot_a, &[0x2a]), (&slot_b, &[0x07])]);
+        let balance = EthereumStorageProof::new(nodes[0].clone(), slot_a.to_vec(), vec![0x2a]);
+        let mint = EthereumStorageProof::new(nodes[1].clone(), slot_b.to_vec(), vec![0x07]);
+        assert_same_root(&[&balance, &mint]).unwrap();
+
+        // the same slot proven from a different block
+        let (other_nodes, _) = single_leaf_trie(&slot_b, &[0x08]);
+        let stale_mint = EthereumStorageProof::new(other_nodes, slot_b.to_vec(), vec![0x08]);
+        assert!(assert_same_root(&[&balance, &stale_mint]).is_err());
+    }
+
+    #[test]
+    fn test_access_list_proof_with_unwritten_slot() {
+        let first_nibble = |slot: &[u8; 32]| digest_keccak(slot)[0] >> 4;
+        let (slot_a, slot_b) = ([0x0a; 32], [0x0b; 32]);
+        // a slot whose hashed path leaves the root branch at an empty child
+        let unwritten = (0u8..=255)
+            .map(|byte| [byte; 32])
+            .find(|slot| {
+                ![first_nibble(&slot_a), first_nibble(&slot_b)].contains(&first_nibble(slot))
+            })
+            .unwrap();
+        let (storage_nodes, storage_root) = branch_trie(&[(&slot_a, &[0x2a]), (&slot_b, &[0x07])]);
+        let address = [0x11u8; 20];
+        let account = account_rlp(1, 100, &storage_root, &[0u8; 32]);
+        let (account_nodes, state_root) = single_leaf_trie(&address, &account);
+        let account_proof = EthereumAccountProof::new(account_nodes, address.to_vec(), account);
+
+        let proof = EthereumAccessListProof::new(
+            account_proof.clone(),
+            vec![
+                EthereumStorageProof::new(storage_nodes[0].clone(), slot_a.to_vec(), vec![0x2a]),
+                EthereumStorageProof::new(storage_nodes[1].clone(), slot_b.to_vec(), vec![0x07]),
+                EthereumStorageProof::new(
+                    vec![storage_nodes[0][0].clone()],
+                    unwritten.to_vec(),
+                    vec![],
+                ),
+            ],
+        );
+        assert!(proof.verify(&state_root).unwrap());
+
+        // an exclusion proof cannot hide a slot that holds a value
+        let hidden = EthereumAccessListProof::new(
+            account_proof,
+            vec![EthereumStorageProof::new(
+                storage_nodes[0].clone(),
+                slot_a.to_vec(),
+                vec![],
+            )],
+        );
+        assert!(!hidden.verify(&state_root).unwrap());
+    }
+
+    #[test]
+    fn test_forged_zero_slot_rejected() {
+        let (slot_a, slot_b) = ([0x0a; 32], [0x0b; 32]);
+        let (storage_nodes, storage_root) =
+            branch_trie(&[(&slot_a, &[0x82, 0x04, 0x06]), (&slot_b, &[0x07])]);
+        let address = [0x11u8; 20];
+        let account = account_rlp(1, 100, &storage_root, &[0u8; 32]);
+        let (account_nodes, state_root) = single_leaf_trie(&address, &account);
+        let account_proof = EthereumAccountProof::new(account_nodes, address.to_vec(), account);
+
+        // claims slot_a is zero with only the root branch, which references the
+        // leaf holding 0x820406 by its hash
+        let forged =
+            EthereumStorageProof::new(vec![storage_nodes[0][0].clone()], slot_a.to_vec(), vec![]);
+        let combined = EthereumCombinedProof::new(account_proof.clone(), forged.clone());
+        assert!(combined.verify(&state_root).is_err());
+        let access_list = EthereumAccessListProof::new(account_proof, vec![forged]);
+        assert!(access_list.verify(&state_root).is_err());
+    }
+
+    #[test]
+    fn test_verify_strict_checks_leaf_path() {
+        let slot_a = [0u8; 32];
+        let slot_b = (1u8..=255)
+            .map(|byte| [byte; 32])
+            .find(|slot| digest_keccak(slot)[0] >> 4 != digest_keccak(&slot_a)[0] >> 4)
+            .unwrap();
+        let (proofs, root) = branch_trie(&[(&slot_a, &[0x2a]), (&slot_b, &[0x2b])]);
+        let proof = EthereumStorageProof::new(proofs[0].clone(), slot_a.to_vec(), vec![0x2a]);
+        proof.assert_leaf_path().unwrap();
+        assert!(proof.verify_strict(&root).unwrap());
+
+        // the same leaf claimed for another slot
+        let other_slot = EthereumStorageProof::new(proofs[0].clone(), slot_b.to_vec(), vec![0x2a]);
+        assert!(other_slot.assert_leaf_path().is_err());
+        assert!(other_slot.verify_strict(&root).is_err());
+
+        // a proof that stops at the branch never reaches a leaf
+        let truncated =
+            EthereumStorageProof::new(vec![proofs[0][0].clone()], slot_a.to_vec(), vec![0x2a]);
+        assert!(truncated.assert_leaf_path().is_err());
+
+        // a leaf with the right value at the wrong path
+        let (nodes, _) = single_leaf_trie(&slot_b, &[0x2a]);
+        let forged = EthereumStorageProof::new(nodes, slot_a.to_vec(), vec![0x2a]);
+        assert!(forged.assert_leaf_path().is_err());
+    }
+
+    #[test]
+    fn test_verify_raw_trie_proof() {
+        use crate::{
+            merkle_lib::verify_raw_trie_proof, mock::storage_proof_for,
+            timewave_trie::builder::TrieBuilder,
+        };
+
+        // a trie keyed by unhashed strings
+        let mut trie = TrieBuilder::new();
+        trie.insert(b"ethereum", vec![0x11; 40]).unwrap();
+        trie.insert(b"neutron", vec![0x22; 40]).unwrap();
+        let root = trie.root();
+        let nodes = trie.proof(b"ethereum");
+        assert!(
+            verify_raw_trie_proof(&root, b"ethereum", Some(&[0x11; 40]), &nodes, false).unwrap()
+        );
+        assert!(
+            !verify_raw_trie_proof(&root, b"ethereum", Some(&[0x22; 40]), &nodes, false).unwrap()
+        );
+        assert!(
+            !verify_raw_trie_proof(&[0u8; 32], b"ethereum", Some(&[0x11; 40]), &nodes, false)
+                .unwrap()
+        );
+        let absent = trie.proof(b"cosmos");
+        assert!(verify_raw_trie_proof(&root, b"cosmos", None, &absent, false).unwrap());
+        // the root alone leaves the key's subtrie unresolved, proving no absence
+        assert!(nodes.len() > 1);
+        assert!(verify_raw_trie_proof(&root, b"ethereum", None, &nodes[..1], false).is_err());
+        assert!(verify_raw_trie_proof(&root[..31], b"ethereum", None, &nodes, false).is_err());
+
+        // a storage trie, keyed by the hash of the slot
+        let (proof, root) = storage_proof_for(&[0x01; 32], &[0x2a]);
+        assert!(
+            verify_raw_trie_proof(&root, &[0x01; 32], Some(&[0x2a]), &proof.proof, true).unwrap()
+        );
+        assert!(
+            !verify_raw_trie_proof(&root, &[0x01; 32], Some(&[0x2a]), &proof.proof, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_value_bytes_and_u256() {
+        use crate::{mock::storage_proof_for, timewave_rlp};
+        use num_bigint::BigUint;
+
+        let (proof, _) = storage_proof_for(&[0x01; 32], &timewave_rlp::encode(&[0x04, 0xd2][..]));
+        assert_eq!(proof.value_bytes().unwrap(), vec![0x04, 0xd2]);
+        assert_eq!(proof.value_as_u256().unwrap(), BigUint::from(1234u32));
+
+        let word = [0xff; 32];
+        let (proof, _) = storage_proof_for(&[0x01; 32], &timewave_rlp::encode(&word[..]));
+        assert_eq!(proof.value_as_u256().unwrap().to_bytes_be(), word);
+
+        // a 40-byte value is not an integer slot
+        let (proof, _) = storage_proof_for(&[0x01; 32], &timewave_rlp::encode(&[0xab; 40][..]));
+        assert_eq!(proof.value_bytes().unwrap(), vec![0xab; 40]);
+        assert!(proof.value_as_u256().is_err());
+
+        let empty = EthereumStorageProof::new(vec![], vec![0x01], vec![]);
+        assert!(empty.value_bytes().unwrap().is_empty());
+        assert_eq!(empty.value_as_u256().unwrap(), BigUint::from(0u8));
+        let trailing = EthereumStorageProof::new(vec![], vec![0x01], vec![0x81, 0xff, 0x00]);
+        assert!(trailing.value_bytes().is_err());
+    }
+
+    #[test]
+    fn test_empty_trie_proof() {
+        use crate::timewave_trie::constants::EMPTY_ROOT_HASH_BYTES;
+
+        let slot = [0x01u8; 32];
+        let empty = EthereumStorageProof::new(vec![vec![0x80]], slot.to_vec(), vec![]);
+        // a clean exclusion proof against the empty root
+        assert!(empty.verify(&EMPTY_ROOT_HASH_BYTES).unwrap());
+        assert!(EthereumStorageProof::new(vec![], slot.to_vec(), vec![])
+            .verify(&EMPTY_ROOT_HASH_BYTES)
+            .unwrap());
+        // the empty trie holds no value
+        let claimed = EthereumStorageProof::new(vec![vec![0x80]], slot.to_vec(), vec![0x2a]);
+        assert!(!claimed.verify(&EMPTY_ROOT_HASH_BYTES).unwrap());
+
+        let (_, root) = single_leaf_trie(&slot, &[0x2a]);
+        let error = empty.verify(&root).unwrap_err();
+        assert!(error.to_string().starts_with("Root mismatch"));
+        let account = EthereumAccountProof::new(vec![vec![0x80]], vec![0xAA; 20], vec![]);
+        assert!(account.verify(&root).is_err());
+    }
+
+    #[test]
+    fn test_empty_proof_error() {
+        use crate::merkle_lib::types::{EmptyProof, EthereumReceiptProof};
+
+        let (_, root) = single_leaf_trie(&[0x01; 32], &[0x2a]);
+        let errors = [
+            EthereumStorageProof::new(vec![], vec![0x01; 32], vec![0x2a]).verify(&root),
+            EthereumStorageProof::new(vec![], vec![0x01; 32], vec![0x2a])
+                .verify_constant_time(&root),
+            EthereumAccountProof::new(vec![], vec![0xAA; 20], vec![0x2a]).verify(&root),
+            EthereumReceiptProof::new(vec![], vec![0x80], vec![0x2a]).verify(&root),
+        ];
+        for error in errors {
+            let error = error.unwrap_err();
+            assert_eq!(error.downcast_ref::<EmptyProof>(), Some(&EmptyProof));
+            assert_eq!(error.to_string(), "Proof holds no nodes");
+        }
+    }
+
+    #[test]
+    fn test_value_encoding_is_not_part_of_equality() {
+        extern crate std;
+        use crate::{merkle_lib::types::ValueEncoding, mock::storage_proof_for};
+        use std::collections::HashSet;
+
+        let (proof, _) = storage_proof_for(&[0x01; 32], &[0x82, 0x04, 0xd2]);
+        let mut word = [0u8; 32];
+        word[30..].copy_from_slice(&[0x04, 0xd2]);
+        let raw = EthereumStorageProof::new(proof.proof.clone(), proof.key.clone(), word.to_vec())
+            .with_value_encoding(ValueEncoding::Raw);
+        assert_eq!(proof, raw);
+        // the same bytes under another encoding are another trie value
+        let double_encoded = proof.clone().with_value_encoding(ValueEncoding::Raw);
+        assert_ne!(proof, double_encoded);
+
+        let unique: HashSet<_> = [proof, raw, double_encoded].into_iter().collect();
+        assert_eq!(unique.len(), 2);
+    }
+
+    #[test]
+    fn test_value_encoding_catches_double_encoding() {
+        use crate::{merkle_lib::types::ValueEncoding, mock::storage_proof_for};
+
+        // 1234 is stored in the leaf as the RLP string 0x8204d2
+        let (proof, root) = storage_proof_for(&[0x01; 32], &[0x82, 0x04, 0xd2]);
+        assert!(proof.verify(&root).unwrap());
+
+        let mut word = [0u8; 32];
+        word[30..].copy_from_slice(&[0x04, 0xd2]);
+        let raw = EthereumStorageProof::new(proof.proof.clone(), proof.key.clone(), word.to_vec())
+            .with_value_encoding(ValueEncoding::Raw);
+        assert!(raw.verify(&root).unwrap());
+        assert_eq!(raw.verify_get(&root).unwrap(), proof.value);
+        let deserialized: EthereumStorageProof =
+            serde_json::from_str(&serde_json::to_string(&raw).unwrap()).unwrap();
+        assert_eq!(deserialized, raw);
+
+        // an already encoded value marked raw is encoded twice
+        let double_encoded = proof.clone().with_value_encoding(ValueEncoding::Raw);
+        assert_eq!(double_encoded.encoded_value(), vec![0x83, 0x82, 0x04, 0xd2]);
+        assert!(!double_encoded.verify(&root).unwrap());
+        // and a raw word left as the default encoding is not encoded at all
+        let mut unencoded = raw;
+        unencoded.value_encoding = ValueEncoding::RlpEncoded;
+        assert!(!unencoded.verify(&root).unwrap());
+
+        assert!(ValueEncoding::Raw.encode(&[0u8; 32]).is_empty());
+
+        // a raw zero word claims the slot is absent, which a truncated proof cannot show
+        let (slot_a, slot_b) = ([0x0a; 32], [0x0b; 32]);
+        let (storage_nodes, storage_root) =
+            branch_trie(&[(&slot_a, &[0x82, 0x04, 0xd2]), (&slot_b, &[0x07])]);
+        let address = [0x11u8; 20];
+        let account = account_rlp(1, 100, &storage_root, &[0u8; 32]);
+        let (account_nodes, state_root) = single_leaf_trie(&address, &account);
+        let zero = EthereumStorageProof::new(
+            vec![storage_nodes[0][0].clone()],
+            slot_a.to_vec(),
+            vec![0u8; 32],
+        )
+        .with_value_encoding(ValueEncoding::Raw);
+        let account_proof = EthereumAccountProof::new(account_nodes, address.to_vec(), account);
+        assert!(account_proof
+            .verify_storage_proofs(&state_root, &[zero])
+            .is_err());
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec;
+    use k256::ecdsa::SigningKey;
+
+    use crate::{
+        merkle_lib::{digest_keccak, types::EthereumTransactionProof},
+        timewave_rlp,
+    };
+
+    fn address_of(key: &SigningKey) -> [u8; 20] {
+        let public_key = key.verifying_key().to_encoded_point(false);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&digest_keccak(&public_key.as_bytes()[1..])[12..]);
+        address
+    }
+
+    #[test]
+    fn test_recover_sender_eip155_legacy_transaction() {
+        // the signed example transaction from the EIP-155 specification
+        let tx = hex::decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+             761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+        let key = SigningKey::from_slice(&[0x46; 32]).unwrap();
+        let proof = EthereumTransactionProof::new(vec![], timewave_rlp::encode(0u32), tx);
+        assert_eq!(proof.recover_sender().unwrap(), address_of(&key));
+        assert_eq!(
+            hex::encode(proof.recover_sender().unwrap()),
+            "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+        );
+    }
+
+    #[test]
+    fn test_recover_sender_eip1559_transaction() {
+        let key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+        let unsigned_fields = vec![
+            timewave_rlp::encode(11155111u64),
+            timewave_rlp::encode(3u64),
+            timewave_rlp::encode(1_000_000_000u64),
+            timewave_rlp::encode(30_000_000_000u64),
+            timewave_rlp::encode(21_000u64),
+            timewave_rlp::encode([0x35u8; 20].as_slice()),
+            timewave_rlp::encode(1_000u64),
+            timewave_rlp::encode([].as_slice() as &[u8]),
+            timewave_rlp::encode_raw_list::<&[u8]>(&[]),
+        ];
+        let mut signing_payload = vec![0x02];
+        signing_payload.extend(timewave_rlp::encode_raw_list(&unsigned_fields));
+        let (signature, recovery_id) = key
+            .sign_prehash_recoverable(&digest_keccak(&signing_payload))
+            .unwrap();
+
+        let mut signed_fields = unsigned_fields;
+        signed_fields.push(timewave_rlp::encode(recovery_id.to_byte() as u64));
+        signed_fields.push(timewave_rlp::encode(signature.r().to_bytes().as_slice()));
+        signed_fields.push(timewave_rlp::encode(signature.s().to_bytes().as_slice()));
+        let mut tx = vec![0x02];
+        tx.extend(timewave_rlp::encode_raw_list(&signed_fields));
+
+        let proof = EthereumTransactionProof::new(vec![], timewave_rlp::encode(0u32), tx.clone());
+        assert_eq!(proof.recover_sender().unwrap(), address_of(&key));
+
+        // tampering with the nonce (type, 2-byte list header, 4-byte chain id) changes the sender
+        assert_eq!(tx[7], 0x03);
+        tx[7] = 0x04;
+        let tampered = EthereumTransactionProof::new(vec![], timewave_rlp::encode(0u32), tx);
+        assert_ne!(tampered.recover_sender().ok(), Some(address_of(&key)));
+    }
+
+    #[test]
+    fn test_recover_sender_rejects_unknown_type() {
+        let proof = EthereumTransactionProof::new(vec![], vec![0x80], vec![0x7f, 0xc0]);
+        assert!(proof.recover_sender().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec;
+
+    use crate::{
+        merkle_lib::{
+            transport::RlpTransport,
+            types::{
+                EthereumAccessListProof, EthereumCombinedProof, EthereumProofType,
+                EthereumSimpleProof, EthereumStorageProof, EthereumTransactionProof, ValueEncoding,
+            },
+        },
+        mock::{combined_proof_for, storage_proof_for},
+    };
+    use common::merkle::types::MerkleVerifiable;
+
+    const SLOT: [u8; 32] = [0x01; 32];
+    const VALUE: [u8; 3] = [0x82, 0x04, 0xd2];
+
+    #[test]
+    fn test_round_trip() {
+        let (combined, state_root) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let decoded = EthereumCombinedProof::from_rlp(&combined.to_rlp()).unwrap();
+        assert_eq!(decoded, combined);
+        assert!(decoded.verify(&state_root).unwrap());
+
+        let (storage, _) = storage_proof_for(&SLOT, &VALUE);
+        assert_eq!(
+            EthereumStorageProof::from_rlp(&storage.to_rlp()).unwrap(),
+            storage
+        );
+
+        // a raw value keeps its encoding
+        let mut word = [0u8; 32];
+        word[30..].copy_from_slice(&VALUE[1..]);
+        let raw = EthereumStorageProof::new(storage.proof.clone(), SLOT.to_vec(), word.to_vec())
+            .with_value_encoding(ValueEncoding::Raw);
+        let decoded = EthereumStorageProof::from_rlp(&raw.to_rlp()).unwrap();
+        assert_eq!(decoded, raw);
+        assert_eq!(decoded.encoded_value(), VALUE);
+
+        let access_list = EthereumAccessListProof::new(
+            combined.account_proof.clone(),
+            vec![combined.storage_proof.clone(), storage.clone()],
+        );
+        assert_eq!(
+            EthereumAccessListProof::from_rlp(&access_list.to_rlp()).unwrap(),
+            access_list
+        );
+
+        let transaction =
+            EthereumTransactionProof::new(storage.proof.clone(), vec![0x80], vec![0x02, 0xc0]);
+        for proof in [
+            EthereumProofType::Storage(storage),
+            EthereumProofType::Combined(combined.clone()),
+            EthereumProofType::Simple(EthereumSimpleProof::from_combined_proof(combined.clone())),
+            EthereumProofType::Transaction(transaction),
+        ] {
+            assert_eq!(EthereumProofType::from_rlp(&proof.to_rlp()).unwrap(), proof);
+        }
+    }
+
+    #[test]
+    fn test_encoding_is_compact() {
+        let (combined, _) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let json = serde_json::to_vec(&combined).unwrap();
+        assert!(combined.to_rlp().len() * 3 < json.len());
+    }
+
+    #[test]
+    fn test_rejects_malformed_encodings() {
+        let (combined, _) = combined_proof_for(&[0xAA; 20], &SLOT, &VALUE);
+        let rlp = combined.to_rlp();
+
+        assert!(EthereumCombinedProof::from_rlp(&rlp[..rlp.len() - 1]).is_err());
+        assert!(EthereumCombinedProof::from_rlp(&[rlp.as_slice(), &[0x80]].concat()).is_err());
+        // a single-trie proof is not a combined proof, and vice versa
+        assert!(EthereumCombinedProof::from_rlp(&combined.storage_proof.to_rlp()).is_err());
+        assert!(EthereumStorageProof::from_rlp(&rlp).is_err());
+        assert!(EthereumProofType::from_rlp(&[0xc2, 0x06, 0xc0]).is_err());
+    }
+}
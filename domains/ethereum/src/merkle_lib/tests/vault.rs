@@ -40,11 +40,11 @@ mod tests {
             rpc_url: read_sepolia_url().to_string(),
         };
 
-        let combined_proof = merkle_prover
+        let (_, combined_proof) = merkle_prover
             .get_account_and_storage_proof(
                 &alloy::hex::encode(&keccak_key),
                 &read_ethereum_vault_contract_address(),
-                sepolia_height,
+                Some(sepolia_height),
             )
             .await
             .unwrap();
@@ -68,6 +68,26 @@ mod tests {
             .unwrap());
     }
 
+    #[tokio::test]
+    async fn test_vault_account_verify_and_decode_on_sepolia() {
+        let sepolia_height = read_sepolia_height().await.unwrap();
+        let merkle_prover = EvmMerkleRpcClient {
+            rpc_url: read_sepolia_url().to_string(),
+        };
+        let account_proof = merkle_prover
+            .get_account_proof(&read_ethereum_vault_contract_address(), sepolia_height)
+            .await
+            .unwrap();
+        let state_root = merkle_prover.state_root_at(sepolia_height).await.unwrap();
+
+        let account_decoded = account_proof.verify_and_decode(&state_root).unwrap();
+        assert_eq!(
+            account_decoded,
+            EthereumAccount::rlp_decode(&account_proof.value).unwrap()
+        );
+        assert!(account_proof.verify_and_decode(&[0u8; 32]).is_err());
+    }
+
     #[tokio::test]
     async fn test_vault_contract_shares_on_sepolia() {
         let sepolia_height = read_sepolia_height().await.unwrap();
@@ -78,11 +98,11 @@ mod tests {
             rpc_url: read_sepolia_url().to_string(),
         };
 
-        let combined_proof = merkle_prover
+        let (_, combined_proof) = merkle_prover
             .get_account_and_storage_proof(
                 &alloy::hex::encode(&storage_slot_key),
                 &read_ethereum_vault_contract_address(),
-                sepolia_height,
+                Some(sepolia_height),
             )
             .await
             .unwrap();
@@ -140,6 +160,28 @@ mod tests {
             .unwrap());
     }
 
+    #[tokio::test]
+    async fn test_vault_storage_root_on_sepolia() {
+        let sepolia_height = read_sepolia_height().await.unwrap();
+        let storage_slot_key = hex::decode(read_ethereum_vault_balances_storage_key()).unwrap();
+        let merkle_prover = EvmMerkleRpcClient {
+            rpc_url: read_sepolia_url().to_string(),
+        };
+        let (_, combined_proof) = merkle_prover
+            .get_account_and_storage_proof(
+                &alloy::hex::encode(&storage_slot_key),
+                &read_ethereum_vault_contract_address(),
+                Some(sepolia_height),
+            )
+            .await
+            .unwrap();
+
+        let storage_root = combined_proof.account_proof.storage_root().unwrap();
+        let account_decoded = rlp_decode_bytes(&combined_proof.account_proof.value).unwrap();
+        assert_eq!(storage_root.as_slice(), account_decoded[2].as_ref());
+        assert!(combined_proof.storage_proof.verify(&storage_root).unwrap());
+    }
+
     #[tokio::test]
     async fn test_simple_state_proof() {
         // try to combine account and storage proof
@@ -156,11 +198,11 @@ mod tests {
             .unwrap()
             .unwrap();
 
-        let combined_proof = merkle_prover
+        let (_, combined_proof) = merkle_prover
             .get_account_and_storage_proof(
                 &alloy::hex::encode(&storage_slot_key),
                 &read_ethereum_vault_contract_address(),
-                sepolia_height,
+                Some(sepolia_height),
             )
             .await
             .unwrap();
@@ -171,6 +213,24 @@ mod tests {
             .unwrap());
     }
 
+    #[tokio::test]
+    async fn test_simple_state_proof_at_finalized_height() {
+        let storage_slot_key = hex::decode(read_ethereum_vault_balances_storage_key()).unwrap();
+        let merkle_prover = EvmMerkleRpcClient {
+            rpc_url: read_sepolia_url().to_string(),
+        };
+        let (finalized_height, combined_proof) = merkle_prover
+            .get_account_and_storage_proof(
+                &alloy::hex::encode(&storage_slot_key),
+                &read_ethereum_vault_contract_address(),
+                None,
+            )
+            .await
+            .unwrap();
+        let state_root = merkle_prover.state_root_at(finalized_height).await.unwrap();
+        assert!(combined_proof.verify(&state_root).unwrap());
+    }
+
     // this test needs to be updated manually with a recent root and height
     // because of this it's commented out and should only be used when needed
     /*#[tokio::test]
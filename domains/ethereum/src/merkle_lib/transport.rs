@@ -0,0 +1,234 @@
+//! A compact binary encoding of proofs for transport.
+//!
+//! [`RlpTransport`] encodes a proof with the crate's own RLP codec rather than
+//! serde. A single-trie proof is the list `[[node, ...], key, value]`, with the
+//! nodes as byte strings, which is how Ethereum clients emit proof nodes and
+//! far smaller than the JSON number arrays serde produces for `Vec<u8>`. A
+//! storage proof whose value is given raw appends its [`ValueEncoding`] as a
+//! fourth field. Proofs of several tries nest their parts: `[account, storage]`
+//! for combined proofs and `[account, [storage, ...]]` for access list proofs.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use anyhow::Result;
+
+use super::types::{
+    EthereumAccessListProof, EthereumAccountProof, EthereumCombinedProof, EthereumProofType,
+    EthereumReceiptProof, EthereumSimpleProof, EthereumStorageProof, EthereumTransactionProof,
+    ValueEncoding,
+};
+use crate::timewave_rlp::{self, decode_list, encode_raw_list, Header};
+
+/// Proofs with a canonical RLP encoding for transport.
+pub trait RlpTransport: Sized {
+    /// Encodes the proof as RLP.
+    fn to_rlp(&self) -> Vec<u8>;
+
+    /// Decodes a proof encoded with [`RlpTransport::to_rlp`].
+    ///
+    /// # Errors
+    /// Returns an error if `rlp` is not the encoding of a proof of this type,
+    /// or has trailing bytes
+    fn from_rlp(rlp: &[u8]) -> Result<Self>;
+}
+
+impl RlpTransport for EthereumAccountProof {
+    fn to_rlp(&self) -> Vec<u8> {
+        encode_trie_proof(&self.proof, &self.address, &self.value)
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let (proof, address, value) = decode_trie_proof(rlp)?;
+        Ok(Self::new(proof, address, value))
+    }
+}
+
+impl RlpTransport for EthereumStorageProof {
+    fn to_rlp(&self) -> Vec<u8> {
+        let mut fields = trie_proof_fields(&self.proof, &self.key, &self.value);
+        if self.value_encoding == ValueEncoding::Raw {
+            fields.push(timewave_rlp::encode(RAW_VALUE_ENCODING));
+        }
+        encode_raw_list(&fields)
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let (parts, value_encoding) = match decode_list(rlp, "storage proof")?[..] {
+            [nodes, key, value] => (
+                decode_trie_proof_fields(nodes, key, value)?,
+                ValueEncoding::RlpEncoded,
+            ),
+            [nodes, key, value, encoding] => {
+                let encoding: u8 = timewave_rlp::decode_exact(encoding)
+                    .map_err(|e| anyhow::anyhow!("Failed to decode value encoding: {:?}", e))?;
+                if encoding != RAW_VALUE_ENCODING {
+                    anyhow::bail!("Unknown value encoding {}", encoding);
+                }
+                (
+                    decode_trie_proof_fields(nodes, key, value)?,
+                    ValueEncoding::Raw,
+                )
+            }
+            _ => anyhow::bail!("Storage proof does not have 3 or 4 fields"),
+        };
+        let (proof, key, value) = parts;
+        Ok(Self::new(proof, key, value).with_value_encoding(value_encoding))
+    }
+}
+
+/// The tag of [`ValueEncoding::Raw`] in a storage proof's optional fourth field.
+const RAW_VALUE_ENCODING: u8 = 1;
+
+impl RlpTransport for EthereumReceiptProof {
+    fn to_rlp(&self) -> Vec<u8> {
+        encode_trie_proof(&self.proof, &self.key, &self.value)
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let (proof, key, value) = decode_trie_proof(rlp)?;
+        Ok(Self::new(proof, key, value))
+    }
+}
+
+impl RlpTransport for EthereumTransactionProof {
+    fn to_rlp(&self) -> Vec<u8> {
+        encode_trie_proof(&self.proof, &self.key, &self.value)
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let (proof, key, value) = decode_trie_proof(rlp)?;
+        Ok(Self::new(proof, key, value))
+    }
+}
+
+impl RlpTransport for EthereumSimpleProof {
+    fn to_rlp(&self) -> Vec<u8> {
+        encode_trie_proof(&self.proof, &self.key, &self.value)
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let (proof, key, value) = decode_trie_proof(rlp)?;
+        Ok(Self::new(proof, key, value))
+    }
+}
+
+impl RlpTransport for EthereumCombinedProof {
+    fn to_rlp(&self) -> Vec<u8> {
+        encode_raw_list(&[self.account_proof.to_rlp(), self.storage_proof.to_rlp()])
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let [account_proof, storage_proof] = decode_list(rlp, "combined proof")?[..] else {
+            anyhow::bail!("Combined proof does not have 2 fields");
+        };
+        Ok(Self::new(
+            EthereumAccountProof::from_rlp(account_proof)?,
+            EthereumStorageProof::from_rlp(storage_proof)?,
+        ))
+    }
+}
+
+impl RlpTransport for EthereumAccessListProof {
+    fn to_rlp(&self) -> Vec<u8> {
+        let storage_proofs: Vec<Vec<u8>> = self
+            .storage_proofs
+            .iter()
+            .map(RlpTransport::to_rlp)
+            .collect();
+        encode_raw_list(&[
+            self.account_proof.to_rlp(),
+            encode_raw_list(&storage_proofs),
+        ])
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let [account_proof, storage_proofs] = decode_list(rlp, "access list proof")?[..] else {
+            anyhow::bail!("Access list proof does not have 2 fields");
+        };
+        let storage_proofs = decode_list(storage_proofs, "storage proofs")?
+            .into_iter()
+            .map(EthereumStorageProof::from_rlp)
+            .collect::<Result<_>>()?;
+        Ok(Self::new(
+            EthereumAccountProof::from_rlp(account_proof)?,
+            storage_proofs,
+        ))
+    }
+}
+
+/// Encodes the wrapped proof as `[variant, proof]`, numbering the variants in
+/// declaration order from 0.
+impl RlpTransport for EthereumProofType {
+    fn to_rlp(&self) -> Vec<u8> {
+        let (variant, proof) = match self {
+            EthereumProofType::Account(proof) => (0u8, proof.to_rlp()),
+            EthereumProofType::Storage(proof) => (1, proof.to_rlp()),
+            EthereumProofType::Combined(proof) => (2, proof.to_rlp()),
+            EthereumProofType::Receipt(proof) => (3, proof.to_rlp()),
+            EthereumProofType::Simple(proof) => (4, proof.to_rlp()),
+            EthereumProofType::Transaction(proof) => (5, proof.to_rlp()),
+        };
+        encode_raw_list(&[timewave_rlp::encode(variant), proof])
+    }
+
+    fn from_rlp(rlp: &[u8]) -> Result<Self> {
+        let [variant, proof] = decode_list(rlp, "proof")?[..] else {
+            anyhow::bail!("Proof does not have 2 fields");
+        };
+        let variant: u8 = timewave_rlp::decode_exact(variant)
+            .map_err(|e| anyhow::anyhow!("Failed to decode proof variant: {:?}", e))?;
+        Ok(match variant {
+            0 => EthereumProofType::Account(RlpTransport::from_rlp(proof)?),
+            1 => EthereumProofType::Storage(RlpTransport::from_rlp(proof)?),
+            2 => EthereumProofType::Combined(RlpTransport::from_rlp(proof)?),
+            3 => EthereumProofType::Receipt(RlpTransport::from_rlp(proof)?),
+            4 => EthereumProofType::Simple(RlpTransport::from_rlp(proof)?),
+            5 => EthereumProofType::Transaction(RlpTransport::from_rlp(proof)?),
+            _ => anyhow::bail!("Unknown proof variant {}", variant),
+        })
+    }
+}
+
+fn encode_trie_proof(nodes: &[Vec<u8>], key: &[u8], value: &[u8]) -> Vec<u8> {
+    encode_raw_list(&trie_proof_fields(nodes, key, value))
+}
+
+/// Encodes the `[nodes, key, value]` fields of a single-trie proof.
+fn trie_proof_fields(nodes: &[Vec<u8>], key: &[u8], value: &[u8]) -> Vec<Vec<u8>> {
+    let nodes: Vec<Vec<u8>> = nodes
+        .iter()
+        .map(|node| timewave_rlp::encode(node.as_slice()))
+        .collect();
+    Vec::from([
+        encode_raw_list(&nodes),
+        timewave_rlp::encode(key),
+        timewave_rlp::encode(value),
+    ])
+}
+
+type TrieProofParts = (Vec<Vec<u8>>, Vec<u8>, Vec<u8>);
+
+fn decode_trie_proof(rlp: &[u8]) -> Result<TrieProofParts> {
+    let [nodes, key, value] = decode_list(rlp, "trie proof")?[..] else {
+        anyhow::bail!("Trie proof does not have 3 fields");
+    };
+    decode_trie_proof_fields(nodes, key, value)
+}
+
+/// Decodes the encoded `[nodes, key, value]` fields of a single-trie proof.
+fn decode_trie_proof_fields(nodes: &[u8], key: &[u8], value: &[u8]) -> Result<TrieProofParts> {
+    let nodes = decode_list(nodes, "proof nodes")?
+        .into_iter()
+        .map(decode_bytes)
+        .collect::<Result<_>>()?;
+    Ok((nodes, decode_bytes(key)?, decode_bytes(value)?))
+}
+
+fn decode_bytes(mut item: &[u8]) -> Result<Vec<u8>> {
+    let bytes = Header::decode_bytes(&mut item, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode proof field: {:?}", e))?;
+    if !item.is_empty() {
+        anyhow::bail!("Proof field has trailing bytes");
+    }
+    Ok(bytes.to_vec())
+}
@@ -5,15 +5,30 @@
 //! Merkle proof traits for Ethereum-specific data structures and provides functionality
 //! to fetch and verify proofs from Ethereum nodes.
 extern crate alloc;
-use alloc::vec::Vec;
+use alloc::{format, string::String, vec::Vec};
 
-use super::{digest_keccak, rlp_decode_bytes};
+use super::{
+    bounds::{split_length_prefixed, split_simple_proof},
+    digest_keccak,
+    receipt::{receipt_status, ReceiptFields, ReceiptType},
+    rlp_decode_bytes,
+};
 use crate::{
-    timewave_rlp::{self, alloy_bytes::Bytes},
-    timewave_trie::verify::verify_proof,
+    timewave_rlp,
+    timewave_trie::{
+        constants::{EMPTY_ROOT_HASH_BYTES, EMPTY_STRING_CODE},
+        types::TrieNode,
+        verify::{verify_proof, ProofVerificationError},
+    },
 };
 use anyhow::{Context, Ok, Result};
-use common::merkle::types::MerkleVerifiable;
+use common::{
+    inspect::{describe_bytes, to_hex},
+    merkle::{
+        types::{Domain, DomainProof, HasRoot, MerkleVerifiable, ProvenEntry},
+        versioned::{deserialize_version, PROOF_FORMAT_VERSION},
+    },
+};
 use num_bigint::BigUint;
 use nybbles::Nibbles;
 use serde::{Deserialize, Serialize};
@@ -25,6 +40,88 @@ pub trait RlpDecodable {
         Self: Sized;
 }
 
+/// How a proof's key is turned into its path in the trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEncoding {
+    /// The path is the keccak256 hash of the key, as in the state and storage tries
+    Keccak256,
+    /// The path is the key itself, as in the receipts and transactions tries,
+    /// which are keyed by the RLP-encoded index
+    Raw,
+}
+
+impl KeyEncoding {
+    /// Returns the trie path of `key` under this encoding.
+    ///
+    /// # Arguments
+    /// * `key` - The key as held by the proof
+    ///
+    /// # Returns
+    /// The nibbles of the path to the key's leaf
+    pub fn path(&self, key: &[u8]) -> Nibbles {
+        match self {
+            KeyEncoding::Keccak256 => Nibbles::unpack(digest_keccak(key)),
+            KeyEncoding::Raw => Nibbles::unpack(key),
+        }
+    }
+}
+
+/// How a storage proof's `value` relates to the value stored in its leaf.
+///
+/// Ethereum stores a slot's value in the leaf as the RLP encoding of the
+/// 32-byte word with its leading zero bytes stripped, e.g. `0x8204d2` for
+/// 1234. A raw uint256 therefore has to be RLP-encoded to match the leaf.
+/// Passing an already encoded value as [`ValueEncoding::Raw`] encodes it a
+/// second time and the proof fails to verify.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ValueEncoding {
+    /// The value is the RLP-encoded leaf value, as returned by `eth_getProof`
+    #[default]
+    RlpEncoded,
+    /// The value is the raw big-endian word, with or without leading zeros
+    Raw,
+}
+
+impl ValueEncoding {
+    /// Returns whether this is [`ValueEncoding::RlpEncoded`].
+    pub fn is_rlp_encoded(&self) -> bool {
+        *self == ValueEncoding::RlpEncoded
+    }
+
+    /// Returns `value` as it is stored in a storage trie leaf.
+    ///
+    /// A raw value of zero encodes to no bytes, since zero slots are absent
+    /// from the trie.
+    ///
+    /// # Arguments
+    /// * `value` - The value in this encoding
+    ///
+    /// # Returns
+    /// The RLP-encoded leaf value
+    pub fn encode(&self, value: &[u8]) -> Vec<u8> {
+        match self {
+            ValueEncoding::RlpEncoded => value.to_vec(),
+            ValueEncoding::Raw => {
+                let start = value.iter().position(|&b| b != 0).unwrap_or(value.len());
+                if start == value.len() {
+                    Vec::new()
+                } else {
+                    timewave_rlp::encode(&value[start..])
+                }
+            }
+        }
+    }
+}
+
+/// Declares how a proof type encodes its key into a trie path.
+///
+/// Verification derives the path through [`KeyEncoding::path`], so a new proof
+/// type only has to pick its encoding.
+pub trait TrieKeyEncoding {
+    /// The encoding of the proof's key
+    const KEY_ENCODING: KeyEncoding;
+}
+
 /// Represents different types of Ethereum Merkle proofs.
 ///
 /// This enum encapsulates the various types of Merkle proofs that can be used
@@ -39,7 +136,8 @@ pub trait RlpDecodable {
 /// * `Combined(EthereumCombinedProof)` - A combined proof containing both account and storage proofs
 /// * `Receipt(EthereumReceiptProof)` - A proof for verifying a transaction receipt in the receipt trie
 /// * `Simple(EthereumSimpleProof)` - A simplified proof format that combines multiple proofs into a single structure
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// * `Transaction(EthereumTransactionProof)` - A proof for verifying a transaction in the transactions trie
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EthereumProofType {
     /// A proof for verifying an account's state in the state trie
     Account(EthereumAccountProof),
@@ -51,21 +149,88 @@ pub enum EthereumProofType {
     Receipt(EthereumReceiptProof),
     /// A simplified proof format that combines multiple proofs into a single structure
     Simple(EthereumSimpleProof),
+    /// A proof for verifying a transaction in the transactions trie
+    Transaction(EthereumTransactionProof),
+}
+
+/// The standard Ethereum proof type with hashed keys, as named in the crate README.
+///
+/// This is the same type as [`EthereumProofType`], so a proof of either name can
+/// be passed where the other is expected.
+pub type EthereumMerkleProof = EthereumProofType;
+
+impl EthereumProofType {
+    /// Renders the wrapped proof as a human-readable multi-line description.
+    ///
+    /// # Returns
+    /// A multi-line description of the wrapped proof
+    pub fn inspect(&self) -> String {
+        match self {
+            EthereumProofType::Account(proof) => proof.inspect(),
+            EthereumProofType::Storage(proof) => proof.inspect(),
+            EthereumProofType::Combined(proof) => proof.inspect(),
+            EthereumProofType::Receipt(proof) => proof.inspect(),
+            EthereumProofType::Simple(proof) => proof.inspect(),
+            EthereumProofType::Transaction(proof) => proof.inspect(),
+        }
+    }
 }
 
+/// Verifies the wrapped proof against `root`.
+///
+/// The meaning of `root` depends on the variant: the state root for account,
+/// combined and simple proofs, the account's storage root for storage proofs, the
+/// block's receipts root for receipt proofs and its transactions root for
+/// transaction proofs.
 impl MerkleVerifiable for EthereumProofType {
     fn verify(&self, root: &[u8]) -> Result<bool> {
-        // Match on the proof type and verify
         match self {
-            EthereumProofType::Simple(simple_proof) => Ok(simple_proof.verify(root)?),
-            EthereumProofType::Account(account_proof) => Ok(account_proof.verify(root)?),
-            _ => {
-                panic!("Unsupported EthereumProofType: The MVP only supports SimpleProof and AccountProof");
-            }
+            EthereumProofType::Account(account_proof) => account_proof.verify(root),
+            EthereumProofType::Storage(storage_proof) => storage_proof.verify(root),
+            EthereumProofType::Combined(combined_proof) => combined_proof.verify(root),
+            EthereumProofType::Receipt(receipt_proof) => receipt_proof.verify(root),
+            EthereumProofType::Simple(simple_proof) => simple_proof.verify(root),
+            EthereumProofType::Transaction(transaction_proof) => transaction_proof.verify(root),
         }
     }
 }
 
+impl From<EthereumAccountProof> for EthereumProofType {
+    fn from(proof: EthereumAccountProof) -> Self {
+        EthereumProofType::Account(proof)
+    }
+}
+
+impl From<EthereumStorageProof> for EthereumProofType {
+    fn from(proof: EthereumStorageProof) -> Self {
+        EthereumProofType::Storage(proof)
+    }
+}
+
+impl From<EthereumCombinedProof> for EthereumProofType {
+    fn from(proof: EthereumCombinedProof) -> Self {
+        EthereumProofType::Combined(proof)
+    }
+}
+
+impl From<EthereumReceiptProof> for EthereumProofType {
+    fn from(proof: EthereumReceiptProof) -> Self {
+        EthereumProofType::Receipt(proof)
+    }
+}
+
+impl From<EthereumSimpleProof> for EthereumProofType {
+    fn from(proof: EthereumSimpleProof) -> Self {
+        EthereumProofType::Simple(proof)
+    }
+}
+
+impl From<EthereumTransactionProof> for EthereumProofType {
+    fn from(proof: EthereumTransactionProof) -> Self {
+        EthereumProofType::Transaction(proof)
+    }
+}
+
 /// Represents a simplified Ethereum Merkle proof that combines multiple proofs into a single structure.
 ///
 /// This struct provides a flattened representation of Ethereum proofs, combining proof nodes,
@@ -73,6 +238,10 @@ impl MerkleVerifiable for EthereumProofType {
 /// serialization and transmission of proofs.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EthereumSimpleProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
     /// The combined proof nodes with length prefixes
     pub proof: Vec<Vec<u8>>,
     /// The combined keys with length prefixes
@@ -81,6 +250,8 @@ pub struct EthereumSimpleProof {
     pub value: Vec<u8>,
 }
 
+common::impl_proof_eq!(EthereumSimpleProof, proof, key, value);
+
 impl EthereumSimpleProof {
     /// Creates a new simplified Ethereum proof.
     ///
@@ -92,7 +263,12 @@ impl EthereumSimpleProof {
     /// # Returns
     /// A new `EthereumSimpleProof` instance
     pub fn new(proof: Vec<Vec<u8>>, key: Vec<u8>, value: Vec<u8>) -> Self {
-        Self { proof, key, value }
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            proof,
+            key,
+            value,
+        }
     }
 
     /// Extracts the storage proof value from the combined values structure.
@@ -114,9 +290,7 @@ impl EthereumSimpleProof {
 
         // Skip the length bytes (2) and the account value to get to the storage value
         let storage_value_start = 2 + account_value_len;
-        let storage_value_part = combined_values[storage_value_start..].to_vec();
-
-        storage_value_part
+        combined_values[storage_value_start..].to_vec()
     }
 
     /// Creates a simplified proof from a combined proof.
@@ -158,7 +332,7 @@ impl EthereumSimpleProof {
         // Create combined values with length information
         let mut combined_values: Vec<u8> = Vec::new();
         let account_value = combined_proof.account_proof.value;
-        let storage_value = combined_proof.storage_proof.value;
+        let storage_value = combined_proof.storage_proof.encoded_value();
 
         // Add length information for account value
         let account_value_len = account_value.len() as u16;
@@ -167,11 +341,29 @@ impl EthereumSimpleProof {
         combined_values.extend(storage_value);
 
         Self {
+            version: PROOF_FORMAT_VERSION,
             proof: combined_nodes,
             key: combined_key,
             value: combined_values,
         }
     }
+
+    /// Renders the proof as a human-readable multi-line description.
+    ///
+    /// The nodes, key and value are shown as flattened, including their length
+    /// prefixes.
+    ///
+    /// # Returns
+    /// A multi-line description of the proof
+    pub fn inspect(&self) -> String {
+        inspect_trie_proof(
+            "EthereumSimpleProof",
+            "key",
+            &self.key,
+            &self.proof,
+            &self.value,
+        )
+    }
 }
 
 /// Implementation of Merkle proof verification for simplified Ethereum proofs.
@@ -183,64 +375,41 @@ impl EthereumSimpleProof {
 /// 4. Returns true only if both verifications succeed
 impl MerkleVerifiable for EthereumSimpleProof {
     fn verify(&self, root: &[u8]) -> Result<bool> {
-        let combined_nodes = &self.proof;
-        let combined_key = &self.key;
-        let combined_values = &self.value;
-
-        // Extract lengths from the combined structures
-        let account_proof_len =
-            u16::from_be_bytes([combined_nodes[0][0], combined_nodes[0][1]]) as usize;
-        let account_key_len = u16::from_be_bytes([combined_key[0], combined_key[1]]) as usize;
-        let account_value_len =
-            u16::from_be_bytes([combined_values[0], combined_values[1]]) as usize;
-
-        // Skip the length nodes when getting the actual proof nodes
-        let account_proof_nodes = combined_nodes[1..1 + account_proof_len].to_vec();
-        let storage_proof_nodes = combined_nodes[1 + account_proof_len..].to_vec();
-
-        // Skip the length bytes when getting the actual key parts
-        let account_key_part = combined_key[2..2 + account_key_len].to_vec();
-        let storage_key_part = combined_key[2 + account_key_len..].to_vec();
-
-        // Skip the length bytes when getting the actual value parts
-        let account_value_part = combined_values[2..2 + account_value_len].to_vec();
-        let storage_value_part = combined_values[2 + account_value_len..].to_vec();
-
-        // Assert that the storage proof is under the storage root used in the account proof
-        let account_decoded = EthereumAccount::rlp_decode(&account_value_part).unwrap();
+        // Split the combined structures at their length prefixes, rejecting
+        // prefixes that claim more than the structures hold
+        let (account_proof_nodes, storage_proof_nodes) = split_simple_proof(self)?;
+        let (account_key_part, storage_key_part) = split_length_prefixed(&self.key, "key")?;
+        let (account_value_part, storage_value_part) = split_length_prefixed(&self.value, "value")?;
 
         let account_proof = EthereumAccountProof::new(
-            account_proof_nodes.clone(),
-            account_key_part,
-            account_value_part,
+            account_proof_nodes.to_vec(),
+            account_key_part.to_vec(),
+            account_value_part.to_vec(),
         );
-
-        let account_result = account_proof.verify(root).unwrap();
-        if !account_result {
+        if !account_proof.verify(root)? {
             return Ok(false);
         }
 
+        // Assert that the storage proof is under the storage root used in the account proof
+        let storage_root = account_proof.storage_root()?;
         let storage_proof = EthereumStorageProof::new(
-            storage_proof_nodes.clone(),
-            storage_key_part,
-            storage_value_part,
+            storage_proof_nodes.to_vec(),
+            storage_key_part.to_vec(),
+            storage_value_part.to_vec(),
         );
-
-        let storage_result = storage_proof.verify(&account_decoded.storage_root).unwrap();
-
-        if !storage_result {
-            return Ok(false);
-        }
-
-        Ok(true)
+        storage_proof.verify(&storage_root)
     }
 }
+/// The prefix of an EIP-7702 delegation designator, followed by the 20-byte
+/// address of the account delegated to.
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
 /// Represents an Ethereum account in the state trie.
 ///
 /// This struct contains the essential data for an Ethereum account, including
 /// its nonce, balance, storage root, and code hash. These fields are used to
 /// verify the account's state in the Ethereum state trie.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EthereumAccount {
     /// The number of transactions sent from this account
     pub nonce: u64,
@@ -287,6 +456,19 @@ impl RlpDecodable for EthereumAccount {
             .context("Failed to get code hash")?
             .to_vec();
 
+        if storage_root.len() != 32 {
+            anyhow::bail!(
+                "Account storage root is {} bytes, expected 32",
+                storage_root.len()
+            );
+        }
+        if code_hash.len() != 32 {
+            anyhow::bail!(
+                "Account code hash is {} bytes, expected 32",
+                code_hash.len()
+            );
+        }
+
         Ok(Self::new(nonce, balance, storage_root, code_hash))
     }
 }
@@ -310,6 +492,59 @@ impl EthereumAccount {
             code_hash,
         }
     }
+
+    /// Returns whether the account has code, either contract bytecode or an
+    /// EIP-7702 delegation designator.
+    ///
+    /// The state trie only holds the hash of an account's code, so this cannot
+    /// tell a contract from a delegated EOA; use [`Self::delegation_target`] or
+    /// [`Self::is_contract`] with the account's code for that.
+    pub fn has_code(&self) -> bool {
+        self.code_hash.as_slice() != digest_keccak(&[]).as_slice()
+    }
+
+    /// Returns the address an EIP-7702 delegated EOA delegates to.
+    ///
+    /// Delegated accounts hold a 23-byte designator, `0xef0100 || address`, as
+    /// their code. Only the code hash is part of the account proof, so the code
+    /// must be obtained separately, e.g. via `eth_getCode`, and is checked
+    /// against the proven code hash here.
+    ///
+    /// # Arguments
+    /// * `code` - The account's code
+    ///
+    /// # Returns
+    /// The delegation target, or `None` if the code is not a delegation designator
+    ///
+    /// # Errors
+    /// Returns an error if `code` does not hash to the account's code hash
+    pub fn delegation_target(&self, code: &[u8]) -> Result<Option<[u8; 20]>> {
+        if digest_keccak(code).as_slice() != self.code_hash.as_slice() {
+            anyhow::bail!(
+                "Code hashes to {}, but the account's code hash is {}",
+                to_hex(&digest_keccak(code)),
+                to_hex(&self.code_hash)
+            );
+        }
+        Ok(code
+            .strip_prefix(&DELEGATION_PREFIX)
+            .and_then(|target| target.try_into().ok()))
+    }
+
+    /// Returns whether the account is a contract, as opposed to an EOA that
+    /// may delegate to one under EIP-7702.
+    ///
+    /// # Arguments
+    /// * `code` - The account's code
+    ///
+    /// # Returns
+    /// `true` if the account has code that is not a delegation designator
+    ///
+    /// # Errors
+    /// Returns an error if `code` does not hash to the account's code hash
+    pub fn is_contract(&self, code: &[u8]) -> Result<bool> {
+        Ok(self.has_code() && self.delegation_target(code)?.is_none())
+    }
 }
 
 /// Represents a combined Ethereum Merkle proof containing both account and storage proofs.
@@ -319,10 +554,16 @@ impl EthereumAccount {
 /// when verifying storage values for a specific account.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EthereumCombinedProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
     pub account_proof: EthereumAccountProof,
     pub storage_proof: EthereumStorageProof,
 }
 
+common::impl_proof_eq!(EthereumCombinedProof, account_proof, storage_proof);
+
 impl EthereumCombinedProof {
     /// Creates a new combined proof from an account proof and storage proof.
     ///
@@ -334,24 +575,147 @@ impl EthereumCombinedProof {
     /// A new `EthereumCombinedProof` instance
     pub fn new(account_proof: EthereumAccountProof, storage_proof: EthereumStorageProof) -> Self {
         Self {
+            version: PROOF_FORMAT_VERSION,
             account_proof,
             storage_proof,
         }
     }
+
+    /// Creates a combined proof from separately fetched parts, checking that they
+    /// belong together.
+    ///
+    /// # Arguments
+    /// * `account` - The proof for the account's existence and state
+    /// * `storage` - The proof for a storage value of that account
+    ///
+    /// # Returns
+    /// A new `EthereumCombinedProof` instance
+    ///
+    /// # Errors
+    /// Returns an error if the storage proof does not verify against the storage
+    /// root of the proven account, see `assert_consistent`
+    pub fn try_from_parts(
+        account: EthereumAccountProof,
+        storage: EthereumStorageProof,
+    ) -> Result<Self> {
+        let combined_proof = Self::new(account, storage);
+        combined_proof.assert_consistent()?;
+        Ok(combined_proof)
+    }
+
+    /// Checks that the storage proof belongs to the account in the account proof.
+    ///
+    /// The account is decoded and the storage proof is verified against its
+    /// storage root, so an account proof for one contract cannot be paired with
+    /// a storage proof for another. A storage proof with an empty value must
+    /// prove that the slot was never written. This does not verify the account
+    /// proof against a state root; `verify` does both.
+    ///
+    /// # Errors
+    /// Returns an error if the account cannot be decoded or the storage proof
+    /// does not verify against the account's storage root
+    pub fn assert_consistent(&self) -> Result<()> {
+        let storage_root = self.account_proof.storage_root()?;
+        let consistent = self
+            .storage_proof
+            .verify_slot(&storage_root)
+            .context("Storage proof does not belong to the proven account")?;
+        if !consistent {
+            anyhow::bail!("Storage proof does not belong to the proven account");
+        }
+        Ok(())
+    }
+
+    /// Renders the account and storage proofs as a human-readable multi-line description.
+    ///
+    /// # Returns
+    /// A multi-line description of both proofs
+    pub fn inspect(&self) -> String {
+        let mut out = String::from("EthereumCombinedProof\n");
+        out.push_str(&indent(&self.account_proof.inspect()));
+        out.push_str(&indent(&self.storage_proof.inspect()));
+        out
+    }
 }
 
 /// Implementation of Merkle proof verification for combined Ethereum proofs.
 ///
 /// This implementation verifies both account and storage proofs in sequence:
 /// 1. First verifies the account proof against the state root
-/// 2. Then verifies the storage proof against the account's storage root
+/// 2. Then verifies the storage proof against the account's storage root, which
+///    is the consistency check of `assert_consistent`
 /// 3. Returns true only if both verifications succeed
 impl MerkleVerifiable for EthereumCombinedProof {
     fn verify(&self, root: &[u8]) -> Result<bool> {
-        let account_decoded = EthereumAccount::rlp_decode(&self.account_proof.value)?;
-        let storage_proof = self.storage_proof.verify(&account_decoded.storage_root)?;
-        let account_proof = self.account_proof.verify(root)?;
-        Ok(storage_proof && account_proof)
+        self.account_proof
+            .verify_storage_proofs(root, core::slice::from_ref(&self.storage_proof))
+    }
+}
+
+/// Represents an account proof bundled with proofs for several of its storage slots.
+///
+/// This is used to prove a complete read-set, such as every slot of an EIP-2930
+/// access list, against a single account proof. Slots that were never written are
+/// carried as exclusion proofs with an empty value.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EthereumAccessListProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
+    pub account_proof: EthereumAccountProof,
+    pub storage_proofs: Vec<EthereumStorageProof>,
+}
+
+common::impl_proof_eq!(EthereumAccessListProof, account_proof, storage_proofs);
+
+impl EthereumAccessListProof {
+    /// Creates a new access list proof.
+    ///
+    /// # Arguments
+    /// * `account_proof` - The proof for the account's existence and state
+    /// * `storage_proofs` - The proofs for the account's storage slots
+    ///
+    /// # Returns
+    /// A new `EthereumAccessListProof` instance
+    pub fn new(
+        account_proof: EthereumAccountProof,
+        storage_proofs: Vec<EthereumStorageProof>,
+    ) -> Self {
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            account_proof,
+            storage_proofs,
+        }
+    }
+
+    /// Renders the account proof and every slot proof as a human-readable
+    /// multi-line description.
+    ///
+    /// # Returns
+    /// A multi-line description of all proofs
+    pub fn inspect(&self) -> String {
+        let mut out = String::from("EthereumAccessListProof\n");
+        out.push_str(&indent(&self.account_proof.inspect()));
+        for storage_proof in &self.storage_proofs {
+            out.push_str(&indent(&storage_proof.inspect()));
+        }
+        out
+    }
+}
+
+/// Verifies the account proof against the state root and every slot against the
+/// account's storage root, see `EthereumAccountProof::verify_storage_proofs`.
+impl MerkleVerifiable for EthereumAccessListProof {
+    fn verify(&self, root: &[u8]) -> Result<bool> {
+        self.account_proof
+            .verify_storage_proofs(root, &self.storage_proofs)
+    }
+}
+
+impl HasRoot for EthereumAccessListProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        self.account_proof.root()
     }
 }
 
@@ -363,12 +727,39 @@ impl MerkleVerifiable for EthereumCombinedProof {
 /// at the leaf node.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EthereumStorageProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
     /// The list of proof nodes in the Merkle path from leaf to root
     pub proof: Vec<Vec<u8>>,
     /// The storage key being proven (keccak256 hash of the original key)
     pub key: Vec<u8>,
-    /// The RLP-encoded value being proven
+    /// The value being proven, RLP-encoded unless `value_encoding` says otherwise
     pub value: Vec<u8>,
+    /// The encoding of `value`
+    #[serde(default, skip_serializing_if = "ValueEncoding::is_rlp_encoded")]
+    pub value_encoding: ValueEncoding,
+}
+
+// The value is compared as it is stored in the trie, so that the caller-side
+// `value_encoding` does not set apart two proofs of the same slot and value
+impl PartialEq for EthereumStorageProof {
+    fn eq(&self, other: &Self) -> bool {
+        self.proof == other.proof
+            && self.key == other.key
+            && self.encoded_value() == other.encoded_value()
+    }
+}
+
+impl Eq for EthereumStorageProof {}
+
+impl core::hash::Hash for EthereumStorageProof {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.proof.hash(state);
+        self.key.hash(state);
+        self.encoded_value().hash(state);
+    }
 }
 
 impl EthereumStorageProof {
@@ -382,68 +773,373 @@ impl EthereumStorageProof {
     /// # Note
     /// The key is automatically hashed using keccak256 before being stored
     pub fn new(proof: Vec<Vec<u8>>, key: Vec<u8>, value: Vec<u8>) -> Self {
-        Self { proof, key, value }
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            proof,
+            key,
+            value,
+            value_encoding: ValueEncoding::default(),
+        }
     }
-}
 
-/// Implementation of Merkle proof verification for Ethereum storage proofs.
-///
-/// This implementation verifies proofs against the Ethereum storage trie by:
-/// 1. Decoding the proof nodes and checking the leaf node value
-/// 2. Verifying the proof path using the keccak256-hashed storage key
-/// 3. Ensuring the computed root matches the expected root
-impl MerkleVerifiable for EthereumStorageProof {
-    fn verify(&self, root: &[u8]) -> Result<bool> {
-        let proof_nodes: Vec<Bytes> = self
-            .proof
-            .iter()
-            .map(|node| Bytes::copy_from_slice(node))
-            .collect();
+    /// Returns the trie path of the proof's slot.
+    ///
+    /// The key is left-padded to the 32 bytes of a storage slot before it is
+    /// hashed, so a slot given in short form, e.g. `0x01`, lies at the same
+    /// path as its full-width encoding.
+    ///
+    /// # Errors
+    /// Returns an error if the key is longer than 32 bytes
+    fn slot_path(&self) -> Result<Nibbles> {
+        Ok(Self::KEY_ENCODING.path(&pad_storage_slot(&self.key)?))
+    }
+
+    /// Sets the encoding of the proof's value.
+    ///
+    /// # Arguments
+    /// * `value_encoding` - The encoding `value` is given in
+    ///
+    /// # Returns
+    /// The proof, with `value` read in the given encoding
+    pub fn with_value_encoding(mut self, value_encoding: ValueEncoding) -> Self {
+        self.value_encoding = value_encoding;
+        self
+    }
+
+    /// Returns the value as it is stored in the leaf, see [`ValueEncoding`].
+    ///
+    /// # Returns
+    /// The RLP-encoded value, empty if the slot is claimed to be absent
+    pub fn encoded_value(&self) -> Vec<u8> {
+        self.value_encoding.encode(&self.value)
+    }
+
+    /// Returns the byte string stored in the slot.
+    ///
+    /// Storage values are RLP-encoded byte strings with leading zero bytes
+    /// stripped; this removes the RLP header without interpreting the bytes. An
+    /// empty value, as carried by an exclusion proof, yields no bytes.
+    ///
+    /// # Returns
+    /// The stored bytes
+    ///
+    /// # Errors
+    /// Returns an error if the value is not a single RLP byte string
+    pub fn value_bytes(&self) -> Result<Vec<u8>> {
+        let encoded = self.encoded_value();
+        if encoded.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut value = encoded.as_slice();
+        let bytes = timewave_rlp::Header::decode_bytes(&mut value, false)
+            .map_err(|e| anyhow::anyhow!("Failed to decode storage value: {:?}", e))?;
+        if !value.is_empty() {
+            anyhow::bail!("Storage value has trailing bytes");
+        }
+        Ok(bytes.to_vec())
+    }
+
+    /// Returns the value stored in the slot as an unsigned 256-bit integer.
+    ///
+    /// # Returns
+    /// The stored value
+    ///
+    /// # Errors
+    /// Returns an error if the value is not an RLP byte string or is longer than
+    /// 32 bytes, in which case the slot does not hold an integer
+    pub fn value_as_u256(&self) -> Result<BigUint> {
+        let bytes = self.value_bytes()?;
+        if bytes.len() > 32 {
+            anyhow::bail!(
+                "Storage value of {} bytes does not fit in 256 bits",
+                bytes.len()
+            );
+        }
+        Ok(BigUint::from_bytes_be(&bytes))
+    }
 
+    /// Verifies the proof without returning early on a value mismatch.
+    ///
+    /// `verify` rejects a mismatched value before walking the proof, so its
+    /// running time reveals whether the value matched. This variant always
+    /// walks the full path, compares the values without short-circuiting and
+    /// combines both results at the end. It is slower and only worth using when
+    /// the verifier is exposed to untrusted callers, e.g. as a public service.
+    /// A proof that does not verify against `root` yields `false` rather than
+    /// an error, so that the result does not tell the two failures apart either.
+    ///
+    /// # Arguments
+    /// * `root` - The storage root to verify against
+    ///
+    /// # Returns
+    /// `true` if the proof verifies against `root` and holds the proof's value
+    ///
+    /// # Errors
+    /// Returns [`EmptyProof`] if the proof has no nodes, and an error if its leaf
+    /// cannot be decoded or its key is longer than 32 bytes
+    pub fn verify_constant_time(&self, root: &[u8]) -> Result<bool> {
+        if self.proof.is_empty() {
+            return Err(EmptyProof.into());
+        }
         let leaf_node_decoded: Vec<timewave_rlp::Bytes> = rlp_decode_bytes(
-            proof_nodes
-                .to_vec()
+            self.proof
                 .last()
                 .context("Failed to extract leaf node from proof")?,
         )?;
-
         let stored_value = leaf_node_decoded
             .last()
             .context("Failed to get stored value from leaf")?
             .to_vec();
 
-        if stored_value != self.value {
-            info!("Value mismatch!");
-            info!("Expected value: {:?}", self.value);
-            info!("Stored value: {:?}", stored_value);
-            return Ok(false);
-        }
-        let key = Nibbles::unpack(&digest_keccak(&self.key));
+        let path_matches = match root.try_into() {
+            core::result::Result::Ok(root) => verify_proof(
+                &root,
+                self.slot_path()?,
+                Some(stored_value.clone()),
+                &self.proof,
+            )
+            .is_ok(),
+            Err(_) => false,
+        };
+        let value_matches = constant_time_eq(&stored_value, &self.encoded_value());
+        Ok(path_matches & value_matches)
+    }
 
-        let result = verify_proof(
-            &root.try_into()?,
-            key,
-            Some(self.value.to_vec()),
-            proof_nodes.iter(),
-        );
+    /// Verifies the proof for a raw storage slot and returns the proven value.
+    ///
+    /// The slot is the unhashed storage key (e.g. `keccak256(abi.encode(holder, slot))`
+    /// for a mapping entry, or the slot index for a plain variable). It is left-padded
+    /// to 32 bytes and must match the key this proof was built for. The keccak256
+    /// hashing into the trie path is performed by `verify`, so callers never hash the
+    /// key themselves.
+    ///
+    /// # Arguments
+    /// * `root` - The storage root of the account that holds the slot
+    /// * `raw_slot` - The unhashed storage slot, at most 32 bytes
+    ///
+    /// # Returns
+    /// The RLP-encoded value stored in the slot
+    ///
+    /// # Errors
+    /// Returns an error if the slot does not match the proof's key or if the
+    /// proof does not verify against `root`
+    pub fn verify_for_slot(&self, root: &[u8], raw_slot: &[u8]) -> Result<Vec<u8>> {
+        let expected_key = pad_storage_slot(raw_slot)?;
+        let proof_key = pad_storage_slot(&self.key)?;
+        if expected_key != proof_key {
+            anyhow::bail!(
+                "Storage proof is for slot {}, expected slot {}",
+                to_hex(&proof_key),
+                to_hex(&expected_key)
+            );
+        }
+        if !self.verify(root)? {
+            anyhow::bail!("Storage proof does not match the stored value");
+        }
+        Ok(self.encoded_value())
+    }
 
-        match result {
-            core::result::Result::Ok(_) => Ok(true),
-            Err(e) => {
-                anyhow::bail!("Proof verification failed: {:?}", e);
-            }
+    /// Verifies the proof and returns the proven value.
+    ///
+    /// Unlike `verify`, the value is only handed out once the proof has verified,
+    /// so an unverified `value` is never read. An empty value is checked as an
+    /// exclusion proof, see `verify_nonexistence`.
+    ///
+    /// # Arguments
+    /// * `root` - The storage root of the account that holds the slot
+    ///
+    /// # Returns
+    /// The RLP-encoded value stored in the slot, empty if the slot is provably absent
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not verify against `root`
+    pub fn verify_get(&self, root: &[u8]) -> Result<Vec<u8>> {
+        if !self.verify_slot(root)? {
+            anyhow::bail!("Storage proof does not verify against the given root");
         }
+        Ok(self.encoded_value())
     }
-}
 
-/// Represents an Ethereum account Merkle proof.
-///
-/// This struct contains the necessary components to verify a Merkle proof for an
-/// Ethereum account in the state trie. The proof includes the path from the leaf
-/// node to the root, the account address being proven, and the RLP-encoded account
+    /// Verifies the proof against an account's storage root.
+    ///
+    /// This is the same check as `verify`, named so that call sites make explicit
+    /// that `storage_root` is the root of the account's storage trie (as decoded
+    /// from a verified account proof) and not the block's state root.
+    ///
+    /// # Arguments
+    /// * `storage_root` - The storage root of the account that holds the slot
+    ///
+    /// # Returns
+    /// A boolean indicating whether the proof is valid for the given storage root
+    pub fn verify_against_storage_root(&self, storage_root: &[u8]) -> Result<bool> {
+        self.verify(storage_root)
+    }
+
+    /// Verifies the proof against an account's storage root, reading an empty
+    /// value as the claim that the slot was never written.
+    ///
+    /// Such a proof is an exclusion proof and is checked with
+    /// `verify_nonexistence`, every other proof with `verify_against_storage_root`.
+    fn verify_slot(&self, storage_root: &[u8]) -> Result<bool> {
+        if self.encoded_value().is_empty() {
+            self.verify_nonexistence(storage_root)
+        } else {
+            self.verify_against_storage_root(storage_root)
+        }
+    }
+
+    /// Checks that the leaf of the proof sits at the path of the proven key.
+    ///
+    /// The path walked through the proof's branch and extension nodes, followed by
+    /// the leaf's partial path, must equal the nibbles of `keccak256(key)`. This
+    /// repeats a check that `verify_proof` already performs, so that a forged leaf
+    /// whose value matches but whose path does not is rejected independently of it.
+    ///
+    /// # Errors
+    /// Returns an error if a node cannot be decoded, the proof does not end at a
+    /// leaf, or the leaf's path does not match the key
+    pub fn assert_leaf_path(&self) -> Result<()> {
+        assert_leaf_path(&self.proof, &self.slot_path()?)
+    }
+
+    /// Verifies the proof in strict mode, checking the leaf path with
+    /// `assert_leaf_path` before running `verify`.
+    ///
+    /// # Arguments
+    /// * `storage_root` - The storage root of the account that holds the slot
+    ///
+    /// # Returns
+    /// A boolean indicating whether the proof is valid for the given storage root
+    ///
+    /// # Errors
+    /// Returns an error if the leaf path does not match the key or the proof is malformed
+    pub fn verify_strict(&self, storage_root: &[u8]) -> Result<bool> {
+        self.assert_leaf_path()?;
+        self.verify(storage_root)
+    }
+
+    /// Verifies that the slot has never been written, i.e. is absent from the
+    /// storage trie and reads as zero.
+    ///
+    /// # Arguments
+    /// * `storage_root` - The storage root of the account that holds the slot
+    ///
+    /// # Returns
+    /// `true` if the slot is provably absent, `false` if the proof shows a value
+    /// stored in the slot
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not belong to `storage_root`
+    pub fn verify_nonexistence(&self, storage_root: &[u8]) -> Result<bool> {
+        verify_exclusion(&self.proof, self.slot_path()?, storage_root)
+    }
+
+    /// Renders the proof as a human-readable multi-line description.
+    ///
+    /// # Returns
+    /// A multi-line description of the key, the proof nodes and the value
+    pub fn inspect(&self) -> String {
+        inspect_trie_proof(
+            "EthereumStorageProof",
+            "key",
+            &self.key,
+            &self.proof,
+            &self.encoded_value(),
+        )
+    }
+}
+
+/// Compares two byte strings, always reading every byte of the longer one.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = (a.len() != b.len()) as u8;
+    for i in 0..a.len().max(b.len()) {
+        diff |= a.get(i).copied().unwrap_or(0) ^ b.get(i).copied().unwrap_or(0);
+    }
+    diff == 0
+}
+
+/// Left-pads a storage slot to the 32 bytes used as the storage trie key.
+pub(crate) fn pad_storage_slot(slot: &[u8]) -> Result<[u8; 32]> {
+    if slot.len() > 32 {
+        anyhow::bail!("Storage slot must be at most 32 bytes, got {}", slot.len());
+    }
+    let mut padded = [0u8; 32];
+    padded[32 - slot.len()..].copy_from_slice(slot);
+    Ok(padded)
+}
+
+/// The proof holds no nodes.
+///
+/// This usually means that the proof field failed to populate, e.g. from a
+/// malformed RPC response. An empty node list is only a valid proof of the
+/// empty trie; against any other root, `verify` returns this error wrapped in
+/// an [`anyhow::Error`], from which it can be recovered with `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyProof;
+
+impl core::fmt::Display for EmptyProof {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Proof holds no nodes")
+    }
+}
+
+impl core::error::Error for EmptyProof {}
+
+/// Implementation of Merkle proof verification for Ethereum storage proofs.
+///
+/// This implementation verifies proofs against the Ethereum storage trie by:
+/// 1. Decoding the proof nodes and checking the leaf node value
+/// 2. Verifying the proof path using the keccak256-hashed storage key, left-padded
+///    to 32 bytes
+/// 3. Ensuring the computed root matches the expected root
+impl MerkleVerifiable for EthereumStorageProof {
+    fn verify(&self, root: &[u8]) -> Result<bool> {
+        let value = self.encoded_value();
+        if let Some(result) = verify_empty_trie(&self.proof, root, &value) {
+            return result;
+        }
+        let leaf_node_decoded: Vec<timewave_rlp::Bytes> = rlp_decode_bytes(
+            self.proof
+                .last()
+                .context("Failed to extract leaf node from proof")?,
+        )?;
+
+        let stored_value = leaf_node_decoded
+            .last()
+            .context("Failed to get stored value from leaf")?
+            .to_vec();
+
+        if stored_value != value {
+            info!("Value mismatch!");
+            info!("Expected value: {:?}", value);
+            info!("Stored value: {:?}", stored_value);
+            return Ok(false);
+        }
+        let key = self.slot_path()?;
+
+        let result = verify_proof(&root.try_into()?, key, Some(value), &self.proof);
+
+        match result {
+            core::result::Result::Ok(_) => Ok(true),
+            Err(e) => {
+                anyhow::bail!("Proof verification failed: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Represents an Ethereum account Merkle proof.
+///
+/// This struct contains the necessary components to verify a Merkle proof for an
+/// Ethereum account in the state trie. The proof includes the path from the leaf
+/// node to the root, the account address being proven, and the RLP-encoded account
 /// data at the leaf node.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EthereumAccountProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
     /// The list of proof nodes in the Merkle path from leaf to root
     pub proof: Vec<Vec<u8>>,
     /// The account address being proven
@@ -452,6 +1148,8 @@ pub struct EthereumAccountProof {
     pub value: Vec<u8>,
 }
 
+common::impl_proof_eq!(EthereumAccountProof, proof, address, value);
+
 impl EthereumAccountProof {
     /// Creates a new Ethereum account Merkle proof.
     ///
@@ -464,11 +1162,168 @@ impl EthereumAccountProof {
     /// A new `EthereumAccountProof` instance
     pub fn new(proof: Vec<Vec<u8>>, address: Vec<u8>, value: Vec<u8>) -> Self {
         Self {
+            version: PROOF_FORMAT_VERSION,
             proof,
             address,
             value,
         }
     }
+
+    /// Checks that the proof is for the expected account.
+    ///
+    /// The address is supplied with the proof, and `verify` only checks that the
+    /// account sits at its path, not that it is the account the caller meant.
+    /// Without this check, an account proof for another contract with a
+    /// matching storage layout would verify just as well.
+    ///
+    /// # Arguments
+    /// * `expected` - The address of the expected account, e.g. pinned by a public input
+    ///
+    /// # Errors
+    /// Returns an error naming both addresses if they differ
+    pub fn assert_address(&self, expected: &[u8]) -> Result<()> {
+        if self.address != expected {
+            anyhow::bail!(
+                "Account proof is for address {}, expected {}",
+                to_hex(&self.address),
+                to_hex(expected)
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the storage root of the proven account.
+    ///
+    /// The account value is only decoded, not verified: use this on a proof that
+    /// has been (or will be) verified against a state root, e.g. to verify storage
+    /// proofs of the account against its storage trie.
+    ///
+    /// # Returns
+    /// The 32-byte root of the account's storage trie
+    ///
+    /// # Errors
+    /// Returns an error if the account value cannot be decoded or its storage
+    /// root is not 32 bytes
+    pub fn storage_root(&self) -> Result<[u8; 32]> {
+        let account = EthereumAccount::rlp_decode(&self.value)?;
+        account
+            .storage_root
+            .as_slice()
+            .try_into()
+            .context("Account storage root is not 32 bytes")
+    }
+
+    /// Verifies the account proof once and then every storage proof against the
+    /// account's storage root.
+    ///
+    /// The account is verified and decoded a single time, so proving N slots of
+    /// the same account costs one account verification instead of N. A storage
+    /// proof with an empty value is an exclusion proof for a slot that was never
+    /// written and is checked with `verify_nonexistence`, which fails on a proof
+    /// cut short before the slot's path provably ends, so a slot holding a value
+    /// cannot be passed off as zero.
+    ///
+    /// # Arguments
+    /// * `root` - The state root to verify the account proof against
+    /// * `storage_proofs` - Storage proofs for slots of this account
+    ///
+    /// # Returns
+    /// A boolean indicating whether the account proof and all storage proofs are valid
+    pub fn verify_storage_proofs(
+        &self,
+        root: &[u8],
+        storage_proofs: &[EthereumStorageProof],
+    ) -> Result<bool> {
+        if !self.verify(root)? {
+            return Ok(false);
+        }
+        let storage_root = self.storage_root()?;
+        for storage_proof in storage_proofs {
+            if !storage_proof.verify_slot(&storage_root)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Verifies that the account is absent from the state trie.
+    ///
+    /// The proof must be an exclusion proof for the address: its path through the
+    /// trie terminates (at an empty branch slot, a diverging leaf or extension, or
+    /// an empty trie) without reaching the address. The account value carried by
+    /// the proof is ignored.
+    ///
+    /// # Arguments
+    /// * `root` - The state root to verify against
+    ///
+    /// # Returns
+    /// `true` if the account provably does not exist, `false` if the proof shows
+    /// an account stored at the address
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not belong to `root`
+    pub fn verify_nonexistence(&self, root: &[u8]) -> Result<bool> {
+        verify_exclusion(&self.proof, Self::KEY_ENCODING.path(&self.address), root)
+    }
+
+    /// Verifies the proof and returns the proven value.
+    ///
+    /// Unlike `verify`, the value is only handed out once the proof has verified,
+    /// so an unverified `value` is never read. An empty value is checked as an
+    /// exclusion proof, see `verify_nonexistence`.
+    ///
+    /// # Arguments
+    /// * `root` - The state root to verify against
+    ///
+    /// # Returns
+    /// The RLP-encoded account, empty if the account provably does not exist
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not verify against `root`
+    pub fn verify_get(&self, root: &[u8]) -> Result<Vec<u8>> {
+        let verified = if self.value.is_empty() {
+            self.verify_nonexistence(root)?
+        } else {
+            self.verify(root)?
+        };
+        if !verified {
+            anyhow::bail!("Account proof does not verify against the given root");
+        }
+        Ok(self.value.clone())
+    }
+
+    /// Verifies the proof and returns the decoded account.
+    ///
+    /// # Arguments
+    /// * `root` - The state root to verify against
+    ///
+    /// # Returns
+    /// The account's nonce, balance, storage root and code hash
+    ///
+    /// # Errors
+    /// Returns an error if the proof does not verify against `root`, proves that
+    /// the account does not exist, or its value cannot be decoded
+    pub fn verify_and_decode(&self, root: &[u8]) -> Result<EthereumAccount> {
+        let value = self.verify_get(root)?;
+        if value.is_empty() {
+            anyhow::bail!("Account {} does not exist", to_hex(&self.address));
+        }
+        EthereumAccount::rlp_decode(&value)
+    }
+
+    /// Renders the proof as a human-readable multi-line description.
+    ///
+    /// # Returns
+    /// A multi-line description of the address, the proof nodes and the value
+    pub fn inspect(&self) -> String {
+        inspect_trie_proof(
+            "EthereumAccountProof",
+            "address",
+            &self.address,
+            &self.proof,
+            &self.value,
+        )
+    }
 }
 
 /// Implementation of Merkle proof verification for Ethereum account proofs.
@@ -479,15 +1334,11 @@ impl EthereumAccountProof {
 /// 3. Ensuring the computed root matches the expected root
 impl MerkleVerifiable for EthereumAccountProof {
     fn verify(&self, root: &[u8]) -> Result<bool> {
-        let proof_nodes: Vec<Bytes> = self
-            .proof
-            .iter()
-            .map(|node| Bytes::copy_from_slice(node))
-            .collect();
-
+        if let Some(result) = verify_empty_trie(&self.proof, root, &self.value) {
+            return result;
+        }
         let leaf_node_decoded: Vec<timewave_rlp::Bytes> = rlp_decode_bytes(
-            proof_nodes
-                .to_vec()
+            self.proof
                 .last()
                 .context("Failed to extract leaf node from proof")?,
         )?;
@@ -501,13 +1352,13 @@ impl MerkleVerifiable for EthereumAccountProof {
             return Ok(false);
         }
 
-        let key = Nibbles::unpack(&digest_keccak(&self.address));
+        let key = Self::KEY_ENCODING.path(&self.address);
 
         let result = verify_proof(
             &root.try_into()?,
             key,
             Some(self.value.to_vec()),
-            proof_nodes.iter(),
+            &self.proof,
         );
 
         match result {
@@ -526,6 +1377,10 @@ impl MerkleVerifiable for EthereumAccountProof {
 /// It contains the proof path, the original key, and the RLP-encoded receipt data.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EthereumReceiptProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
     /// The list of proof nodes in the Merkle path from leaf to root
     pub proof: Vec<Vec<u8>>,
     /// The original key before hashing (typically the transaction index)
@@ -534,6 +1389,8 @@ pub struct EthereumReceiptProof {
     pub value: Vec<u8>,
 }
 
+common::impl_proof_eq!(EthereumReceiptProof, proof, key, value);
+
 impl EthereumReceiptProof {
     /// Creates a new raw Ethereum receipt Merkle proof.
     ///
@@ -545,21 +1402,112 @@ impl EthereumReceiptProof {
     /// # Returns
     /// A new `EthereumReceiptProof` instance
     pub fn new(proof: Vec<Vec<u8>>, key: Vec<u8>, value: Vec<u8>) -> Self {
-        Self { proof, key, value }
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            proof,
+            key,
+            value,
+        }
+    }
+
+    /// Returns the type of the transaction the proven receipt belongs to.
+    ///
+    /// # Returns
+    /// The receipt type, read from the EIP-2718 type prefix of the value
+    ///
+    /// # Errors
+    /// Returns an error if the value is empty or has an unknown type byte
+    pub fn receipt_type(&self) -> Result<ReceiptType> {
+        Ok(ReceiptType::split_envelope(&self.value)?.0)
+    }
+
+    /// Returns whether the transaction of the proven receipt succeeded.
+    ///
+    /// The type prefix of typed receipts is stripped before the receipt is
+    /// decoded. This does not verify the proof itself.
+    ///
+    /// # Returns
+    /// The status of the receipt
+    ///
+    /// # Errors
+    /// Returns an error if the receipt is malformed, has an unknown type or
+    /// predates Byzantium, when receipts held a state root instead of a status
+    pub fn status(&self) -> Result<bool> {
+        receipt_status(&self.value)
+    }
+
+    /// Verifies the proof for the receipt at a transaction index and decodes it.
+    ///
+    /// `verify` proves the receipt under whatever index the proof's key holds.
+    /// This also checks that the key is the RLP encoding of `expected_index`, so
+    /// that a receipt cannot be passed off as belonging to another transaction.
+    ///
+    /// # Arguments
+    /// * `root` - The receipts root of the block
+    /// * `expected_index` - The index of the transaction in the block
+    ///
+    /// # Returns
+    /// The decoded receipt
+    ///
+    /// # Errors
+    /// Returns an error if the proof is for another index, does not verify
+    /// against `root` or holds a malformed receipt
+    pub fn verify_for_index(&self, root: &[u8], expected_index: u32) -> Result<ReceiptFields> {
+        if self.key != timewave_rlp::encode(expected_index) {
+            anyhow::bail!(
+                "Receipt proof is for key {}, expected index {}",
+                to_hex(&self.key),
+                expected_index
+            );
+        }
+        if !self.verify(root)? {
+            anyhow::bail!("Receipt proof does not verify against the receipts root");
+        }
+        ReceiptFields::decode(&self.value)
+    }
+
+    /// Verifies the proof and returns the proven value.
+    ///
+    /// Unlike `verify`, the value is only handed out once the proof has verified,
+    /// so an unverified `value` is never read.
+    ///
+    /// # Arguments
+    /// * `root` - The receipts root of the block
+    ///
+    /// # Returns
+    /// The encoded receipt
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not verify against `root`
+    pub fn verify_get(&self, root: &[u8]) -> Result<Vec<u8>> {
+        if !self.verify(root)? {
+            anyhow::bail!("Receipt proof does not verify against the given root");
+        }
+        Ok(self.value.clone())
+    }
+
+    /// Renders the proof as a human-readable multi-line description.
+    ///
+    /// # Returns
+    /// A multi-line description of the key, the proof nodes and the value
+    pub fn inspect(&self) -> String {
+        inspect_trie_proof(
+            "EthereumReceiptProof",
+            "key",
+            &self.key,
+            &self.proof,
+            &self.value,
+        )
     }
 }
 
 impl MerkleVerifiable for EthereumReceiptProof {
     fn verify(&self, root: &[u8]) -> Result<bool> {
-        let proof_nodes: Vec<Bytes> = self
-            .proof
-            .iter()
-            .map(|node| Bytes::copy_from_slice(node))
-            .collect();
-
+        if let Some(result) = verify_empty_trie(&self.proof, root, &self.value) {
+            return result;
+        }
         let leaf_node_decoded: Vec<timewave_rlp::Bytes> = rlp_decode_bytes(
-            proof_nodes
-                .to_vec()
+            self.proof
                 .last()
                 .context("Failed to extract leaf node from proof")?,
         )?;
@@ -575,13 +1523,13 @@ impl MerkleVerifiable for EthereumReceiptProof {
             info!("Stored value: {:?}", stored_value);
             return Ok(false);
         }
-        let key = Nibbles::unpack(&self.key);
+        let key = Self::KEY_ENCODING.path(&self.key);
 
         let result = verify_proof(
             &root.try_into()?,
             key,
             Some(self.value.to_vec()),
-            proof_nodes.iter(),
+            &self.proof,
         );
 
         match result {
@@ -606,10 +1554,482 @@ impl MerkleVerifiable for EthereumReceiptProof {
 /// A new `EthereumStorageProof` with the same proof nodes and value
 impl From<EthereumReceiptProof> for EthereumStorageProof {
     fn from(proof: EthereumReceiptProof) -> Self {
+        Self::new(proof.proof, proof.key, proof.value)
+    }
+}
+
+/// Represents an Ethereum transaction Merkle proof.
+///
+/// This struct contains the necessary components to verify a Merkle proof for a
+/// transaction in a block's transactions trie. Like receipts, transactions are keyed
+/// by their RLP-encoded index and the key is not hashed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EthereumTransactionProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
+    /// The list of proof nodes in the Merkle path from leaf to root
+    pub proof: Vec<Vec<u8>>,
+    /// The RLP-encoded transaction index
+    pub key: Vec<u8>,
+    /// The EIP-2718 encoded transaction being proven
+    pub value: Vec<u8>,
+}
+
+common::impl_proof_eq!(EthereumTransactionProof, proof, key, value);
+
+impl EthereumTransactionProof {
+    /// Creates a new Ethereum transaction Merkle proof.
+    ///
+    /// # Arguments
+    /// * `proof` - The list of proof nodes in the Merkle path
+    /// * `key` - The RLP-encoded transaction index
+    /// * `value` - The EIP-2718 encoded transaction being proven
+    ///
+    /// # Returns
+    /// A new `EthereumTransactionProof` instance
+    pub fn new(proof: Vec<Vec<u8>>, key: Vec<u8>, value: Vec<u8>) -> Self {
         Self {
-            proof: proof.proof,
-            key: proof.key,
-            value: proof.value,
+            version: PROOF_FORMAT_VERSION,
+            proof,
+            key,
+            value,
+        }
+    }
+
+    /// Recovers the address that signed the proven transaction.
+    ///
+    /// The transaction is parsed as either a legacy transaction (with or without
+    /// EIP-155 replay protection) or an EIP-2718 typed transaction, its signing
+    /// hash is reconstructed and the signer's public key is recovered from the
+    /// signature. This does not verify the proof itself; call `verify` against
+    /// the block's transactions root first.
+    ///
+    /// # Returns
+    /// The 20-byte address of the transaction sender
+    ///
+    /// # Errors
+    /// Returns an error if the transaction cannot be parsed, has an unsupported
+    /// type or carries an invalid signature
+    #[cfg(feature = "k256")]
+    pub fn recover_sender(&self) -> Result<[u8; 20]> {
+        use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+        let TransactionSignature {
+            signing_hash,
+            recovery_id,
+            r,
+            s,
+        } = transaction_signature(&self.value)?;
+        let signature = Signature::from_scalars(r, s)
+            .map_err(|e| anyhow::anyhow!("Invalid transaction signature: {:?}", e))?;
+        let recovery_id =
+            RecoveryId::from_byte(recovery_id).context("Invalid signature recovery id")?;
+        let public_key = VerifyingKey::recover_from_prehash(&signing_hash, &signature, recovery_id)
+            .map_err(|e| anyhow::anyhow!("Failed to recover transaction sender: {:?}", e))?;
+        let public_key = public_key.to_encoded_point(false);
+        let mut sender = [0u8; 20];
+        sender.copy_from_slice(&digest_keccak(&public_key.as_bytes()[1..])[12..]);
+        Ok(sender)
+    }
+
+    /// Renders the proof as a human-readable multi-line description.
+    ///
+    /// # Returns
+    /// A multi-line description of the key, the proof nodes and the value
+    pub fn inspect(&self) -> String {
+        inspect_trie_proof(
+            "EthereumTransactionProof",
+            "key",
+            &self.key,
+            &self.proof,
+            &self.value,
+        )
+    }
+}
+
+impl MerkleVerifiable for EthereumTransactionProof {
+    fn verify(&self, root: &[u8]) -> Result<bool> {
+        EthereumReceiptProof::new(self.proof.clone(), self.key.clone(), self.value.clone())
+            .verify(root)
+    }
+}
+
+impl HasRoot for EthereumTransactionProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        root_of_nodes(&self.proof)
+    }
+}
+
+/// The signing hash and signature of a transaction.
+#[cfg(feature = "k256")]
+struct TransactionSignature {
+    signing_hash: [u8; 32],
+    recovery_id: u8,
+    r: [u8; 32],
+    s: [u8; 32],
+}
+
+/// Splits an EIP-2718 encoded transaction into its signing hash and signature.
+#[cfg(feature = "k256")]
+fn transaction_signature(tx: &[u8]) -> Result<TransactionSignature> {
+    let first = *tx.first().context("Empty transaction")?;
+    let (tx_type, payload) = if first >= timewave_rlp::EMPTY_LIST_CODE {
+        (None, tx)
+    } else {
+        if !(0x01..=0x04).contains(&first) {
+            anyhow::bail!("Unsupported transaction type 0x{:02x}", first);
+        }
+        (Some(first), &tx[1..])
+    };
+    let fields = timewave_rlp::decode_list(payload, "transaction")?;
+    if fields.len() < 3 {
+        anyhow::bail!("Malformed transaction encoding");
+    }
+    let (unsigned_fields, signature_fields) = fields.split_at(fields.len() - 3);
+    let v = rlp_u64(signature_fields[0])?;
+    let r = rlp_scalar(signature_fields[1])?;
+    let s = rlp_scalar(signature_fields[2])?;
+
+    let mut signing_payload = Vec::new();
+    let recovery_id = match tx_type {
+        Some(tx_type) => {
+            signing_payload.push(tx_type);
+            signing_payload.extend(timewave_rlp::encode_raw_list(unsigned_fields));
+            v
+        }
+        // pre EIP-155 legacy transactions sign the six transaction fields only
+        None if v == 27 || v == 28 => {
+            signing_payload.extend(timewave_rlp::encode_raw_list(unsigned_fields));
+            v - 27
+        }
+        // EIP-155 legacy transactions also sign `chain_id, 0, 0`
+        None if v >= 35 => {
+            let chain_id = timewave_rlp::encode((v - 35) / 2);
+            let zero = [timewave_rlp::EMPTY_STRING_CODE];
+            let mut signed_fields = unsigned_fields.to_vec();
+            signed_fields.extend([chain_id.as_slice(), &zero, &zero]);
+            signing_payload.extend(timewave_rlp::encode_raw_list(&signed_fields));
+            (v - 35) % 2
+        }
+        None => anyhow::bail!("Invalid legacy transaction signature v value {}", v),
+    };
+    let recovery_id = u8::try_from(recovery_id).context("Invalid signature recovery id")?;
+    Ok(TransactionSignature {
+        signing_hash: digest_keccak(&signing_payload),
+        recovery_id,
+        r,
+        s,
+    })
+}
+
+/// Decodes an RLP-encoded big-endian integer of at most 8 bytes.
+#[cfg(feature = "k256")]
+fn rlp_u64(mut item: &[u8]) -> Result<u64> {
+    let bytes = timewave_rlp::Header::decode_bytes(&mut item, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode integer: {:?}", e))?;
+    if bytes.len() > 8 {
+        anyhow::bail!("Integer does not fit in 64 bits");
+    }
+    let mut padded = [0u8; 8];
+    padded[8 - bytes.len()..].copy_from_slice(bytes);
+    Ok(u64::from_be_bytes(padded))
+}
+
+/// Decodes an RLP-encoded signature scalar, left-padded to 32 bytes.
+#[cfg(feature = "k256")]
+fn rlp_scalar(mut item: &[u8]) -> Result<[u8; 32]> {
+    let bytes = timewave_rlp::Header::decode_bytes(&mut item, false)
+        .map_err(|e| anyhow::anyhow!("Failed to decode signature scalar: {:?}", e))?;
+    if bytes.len() > 32 {
+        anyhow::bail!("Signature scalar is longer than 32 bytes");
+    }
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(bytes);
+    Ok(padded)
+}
+
+/// Checks that the proof ends at a leaf whose full path equals `key`.
+///
+/// Every node but the last contributes to the path: one nibble of the key for a
+/// branch node and its partial path for an extension node. The last node is the
+/// leaf, or a branch or extension node holding the leaf in place.
+fn assert_leaf_path(proof: &[Vec<u8>], key: &Nibbles) -> Result<()> {
+    let decode = |node: &[u8]| {
+        <TrieNode as timewave_rlp::Decodable>::decode(&mut &node[..])
+            .map_err(|e| anyhow::anyhow!("Failed to decode trie node: {:?}", e))
+    };
+    let (last_node, path_nodes) = proof.split_last().context("Proof has no nodes")?;
+    let mut walked_path = Nibbles::with_capacity(key.len());
+    for node in path_nodes {
+        match decode(node)? {
+            TrieNode::Branch(_) => {
+                let nibble = key
+                    .get(walked_path.len())
+                    .context("Key ends before the last proof node")?;
+                walked_path.push(*nibble);
+            }
+            TrieNode::Extension(extension) => walked_path.extend_from_slice(&extension.key),
+            TrieNode::Leaf(_) | TrieNode::EmptyRoot => {
+                anyhow::bail!("Proof reaches a leaf or empty node before its last node")
+            }
+        }
+    }
+    let mut node = decode(last_node)?;
+    loop {
+        node = match node {
+            TrieNode::Leaf(leaf) => {
+                walked_path.extend_from_slice(&leaf.key);
+                break;
+            }
+            TrieNode::Branch(mut branch) => {
+                let nibble = *key
+                    .get(walked_path.len())
+                    .context("Key ends at the last proof node")?;
+                if !branch.state_mask.is_bit_set(nibble) {
+                    anyhow::bail!("Branch node has no child for the key");
+                }
+                let index = branch.as_ref().first_child_index()
+                    + (0..nibble)
+                        .filter(|index| branch.state_mask.is_bit_set(*index))
+                        .count();
+                let child = branch.stack.remove(index);
+                if child.as_hash().is_some() {
+                    anyhow::bail!("Proof ends at a branch node");
+                }
+                walked_path.push(nibble);
+                decode(&child)?
+            }
+            TrieNode::Extension(extension) => {
+                if extension.child.as_hash().is_some() {
+                    anyhow::bail!("Proof ends at an extension node");
+                }
+                walked_path.extend_from_slice(&extension.key);
+                decode(&extension.child)?
+            }
+            TrieNode::EmptyRoot => anyhow::bail!("Proof ends at an empty node"),
+        };
+    }
+    if walked_path != *key {
+        anyhow::bail!(
+            "Leaf path {:?} does not match the key {:?}",
+            walked_path,
+            key
+        );
+    }
+    Ok(())
+}
+
+/// Verifies that the path `key` is absent from the trie with the given root.
+///
+/// # Returns
+/// `true` if the proof terminates without reaching `key`, `false` if it shows a
+/// value stored under the key
+fn verify_exclusion(proof: &[Vec<u8>], key: Nibbles, root: &[u8]) -> Result<bool> {
+    match verify_proof(&root.try_into()?, key.clone(), None, proof) {
+        core::result::Result::Ok(()) => Ok(true),
+        Err(ProofVerificationError::ValueMismatch {
+            path, got: Some(_), ..
+        }) if path == key => Ok(false),
+        Err(e) => {
+            anyhow::bail!("Proof verification failed: {:?}", e);
+        }
+    }
+}
+
+/// Verifies a proof of an empty trie: no nodes, or the single empty node `0x80`.
+///
+/// Such a proof proves that every key is absent, so it verifies for an empty
+/// value only, and only against the root of the empty trie. A proof with no
+/// nodes against another root fails with [`EmptyProof`].
+///
+/// # Returns
+/// `None` if the proof holds trie nodes, and the verification result otherwise
+fn verify_empty_trie(proof: &[Vec<u8>], root: &[u8], value: &[u8]) -> Option<Result<bool>> {
+    match proof {
+        [] if root != EMPTY_ROOT_HASH_BYTES => return Some(Err(EmptyProof.into())),
+        [] => {}
+        [node] if node.as_slice() == [EMPTY_STRING_CODE] => {}
+        _ => return None,
+    }
+    if root != EMPTY_ROOT_HASH_BYTES {
+        return Some(Err(anyhow::anyhow!(
+            "Root mismatch: the proof is of the empty trie with root {}, expected {}",
+            to_hex(&EMPTY_ROOT_HASH_BYTES),
+            to_hex(root)
+        )));
+    }
+    Some(Ok(value.is_empty()))
+}
+
+/// Describes a proof over a single trie: its key, node count and value.
+fn inspect_trie_proof(
+    name: &str,
+    key_label: &str,
+    key: &[u8],
+    nodes: &[Vec<u8>],
+    value: &[u8],
+) -> String {
+    let node_bytes: usize = nodes.iter().map(Vec::len).sum();
+    format!(
+        "{name}\n  {key_label}: {}\n  nodes: {} ({node_bytes} bytes)\n  value: {}\n",
+        to_hex(key),
+        nodes.len(),
+        describe_bytes(value),
+    )
+}
+
+/// Indents every line of a nested description by two spaces.
+fn indent(description: &str) -> String {
+    description
+        .lines()
+        .map(|line| format!("  {line}\n"))
+        .collect()
+}
+
+impl TrieKeyEncoding for EthereumAccountProof {
+    const KEY_ENCODING: KeyEncoding = KeyEncoding::Keccak256;
+}
+
+impl TrieKeyEncoding for EthereumStorageProof {
+    const KEY_ENCODING: KeyEncoding = KeyEncoding::Keccak256;
+}
+
+impl TrieKeyEncoding for EthereumReceiptProof {
+    const KEY_ENCODING: KeyEncoding = KeyEncoding::Raw;
+}
+
+impl TrieKeyEncoding for EthereumTransactionProof {
+    const KEY_ENCODING: KeyEncoding = KeyEncoding::Raw;
+}
+
+impl ProvenEntry for EthereumAccountProof {
+    fn proven_key(&self) -> Vec<u8> {
+        self.address.clone()
+    }
+
+    fn proven_value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+}
+
+impl ProvenEntry for EthereumStorageProof {
+    fn proven_key(&self) -> Vec<u8> {
+        self.key.clone()
+    }
+
+    fn proven_value(&self) -> Vec<u8> {
+        self.encoded_value()
+    }
+}
+
+/// The key of a combined proof is the account address followed by the storage
+/// key, so that the same slot of two contracts yields two distinct keys.
+impl ProvenEntry for EthereumCombinedProof {
+    fn proven_key(&self) -> Vec<u8> {
+        [
+            self.account_proof.address.as_slice(),
+            &self.storage_proof.key,
+        ]
+        .concat()
+    }
+
+    fn proven_value(&self) -> Vec<u8> {
+        self.storage_proof.encoded_value()
+    }
+}
+
+impl ProvenEntry for EthereumReceiptProof {
+    fn proven_key(&self) -> Vec<u8> {
+        self.key.clone()
+    }
+
+    fn proven_value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+}
+
+impl ProvenEntry for EthereumTransactionProof {
+    fn proven_key(&self) -> Vec<u8> {
+        self.key.clone()
+    }
+
+    fn proven_value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+}
+
+impl DomainProof for EthereumAccountProof {
+    const DOMAIN: Domain = Domain::Ethereum;
+}
+
+impl DomainProof for EthereumStorageProof {
+    const DOMAIN: Domain = Domain::Ethereum;
+}
+
+impl DomainProof for EthereumCombinedProof {
+    const DOMAIN: Domain = Domain::Ethereum;
+}
+
+impl DomainProof for EthereumReceiptProof {
+    const DOMAIN: Domain = Domain::Ethereum;
+}
+
+impl DomainProof for EthereumTransactionProof {
+    const DOMAIN: Domain = Domain::Ethereum;
+}
+
+/// Returns the root committed to by a list of proof nodes: the keccak256 hash of
+/// the first (root) node.
+fn root_of_nodes(nodes: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let root_node = nodes.first().context("Proof has no nodes")?;
+    Ok(digest_keccak(root_node).to_vec())
+}
+
+impl HasRoot for EthereumAccountProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        root_of_nodes(&self.proof)
+    }
+}
+
+impl HasRoot for EthereumStorageProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        root_of_nodes(&self.proof)
+    }
+}
+
+impl HasRoot for EthereumReceiptProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        root_of_nodes(&self.proof)
+    }
+}
+
+/// The root of a combined proof is the state root committed by its account proof.
+impl HasRoot for EthereumCombinedProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        self.account_proof.root()
+    }
+}
+
+/// The root of a simple proof is the state root committed by its account nodes,
+/// which follow the length prefix node.
+impl HasRoot for EthereumSimpleProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        let (account_proof, _) = split_simple_proof(self)?;
+        root_of_nodes(account_proof)
+    }
+}
+
+impl HasRoot for EthereumProofType {
+    fn root(&self) -> Result<Vec<u8>> {
+        match self {
+            EthereumProofType::Account(proof) => proof.root(),
+            EthereumProofType::Storage(proof) => proof.root(),
+            EthereumProofType::Combined(proof) => proof.root(),
+            EthereumProofType::Receipt(proof) => proof.root(),
+            EthereumProofType::Simple(proof) => proof.root(),
+            EthereumProofType::Transaction(proof) => proof.root(),
         }
     }
 }
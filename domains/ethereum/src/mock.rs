@@ -0,0 +1,125 @@
+//! Synthetic proofs for testing without an Ethereum node.
+//!
+//! The builders in this module construct minimal, valid tries in memory and return
+//! proofs together with the root they verify against, so that verification can be
+//! exercised deterministically and offline. Each trie holds a single entry, which
+//! makes its root node a leaf and its proof a single node.
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::{
+    merkle_lib::{
+        digest_keccak,
+        types::{EthereumAccountProof, EthereumCombinedProof, EthereumStorageProof},
+    },
+    timewave_rlp::{self, Encodable, Header},
+};
+
+/// keccak256 of the empty byte string, the code hash of an account without code.
+pub const EMPTY_CODE_HASH: [u8; 32] =
+    hex_literal::hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+
+/// Encodes a trie leaf node for a full 32-byte path.
+///
+/// The path is hex-prefix encoded with the even-length leaf flag (`0x20`),
+/// matching how Ethereum stores leaves whose path is a keccak256 hash.
+pub(crate) fn leaf_node(path: &[u8; 32], value: &[u8]) -> Vec<u8> {
+    let mut encoded_path = Vec::with_capacity(33);
+    encoded_path.push(0x20);
+    encoded_path.extend_from_slice(path);
+    let mut out = Vec::new();
+    timewave_rlp::encode_list::<&[u8], [u8]>(&[encoded_path.as_slice(), value], &mut out);
+    out
+}
+
+/// RLP-encodes an account as stored in the state trie.
+///
+/// # Arguments
+/// * `nonce` - The account's nonce
+/// * `balance` - The account's balance in wei
+/// * `storage_root` - The root of the account's storage trie
+/// * `code_hash` - The hash of the account's code
+///
+/// # Returns
+/// The RLP-encoded account
+pub fn account_rlp(
+    nonce: u64,
+    balance: u64,
+    storage_root: &[u8; 32],
+    code_hash: &[u8; 32],
+) -> Vec<u8> {
+    let payload_length =
+        nonce.length() + balance.length() + storage_root.length() + code_hash.length();
+    let mut out = Vec::new();
+    Header {
+        list: true,
+        payload_length,
+    }
+    .encode(&mut out);
+    nonce.encode(&mut out);
+    balance.encode(&mut out);
+    storage_root.encode(&mut out);
+    code_hash.encode(&mut out);
+    out
+}
+
+/// Builds a storage trie holding a single slot and returns its proof.
+///
+/// # Arguments
+/// * `key` - The unhashed storage slot
+/// * `value` - The RLP-encoded value stored in the slot
+///
+/// # Returns
+/// The storage proof for `key` and the storage root it verifies against
+pub fn storage_proof_for(key: &[u8], value: &[u8]) -> (EthereumStorageProof, Vec<u8>) {
+    let leaf = leaf_node(&digest_keccak(key), value);
+    let root = digest_keccak(&leaf).to_vec();
+    (
+        EthereumStorageProof::new(Vec::from([leaf]), key.to_vec(), value.to_vec()),
+        root,
+    )
+}
+
+/// Builds a state trie holding a single account and returns its proof.
+///
+/// # Arguments
+/// * `address` - The 20-byte account address
+/// * `account` - The RLP-encoded account, see [`account_rlp`]
+///
+/// # Returns
+/// The account proof for `address` and the state root it verifies against
+pub fn account_proof_for(address: &[u8], account: &[u8]) -> (EthereumAccountProof, Vec<u8>) {
+    let leaf = leaf_node(&digest_keccak(address), account);
+    let root = digest_keccak(&leaf).to_vec();
+    (
+        EthereumAccountProof::new(Vec::from([leaf]), address.to_vec(), account.to_vec()),
+        root,
+    )
+}
+
+/// Builds an account whose storage holds a single slot, in a state trie holding
+/// only that account, and returns the matching account and storage proof pair.
+///
+/// The account has a zero nonce and balance and no code.
+///
+/// # Arguments
+/// * `address` - The 20-byte account address
+/// * `key` - The unhashed storage slot
+/// * `value` - The RLP-encoded value stored in the slot
+///
+/// # Returns
+/// The combined proof and the state root it verifies against
+pub fn combined_proof_for(
+    address: &[u8],
+    key: &[u8],
+    value: &[u8],
+) -> (EthereumCombinedProof, Vec<u8>) {
+    let (storage_proof, _) = storage_proof_for(key, value);
+    let storage_root = digest_keccak(&storage_proof.proof[0]);
+    let account = account_rlp(0, 0, &storage_root, &EMPTY_CODE_HASH);
+    let (account_proof, state_root) = account_proof_for(address, &account);
+    (
+        EthereumCombinedProof::new(account_proof, storage_proof),
+        state_root,
+    )
+}
@@ -257,6 +257,24 @@ impl From<Bytes> for Vec<u8> {
     }
 }
 
+/// Converts from the `alloy_primitives` type returned by the RPC client. Both
+/// wrap a [`bytes::Bytes`], so the buffer is shared rather than copied.
+#[cfg(feature = "no-zkvm")]
+impl From<alloy_primitives::Bytes> for Bytes {
+    #[inline]
+    fn from(value: alloy_primitives::Bytes) -> Self {
+        Self(value.0)
+    }
+}
+
+#[cfg(feature = "no-zkvm")]
+impl From<Bytes> for alloy_primitives::Bytes {
+    #[inline]
+    fn from(value: Bytes) -> Self {
+        Self(value.0)
+    }
+}
+
 impl PartialEq<[u8]> for Bytes {
     #[inline]
     fn eq(&self, other: &[u8]) -> bool {
@@ -442,4 +460,13 @@ mod tests {
         assert_eq!(format!("{b:X}"), "0x0123456789ABCDEF");
         assert_eq!(format!("{b:#X}"), "0x0123456789ABCDEF");
     }
+
+    #[cfg(feature = "no-zkvm")]
+    #[test]
+    fn alloy_conversion() {
+        let b = Bytes::from_static(&[0x12, 0x13, 0xab, 0xcd]);
+        let alloy: alloy_primitives::Bytes = b.clone().into();
+        assert_eq!(alloy.as_ref(), b.as_ref());
+        assert_eq!(Bytes::from(alloy), b);
+    }
 }
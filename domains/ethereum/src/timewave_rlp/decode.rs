@@ -1,5 +1,8 @@
 extern crate alloc;
-use crate::{timewave_rlp::Error, timewave_rlp::Header, timewave_rlp::Result};
+use crate::{
+    timewave_rlp::Error, timewave_rlp::Header, timewave_rlp::PayloadView, timewave_rlp::Result,
+};
+use alloc::vec::Vec;
 use bytes::{Bytes, BytesMut};
 use core::marker::{PhantomData, PhantomPinned};
 
@@ -185,6 +188,18 @@ pub fn decode_exact<T: Decodable>(bytes: impl AsRef<[u8]>) -> Result<T> {
     Ok(out)
 }
 
+/// Splits an RLP list that must span all of `item` into its encoded items.
+///
+/// `what` names the item in error messages.
+pub(crate) fn decode_list<'a>(mut item: &'a [u8], what: &str) -> anyhow::Result<Vec<&'a [u8]>> {
+    let view = Header::decode_raw(&mut item)
+        .map_err(|e| anyhow::anyhow!("Failed to decode {}: {:?}", what, e))?;
+    match view {
+        PayloadView::List(items) if item.is_empty() => Ok(items),
+        _ => anyhow::bail!("Malformed {} encoding", what),
+    }
+}
+
 /// Left-pads a slice to a statically known size array.
 ///
 /// # Errors
@@ -296,6 +296,22 @@ where
     }
 }
 
+/// Encodes already RLP-encoded items as a list, so that they are embedded in place
+/// rather than as byte strings.
+pub(crate) fn encode_raw_list<B: AsRef<[u8]>>(items: &[B]) -> Vec<u8> {
+    let payload_length = items.iter().map(|item| item.as_ref().len()).sum();
+    let mut out = Vec::with_capacity(payload_length + length_of_length(payload_length));
+    Header {
+        list: true,
+        payload_length,
+    }
+    .encode(&mut out);
+    for item in items {
+        out.extend_from_slice(item.as_ref());
+    }
+    out
+}
+
 /// Encode all items from an iterator.
 ///
 /// This clones the iterator. Prefer [`encode_list`] if possible.
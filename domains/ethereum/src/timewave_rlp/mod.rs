@@ -1,6 +1,7 @@
 extern crate alloc;
 
 mod decode;
+pub(crate) use decode::decode_list;
 pub use decode::{decode_exact, Decodable, Rlp};
 
 mod error;
@@ -10,6 +11,7 @@ pub mod alloy_bytes;
 
 mod encode;
 pub use encode::encode_fixed_size;
+pub(crate) use encode::encode_raw_list;
 pub use encode::{
     encode, encode_iter, encode_list, length_of_length, list_length, Encodable, MaxEncodedLen,
     MaxEncodedLenAssoc,
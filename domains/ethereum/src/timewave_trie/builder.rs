@@ -0,0 +1,365 @@
+//! Construction of Merkle-Patricia tries.
+//!
+//! [`TrieBuilder`] is the counterpart of [`verify_proof`](super::verify::verify_proof):
+//! it inserts key-value pairs into an in-memory trie, computes the root and emits
+//! proofs in the format the verifier consumes. Like the verifier it follows
+//! Ethereum's tries, so values are only stored in leaves and no key may be a
+//! prefix of another.
+extern crate alloc;
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use anyhow::Result;
+use nybbles::Nibbles;
+
+use crate::{
+    merkle_lib::digest_keccak,
+    timewave_rlp::{self, Encodable},
+    timewave_trie::{
+        constants::{EMPTY_ROOT_HASH_BYTES, EMPTY_STRING_CODE},
+        types::RlpNode,
+    },
+};
+
+/// An in-memory Merkle-Patricia trie.
+///
+/// Keys are inserted as given: callers building a state or storage trie hash
+/// them with keccak256 first, as the trie's key encoding requires.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrieBuilder {
+    root: Node,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum Node {
+    #[default]
+    Empty,
+    Leaf {
+        path: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Extension {
+        path: Vec<u8>,
+        child: Box<Node>,
+    },
+    Branch {
+        children: [Option<Box<Node>>; 16],
+    },
+}
+
+impl TrieBuilder {
+    /// Creates an empty trie.
+    ///
+    /// # Returns
+    /// A new `TrieBuilder` instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether no key has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.root == Node::Empty
+    }
+
+    /// Inserts a value under a key, replacing any previous value.
+    ///
+    /// # Arguments
+    /// * `key` - The key, used as the path in the trie
+    /// * `value` - The value to store, e.g. an RLP-encoded account or receipt
+    ///
+    /// # Errors
+    /// Returns an error if the value is empty, which Ethereum tries treat as a
+    /// deletion, or if the key is a prefix of an inserted key or vice versa
+    pub fn insert(&mut self, key: &[u8], value: Vec<u8>) -> Result<()> {
+        if value.is_empty() {
+            anyhow::bail!("Trie values must not be empty");
+        }
+        self.root.insert(&Nibbles::unpack(key), value)
+    }
+
+    /// Computes the root of the trie.
+    ///
+    /// # Returns
+    /// The keccak256 hash of the root node, or the empty root hash for an empty trie
+    pub fn root(&self) -> [u8; 32] {
+        match self.root {
+            Node::Empty => EMPTY_ROOT_HASH_BYTES,
+            _ => digest_keccak(&self.root.encode()),
+        }
+    }
+
+    /// Builds a proof for a key, from the root towards the key's leaf.
+    ///
+    /// Nodes small enough to be embedded in their parent are not listed
+    /// separately, matching the proofs returned by `eth_getProof`. For a key that
+    /// is not in the trie, the proof ends at the node where its path diverges and
+    /// verifies as an exclusion proof.
+    ///
+    /// # Arguments
+    /// * `key` - The key to prove
+    ///
+    /// # Returns
+    /// The RLP-encoded proof nodes
+    pub fn proof(&self, key: &[u8]) -> Vec<Vec<u8>> {
+        let path = Nibbles::unpack(key);
+        let mut path: &[u8] = &path;
+        let mut node = &self.root;
+        let mut proof = Vec::new();
+        if *node == Node::Empty {
+            return proof;
+        }
+        proof.push(node.encode());
+        loop {
+            let child = match node {
+                Node::Extension {
+                    path: prefix,
+                    child,
+                } if path.starts_with(prefix) => {
+                    path = &path[prefix.len()..];
+                    child
+                }
+                Node::Branch { children } if !path.is_empty() => {
+                    match &children[path[0] as usize] {
+                        Some(child) => {
+                            path = &path[1..];
+                            child
+                        }
+                        None => break,
+                    }
+                }
+                _ => break,
+            };
+            let encoded = child.encode();
+            if encoded.len() >= 32 {
+                proof.push(encoded);
+            }
+            node = child;
+        }
+        proof
+    }
+}
+
+impl Node {
+    /// Inserts a value below this node. Conflicting keys are detected before
+    /// anything is changed, so a failed insertion leaves the trie intact.
+    fn insert(&mut self, path: &[u8], value: Vec<u8>) -> Result<()> {
+        match self {
+            Node::Empty => {
+                *self = Node::Leaf {
+                    path: path.to_vec(),
+                    value,
+                };
+            }
+            Node::Leaf {
+                path: leaf_path,
+                value: leaf_value,
+            } => {
+                if leaf_path == path {
+                    *leaf_value = value;
+                    return Ok(());
+                }
+                let common = common_prefix(leaf_path, path);
+                if common == leaf_path.len() || common == path.len() {
+                    anyhow::bail!("Trie keys must not be prefixes of each other");
+                }
+                let mut children: [Option<Box<Node>>; 16] = Default::default();
+                children[leaf_path[common] as usize] = Some(Box::new(Node::Leaf {
+                    path: leaf_path[common + 1..].to_vec(),
+                    value: core::mem::take(leaf_value),
+                }));
+                children[path[common] as usize] = Some(Box::new(Node::Leaf {
+                    path: path[common + 1..].to_vec(),
+                    value,
+                }));
+                *self = Node::extend(&path[..common], Node::Branch { children });
+            }
+            Node::Extension {
+                path: prefix,
+                child,
+            } => {
+                let common = common_prefix(prefix, path);
+                if common == prefix.len() {
+                    return child.insert(&path[common..], value);
+                }
+                if common == path.len() {
+                    anyhow::bail!("Trie keys must not be prefixes of each other");
+                }
+                let mut children: [Option<Box<Node>>; 16] = Default::default();
+                children[prefix[common] as usize] = Some(Box::new(Node::extend(
+                    &prefix[common + 1..],
+                    core::mem::take(child),
+                )));
+                children[path[common] as usize] = Some(Box::new(Node::Leaf {
+                    path: path[common + 1..].to_vec(),
+                    value,
+                }));
+                *self = Node::extend(&path[..common], Node::Branch { children });
+            }
+            Node::Branch { children } => {
+                let Some((&nibble, rest)) = path.split_first() else {
+                    anyhow::bail!("Trie keys must not be prefixes of each other");
+                };
+                children[nibble as usize]
+                    .get_or_insert_with(Default::default)
+                    .insert(rest, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Places `node` below an extension for `path`, unless `path` is empty.
+    fn extend(path: &[u8], node: Node) -> Node {
+        if path.is_empty() {
+            node
+        } else {
+            Node::Extension {
+                path: path.to_vec(),
+                child: Box::new(node),
+            }
+        }
+    }
+
+    /// RLP-encodes the node.
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Node::Empty => out.push(EMPTY_STRING_CODE),
+            Node::Leaf { path, value } => {
+                timewave_rlp::encode_list::<&[u8], [u8]>(
+                    &[&encode_path(path, true), value],
+                    &mut out,
+                );
+            }
+            Node::Extension { path, child } => {
+                let mut payload = Vec::new();
+                encode_path(path, false).as_slice().encode(&mut payload);
+                payload.extend_from_slice(&<RlpNode>::from_rlp(&child.encode()));
+                out.extend(timewave_rlp::encode_raw_list(&[payload]));
+            }
+            Node::Branch { children } => {
+                let mut payload = Vec::new();
+                for child in children {
+                    match child {
+                        Some(child) => {
+                            payload.extend_from_slice(&<RlpNode>::from_rlp(&child.encode()))
+                        }
+                        None => payload.push(EMPTY_STRING_CODE),
+                    }
+                }
+                payload.push(EMPTY_STRING_CODE);
+                out.extend(timewave_rlp::encode_raw_list(&[payload]));
+            }
+        }
+        out
+    }
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Hex-prefix encodes a path of nibbles, flagging leaves and odd lengths.
+fn encode_path(path: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 0x20 } else { 0x00 };
+    let mut out = vec![];
+    let rest = if path.len() % 2 == 1 {
+        out.push(flag | 0x10 | path[0]);
+        &path[1..]
+    } else {
+        out.push(flag);
+        path
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timewave_trie::verify::verify_proof;
+
+    fn index_key(index: usize) -> Vec<u8> {
+        timewave_rlp::encode(index)
+    }
+
+    #[test]
+    fn test_empty_trie() {
+        let trie = TrieBuilder::new();
+        assert!(trie.is_empty());
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH_BYTES);
+        assert!(trie.proof(&[0x01]).is_empty());
+    }
+
+    #[test]
+    fn test_single_leaf_matches_mock() {
+        let key = digest_keccak(&[0x01; 32]);
+        let value = vec![0x82, 0x04, 0xd2];
+        let mut trie = TrieBuilder::new();
+        trie.insert(&key, value.clone()).unwrap();
+        let (_, root) = crate::mock::storage_proof_for(&[0x01; 32], &value);
+        assert_eq!(trie.root().to_vec(), root);
+    }
+
+    #[test]
+    fn test_proofs_verify() {
+        let mut trie = TrieBuilder::new();
+        for index in 0..130 {
+            trie.insert(&index_key(index), vec![index as u8 + 1; 1 + index % 40])
+                .unwrap();
+        }
+        let root = trie.root();
+        for index in 0..130 {
+            let key = index_key(index);
+            verify_proof(
+                &root,
+                Nibbles::unpack(&key),
+                Some(vec![index as u8 + 1; 1 + index % 40]),
+                trie.proof(&key),
+            )
+            .unwrap();
+        }
+        let missing = index_key(500);
+        verify_proof(&root, Nibbles::unpack(&missing), None, trie.proof(&missing)).unwrap();
+    }
+
+    #[test]
+    fn test_insert_replaces_value() {
+        let mut trie = TrieBuilder::new();
+        trie.insert(&[0x12, 0x34], vec![0x01]).unwrap();
+        trie.insert(&[0x12, 0x56], vec![0x02]).unwrap();
+        let root = trie.root();
+        trie.insert(&[0x12, 0x34], vec![0x03]).unwrap();
+        assert_ne!(trie.root(), root);
+        trie.insert(&[0x12, 0x34], vec![0x01]).unwrap();
+        assert_eq!(trie.root(), root);
+    }
+
+    #[test]
+    fn test_rejects_prefix_keys_and_empty_values() {
+        let mut trie = TrieBuilder::new();
+        trie.insert(&[0x12, 0x34], vec![0x01]).unwrap();
+        assert!(trie.insert(&[0x12], vec![0x02]).is_err());
+        assert!(trie.insert(&[0x12, 0x34, 0x56], vec![0x02]).is_err());
+        assert!(trie.insert(&[0x56], vec![]).is_err());
+        // failed insertions leave the trie unchanged
+        let mut expected = TrieBuilder::new();
+        expected.insert(&[0x12, 0x34], vec![0x01]).unwrap();
+        assert_eq!(trie, expected);
+    }
+
+    #[cfg(feature = "no-zkvm")]
+    #[test]
+    fn test_root_matches_hash_builder() {
+        let values: Vec<Vec<u8>> = (0..130).map(|i| vec![i as u8; 1 + i % 40]).collect();
+        let (expected_root, expected_proofs) =
+            crate::ethereum_rpc::rpc::index_trie_proofs(&values, &[0, 5, 127, 128, 129]);
+        let mut trie = TrieBuilder::new();
+        for (index, value) in values.iter().enumerate() {
+            trie.insert(&index_key(index), value.clone()).unwrap();
+        }
+        assert_eq!(trie.root(), expected_root.0);
+        for (index, expected) in [0, 5, 127, 128, 129].into_iter().zip(expected_proofs) {
+            let expected: Vec<Vec<u8>> = expected.into_iter().filter(|n| n.len() >= 32).collect();
+            assert_eq!(trie.proof(&index_key(index)), expected);
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use core::ops::Range;
 pub const EMPTY_STRING_CODE: u8 = 0x80;
 pub const MAX: usize = 33;
+/// Ethereum inlines a child node in its parent if its RLP is shorter than this.
+pub const INLINE_THRESHOLD: usize = 32;
 pub const CHILD_INDEX_RANGE: Range<u8> = 0..16;
 
+pub const EXTENSION_EVEN_FLAG: u8 = 0x00;
+pub const EXTENSION_ODD_FLAG: u8 = 0x10;
 pub const EVEN_FLAG: u8 = 0x20;
 pub const ODD_FLAG: u8 = 0x30;
 
@@ -0,0 +1,37 @@
+//! Hash functions for Merkle-Patricia tries.
+//!
+//! Ethereum's tries reference nodes by their keccak256 hash, which is what
+//! [`verify_proof`](super::verify::verify_proof) uses. Tries built the same way
+//! over another hash function, e.g. on a testnet using blake3, can be verified
+//! by passing a [`TrieHasher`] to
+//! [`verify_proof_with_hasher`](super::verify::verify_proof_with_hasher).
+
+use crate::{
+    merkle_lib::digest_keccak,
+    timewave_trie::constants::{EMPTY_ROOT_HASH_BYTES, EMPTY_STRING_CODE},
+};
+
+/// The hash function a trie references its nodes by.
+pub trait TrieHasher {
+    /// Hashes an RLP-encoded trie node.
+    fn hash(data: &[u8]) -> [u8; 32];
+
+    /// Returns the root of an empty trie, the hash of an empty RLP string.
+    fn empty_root() -> [u8; 32] {
+        Self::hash(&[EMPTY_STRING_CODE])
+    }
+}
+
+/// The keccak256 hasher of Ethereum's tries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeccakHasher;
+
+impl TrieHasher for KeccakHasher {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        digest_keccak(data)
+    }
+
+    fn empty_root() -> [u8; 32] {
+        EMPTY_ROOT_HASH_BYTES
+    }
+}
@@ -1,3 +1,5 @@
+pub mod builder;
 pub(crate) mod constants;
+pub mod hasher;
 pub mod types;
 pub mod verify;
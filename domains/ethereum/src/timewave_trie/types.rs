@@ -6,12 +6,21 @@ extern crate alloc;
 use alloc::vec::Vec;
 
 use crate::{
-    merkle_lib::digest_keccak,
     timewave_rlp::{self, alloy_bytes::Bytes, Decodable},
+    timewave_trie::hasher::{KeccakHasher, TrieHasher},
 };
 
+/// A reference to a trie node: the node's RLP itself if it is shorter than 32
+/// bytes, otherwise the RLP of its 32-byte hash.
+///
+/// `N` bounds the length of a reference. In Ethereum tries a reference is
+/// always at most 33 bytes, since any node of 32 bytes or more is replaced by
+/// its hash, so the default [`MAX`] never rejects a valid Ethereum node. Other
+/// MPT variants that inline larger nodes can raise the bound, decode their
+/// nodes as [`TrieNode<N>`] and reference them with [`RlpNode::from_rlp_inlining`].
+/// `N` must be at least [`MAX`] to hold a hash.
 #[derive(PartialEq, Eq)]
-pub struct RlpNode(ArrayVec<u8, MAX>);
+pub struct RlpNode<const N: usize = MAX>(ArrayVec<u8, N>);
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
 pub struct TrieMask(u16);
@@ -26,14 +35,14 @@ impl TrieMask {
     }
 }
 
-impl timewave_rlp::Decodable for RlpNode {
+impl<const N: usize> timewave_rlp::Decodable for RlpNode<N> {
     fn decode(buf: &mut &[u8]) -> timewave_rlp::Result<Self> {
         let bytes = timewave_rlp::Header::decode_bytes(buf, false)?;
         Self::from_raw_rlp(bytes)
     }
 }
 
-impl core::ops::Deref for RlpNode {
+impl<const N: usize> core::ops::Deref for RlpNode<N> {
     type Target = [u8];
 
     #[inline]
@@ -42,30 +51,32 @@ impl core::ops::Deref for RlpNode {
     }
 }
 
-impl core::ops::DerefMut for RlpNode {
+impl<const N: usize> core::ops::DerefMut for RlpNode<N> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 
-impl AsRef<[u8]> for RlpNode {
+impl<const N: usize> AsRef<[u8]> for RlpNode<N> {
     #[inline]
     fn as_ref(&self) -> &[u8] {
         &self.0
     }
 }
 
-impl core::fmt::Debug for RlpNode {
+impl<const N: usize> core::fmt::Debug for RlpNode<N> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "RlpNode({})", hex::encode_prefixed(&self.0))
     }
 }
 
-impl RlpNode {
+impl<const N: usize> RlpNode<N> {
+    const HOLDS_HASH: () = assert!(N >= MAX, "RlpNode must be able to hold a hash");
+
     /// Creates a new RLP-encoded node from the given data.
     ///
-    /// Returns `None` if the data is too large (greater than 33 bytes).
+    /// Returns `None` if the data is too large (greater than `N` bytes).
     #[inline]
     pub fn from_raw(data: &[u8]) -> Option<Self> {
         let mut arr = ArrayVec::new();
@@ -83,21 +94,47 @@ impl RlpNode {
     #[doc(alias = "rlp_node")]
     #[inline]
     pub fn from_rlp(rlp: &[u8]) -> Self {
-        if rlp.len() < 32 {
-            // SAFETY: `rlp` is less than max capacity (33).
+        Self::from_rlp_with::<KeccakHasher>(rlp)
+    }
+
+    /// Given an RLP-encoded node, returns it either as `rlp(node)` or `rlp(H(rlp(node)))`.
+    #[inline]
+    pub fn from_rlp_with<H: TrieHasher>(rlp: &[u8]) -> Self {
+        Self::from_rlp_inlining::<H, INLINE_THRESHOLD>(rlp)
+    }
+
+    /// Given an RLP-encoded node, returns it as `rlp(node)` if it is shorter than
+    /// `INLINE` bytes, and as `rlp(H(rlp(node)))` otherwise.
+    ///
+    /// Ethereum inlines nodes shorter than [`INLINE_THRESHOLD`]; MPT variants that
+    /// inline larger nodes raise `INLINE` together with `N`, which must hold any
+    /// inlined node, i.e. `INLINE` is at most `N + 1`.
+    #[inline]
+    pub fn from_rlp_inlining<H: TrieHasher, const INLINE: usize>(rlp: &[u8]) -> Self {
+        let () = Self::HOLDS_HASH;
+        const {
+            assert!(
+                INLINE <= N + 1,
+                "RlpNode must be able to hold an inlined node"
+            )
+        };
+        if rlp.len() < INLINE {
+            // SAFETY: `rlp` is shorter than `INLINE`, so at most `N` bytes long.
             unsafe { Self::from_raw(rlp).unwrap_unchecked() }
         } else {
-            Self::word_rlp(&digest_keccak(rlp))
+            Self::word_rlp(&H::hash(rlp))
         }
     }
 
     /// RLP-encodes the given word and returns it as a new RLP node.
     #[inline]
     pub fn word_rlp(word: &[u8; 32]) -> Self {
-        let mut arr = [0u8; 33];
-        arr[0] = EMPTY_STRING_CODE + 32;
-        arr[1..].copy_from_slice(word.as_slice());
-        Self(ArrayVec::from(arr))
+        let () = Self::HOLDS_HASH;
+        let mut arr = ArrayVec::new();
+        arr.push(EMPTY_STRING_CODE + 32);
+        // SAFETY: the capacity `N` is at least 33.
+        unsafe { arr.try_extend_from_slice(word).unwrap_unchecked() };
+        Self(arr)
     }
 
     /// Returns the RLP-encoded node as a slice.
@@ -107,9 +144,12 @@ impl RlpNode {
     }
 
     /// Returns hash if this is an RLP-encoded hash
+    ///
+    /// A hash is an RLP string, while an inlined node is an RLP list, so the two
+    /// are told apart even if a raised bound lets an inlined node be 33 bytes long.
     #[inline]
     pub fn as_hash(&self) -> Option<[u8; 32]> {
-        if self.len() == 32 + 1 {
+        if self.len() == 32 + 1 && self.0[0] == EMPTY_STRING_CODE + 32 {
             Some(self.0[1..].try_into().unwrap())
         } else {
             None
@@ -122,14 +162,16 @@ impl RlpNode {
 /// This enum defines the different types of nodes that can exist in an Ethereum
 /// state trie. Each variant represents a specific node type with its associated
 /// data structure.
+///
+/// `N` bounds the length of child references, see [`RlpNode`].
 #[derive(Debug)]
-pub enum TrieNode {
+pub enum TrieNode<const N: usize = MAX> {
     /// An empty root node, representing an empty trie
     EmptyRoot,
     /// A branch node that can have up to 16 children
-    Branch(BranchNode),
+    Branch(BranchNode<N>),
     /// An extension node that shares a common prefix with its child
-    Extension(ExtensionNode),
+    Extension(ExtensionNode<N>),
     /// A leaf node containing the final value
     Leaf(LeafNode),
 }
@@ -139,33 +181,35 @@ pub enum TrieNode {
 /// Branch nodes are used when multiple paths diverge at a particular point in
 /// the trie. Each branch node can have up to 16 children, one for each possible
 /// nibble value (0-15).
-#[derive(Debug, Default)]
-pub struct BranchNode {
+#[derive(Debug)]
+pub struct BranchNode<const N: usize = MAX> {
     /// The collection of RLP encoded children.
-    pub stack: Vec<RlpNode>,
+    pub stack: Vec<RlpNode<N>>,
     /// The bitmask indicating the presence of children at the respective nibble positions
     pub state_mask: TrieMask,
+    /// The value stored in the branch itself (item 16). Always `None` for Ethereum tries.
+    pub value: Option<Vec<u8>>,
 }
 
 /// A reference to a branch node's data.
 ///
 /// This struct provides a view into a branch node's data without taking ownership.
 /// It's used for efficient traversal and verification of the trie structure.
-pub struct BranchNodeRef<'a> {
+pub struct BranchNodeRef<'a, const N: usize = MAX> {
     /// Reference to the collection of RLP encoded nodes.
     /// NOTE: The referenced stack might have more items than the number of children
     /// for this node. We should only ever access items starting from
     /// [BranchNodeRef::first_child_index].
-    pub stack: &'a [RlpNode],
+    pub stack: &'a [RlpNode<N>],
     /// Reference to bitmask indicating the presence of children at
     /// the respective nibble positions.
     pub state_mask: TrieMask,
 }
 
-impl<'a> BranchNodeRef<'a> {
+impl<'a, const N: usize> BranchNodeRef<'a, N> {
     /// Create a new branch node from the stack of nodes.
     #[inline]
-    pub const fn new(stack: &'a [RlpNode], state_mask: TrieMask) -> Self {
+    pub const fn new(stack: &'a [RlpNode<N>], state_mask: TrieMask) -> Self {
         Self { stack, state_mask }
     }
 
@@ -177,8 +221,18 @@ impl<'a> BranchNodeRef<'a> {
     }
 }
 
-impl BranchNode {
-    pub fn as_ref(&self) -> BranchNodeRef<'_> {
+impl<const N: usize> Default for BranchNode<N> {
+    fn default() -> Self {
+        Self {
+            stack: Vec::new(),
+            state_mask: TrieMask::default(),
+            value: None,
+        }
+    }
+}
+
+impl<const N: usize> BranchNode<N> {
+    pub fn as_ref(&self) -> BranchNodeRef<'_, N> {
         BranchNodeRef::new(&self.stack, self.state_mask)
     }
 }
@@ -189,15 +243,15 @@ impl BranchNode {
 /// between multiple paths. They contain a key (the shared prefix) and a pointer
 /// to the next node.
 #[derive(Debug)]
-pub struct ExtensionNode {
+pub struct ExtensionNode<const N: usize = MAX> {
     /// The key for this extension node.
     pub key: Nibbles,
     /// A pointer to the child node.
-    pub child: RlpNode,
+    pub child: RlpNode<N>,
 }
 
-impl ExtensionNode {
-    pub fn new(key: Nibbles, child: RlpNode) -> Self {
+impl<const N: usize> ExtensionNode<N> {
+    pub fn new(key: Nibbles, child: RlpNode<N>) -> Self {
         Self { key, child }
     }
 }
@@ -220,8 +274,23 @@ impl LeafNode {
     }
 }
 
-impl Decodable for TrieNode {
+impl<const N: usize> Decodable for TrieNode<N> {
     fn decode(buf: &mut &[u8]) -> timewave_rlp::Result<Self> {
+        Self::decode_node(buf, false)
+    }
+}
+
+impl<const N: usize> TrieNode<N> {
+    /// Decodes a trie node, accepting a value in item 16 of branch nodes.
+    ///
+    /// Ethereum's Merkle-Patricia tries never store values in branch nodes, so the
+    /// strict [`Decodable`] implementation rejects them. Other MPT variants (and some
+    /// test fixtures) do, and this decoder captures the value in [`BranchNode::value`].
+    pub fn decode_with_branch_values(buf: &mut &[u8]) -> timewave_rlp::Result<Self> {
+        Self::decode_node(buf, true)
+    }
+
+    fn decode_node(buf: &mut &[u8], allow_branch_values: bool) -> timewave_rlp::Result<Self> {
         let mut items = match timewave_rlp::Header::decode_raw(buf)? {
             timewave_rlp::PayloadView::List(list) => list,
             timewave_rlp::PayloadView::String(val) => {
@@ -241,9 +310,12 @@ impl Decodable for TrieNode {
                 for (idx, item) in items.into_iter().enumerate() {
                     if idx == 16 {
                         if item != [EMPTY_STRING_CODE] {
-                            return Err(timewave_rlp::Error::Custom(
-                                "branch node values are not supported",
-                            ));
+                            if !allow_branch_values {
+                                return Err(timewave_rlp::Error::Custom(
+                                    "branch node values are not supported",
+                                ));
+                            }
+                            branch.value = Some(Bytes::decode(&mut &item[..])?.into());
                         }
                     } else if item != [EMPTY_STRING_CODE] {
                         branch.stack.push(RlpNode::from_raw_rlp(item)?);
@@ -264,8 +336,8 @@ impl Decodable for TrieNode {
                 let key_flag = encoded_key[0] & 0xf0;
                 // Retrieve first byte. If it's [Some], then the nibbles are odd.
                 let first = match key_flag {
-                    ODD_FLAG => Some(encoded_key[0] & 0x0f),
-                    EVEN_FLAG => None,
+                    ODD_FLAG | EXTENSION_ODD_FLAG => Some(encoded_key[0] & 0x0f),
+                    EVEN_FLAG | EXTENSION_EVEN_FLAG => None,
                     _ => return Err(timewave_rlp::Error::Custom("node is not extension or leaf")),
                 };
 
@@ -304,3 +376,39 @@ pub(crate) fn unpack_path_to_nibbles(first: Option<u8>, rest: &[u8]) -> Nibbles
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes a root branch whose child under nibble 0 is the given node inline.
+    fn branch_with_inline_child(child: &[u8]) -> Vec<u8> {
+        let mut items: Vec<&[u8]> = alloc::vec![&[EMPTY_STRING_CODE]; 17];
+        items[0] = child;
+        timewave_rlp::encode_raw_list(&items)
+    }
+
+    #[test]
+    fn test_child_reference_bound() {
+        let mut leaf = Vec::new();
+        timewave_rlp::encode_list::<&[u8], [u8]>(&[&[0x20], &[0xab; 36]], &mut leaf);
+        assert!(leaf.len() > MAX);
+        let branch = branch_with_inline_child(&leaf);
+
+        assert!(<TrieNode as Decodable>::decode(&mut branch.as_slice()).is_err());
+        let TrieNode::<64>::Branch(decoded) =
+            TrieNode::<64>::decode(&mut branch.as_slice()).unwrap()
+        else {
+            panic!("expected a branch node");
+        };
+        assert_eq!(decoded.stack[0].as_slice(), leaf.as_slice());
+
+        // a larger bound still references nodes of 32 bytes or more by hash
+        let word = RlpNode::<64>::word_rlp(&[0x11; 32]);
+        assert_eq!(word.as_hash(), Some([0x11; 32]));
+        assert_eq!(
+            RlpNode::<64>::from_rlp(&leaf).as_slice(),
+            <RlpNode>::from_rlp(&leaf).as_slice()
+        );
+    }
+}
@@ -10,7 +10,8 @@ use crate::timewave_rlp::{Decodable, EMPTY_STRING_CODE};
 use crate::{
     timewave_rlp::{self, alloy_bytes::Bytes},
     timewave_trie::{
-        constants::{CHILD_INDEX_RANGE, EMPTY_ROOT_HASH_BYTES},
+        constants::{CHILD_INDEX_RANGE, INLINE_THRESHOLD, MAX},
+        hasher::{KeccakHasher, TrieHasher},
         types::{BranchNode, RlpNode, TrieNode},
     },
 };
@@ -37,6 +38,12 @@ pub enum ProofVerificationError {
         /// Expected value.
         expected: Option<Bytes>,
     },
+    /// The proof ends at a hashed node on the key's path, before reaching
+    /// either the key or a point where the key provably diverges.
+    IncompleteProof {
+        /// Path at which the proof ends.
+        path: Nibbles,
+    },
     /// Encountered unexpected empty root node.
     UnexpectedEmptyRoot,
     /// Error during RLP decoding of trie node.
@@ -44,9 +51,23 @@ pub enum ProofVerificationError {
 }
 
 extern crate alloc;
-use alloc::vec::Vec;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
 use nybbles::Nibbles;
 
+/// Emits a `tracing::debug!` event for a step of the trie walk.
+///
+/// Expands to nothing unless the `trace` feature is enabled, so tracing adds no
+/// cost to regular (and in particular zkVM) builds.
+macro_rules! trace_step {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "trace")]
+        tracing::debug!($($arg)*);
+    };
+}
+
 /// Verify the proof for given key value pair against the provided state root.
 ///
 /// This function verifies that a given key-value pair exists in the state trie
@@ -58,7 +79,8 @@ use nybbles::Nibbles;
 /// * `root` - The expected state root hash to verify against
 /// * `key` - The key to verify the proof for
 /// * `expected_value` - The expected value for the key, or None for exclusion proofs
-/// * `proof` - An iterator over the proof nodes
+/// * `proof` - An iterator over the proof nodes, e.g. `&[Vec<u8>]` or `&[Bytes]`;
+///   nodes are read in place without being copied
 ///
 /// # Returns
 /// * `Ok(())` if the proof is valid
@@ -67,16 +89,108 @@ use nybbles::Nibbles;
 /// # Errors
 /// * `RootMismatch` if the computed root doesn't match the expected root
 /// * `ValueMismatch` if the value doesn't match the expected value
+/// * `IncompleteProof` if the proof stops at a hashed node that the key's path still
+///   leads through, so it proves neither the value nor its absence
 /// * `UnexpectedEmptyRoot` if an empty root node is encountered unexpectedly
 /// * `Rlp` if there's an error decoding the RLP data
-pub fn verify_proof<'a, I>(
+#[allow(clippy::result_large_err)]
+pub fn verify_proof<I>(
+    root: &[u8; 32],
+    key: Nibbles,
+    expected_value: Option<Vec<u8>>,
+    proof: I,
+) -> Result<(), ProofVerificationError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    verify_proof_inner::<KeccakHasher, MAX, INLINE_THRESHOLD, I>(
+        root,
+        key,
+        expected_value,
+        proof,
+        false,
+    )
+}
+
+/// Verify the proof for given key value pair in a trie hashed with `H`.
+///
+/// Behaves like [`verify_proof`], which is this function with the
+/// [`KeccakHasher`] of Ethereum's tries, but references nodes and computes the
+/// empty root with `H`.
+#[allow(clippy::result_large_err)]
+pub fn verify_proof_with_hasher<H, I>(
     root: &[u8; 32],
     key: Nibbles,
     expected_value: Option<Vec<u8>>,
     proof: I,
 ) -> Result<(), ProofVerificationError>
 where
-    I: IntoIterator<Item = &'a Bytes>,
+    H: TrieHasher,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    verify_proof_inner::<H, MAX, INLINE_THRESHOLD, I>(root, key, expected_value, proof, false)
+}
+
+/// Verify the proof for given key value pair, allowing values stored in branch nodes.
+///
+/// Behaves like [`verify_proof`], but decodes branch nodes with
+/// [`TrieNode::decode_with_branch_values`] so that a key terminating at a branch node
+/// is matched against the branch's value. This is required for non-Ethereum MPT
+/// variants; Ethereum tries should use the strict [`verify_proof`].
+#[allow(clippy::result_large_err)]
+pub fn verify_proof_with_branch_values<I>(
+    root: &[u8; 32],
+    key: Nibbles,
+    expected_value: Option<Vec<u8>>,
+    proof: I,
+) -> Result<(), ProofVerificationError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    verify_proof_inner::<KeccakHasher, MAX, INLINE_THRESHOLD, I>(
+        root,
+        key,
+        expected_value,
+        proof,
+        true,
+    )
+}
+
+/// Verify the proof for given key value pair in a trie that inlines larger nodes.
+///
+/// Behaves like [`verify_proof`], which is this function with Ethereum's bounds,
+/// but inlines child nodes shorter than `INLINE` bytes rather than 32, and
+/// accepts child references of up to `N` bytes rather than 33, see [`RlpNode`].
+/// `INLINE` is at most `N + 1`.
+#[allow(clippy::result_large_err)]
+pub fn verify_proof_with_node_bound<const N: usize, const INLINE: usize, I>(
+    root: &[u8; 32],
+    key: Nibbles,
+    expected_value: Option<Vec<u8>>,
+    proof: I,
+) -> Result<(), ProofVerificationError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    verify_proof_inner::<KeccakHasher, N, INLINE, I>(root, key, expected_value, proof, false)
+}
+
+#[allow(clippy::result_large_err)]
+fn verify_proof_inner<H, const N: usize, const INLINE: usize, I>(
+    root: &[u8; 32],
+    key: Nibbles,
+    expected_value: Option<Vec<u8>>,
+    proof: I,
+    allow_branch_values: bool,
+) -> Result<(), ProofVerificationError>
+where
+    H: TrieHasher,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
 {
     let mut proof = proof.into_iter().peekable();
     // If the proof is empty or contains only an empty node, the expected value must be None.
@@ -85,7 +199,8 @@ where
         .map(|node| node.as_ref() == [EMPTY_STRING_CODE])
         .unwrap_or(true)
     {
-        return if root == &EMPTY_ROOT_HASH_BYTES {
+        let empty_root = H::empty_root();
+        return if *root == empty_root {
             if expected_value.is_none() {
                 Ok(())
             } else {
@@ -97,18 +212,26 @@ where
             }
         } else {
             Err(ProofVerificationError::RootMismatch {
-                got: EMPTY_ROOT_HASH_BYTES,
+                got: empty_root,
                 expected: *root,
             })
         };
     }
 
     let mut walked_path = Nibbles::with_capacity(key.len());
-    let mut last_decoded_node = Some(NodeDecodingResult::Node(RlpNode::word_rlp(root)));
-    for node in proof {
+    let mut last_decoded_node = Some(NodeDecodingResult::<N>::Node(RlpNode::word_rlp(root)));
+    for (depth, node) in proof.enumerate() {
+        let node = node.as_ref();
+        trace_step!(walked_path = ?walked_path, node = ?node, "visiting proof node");
         // Check if the node that we just decoded (or root node, if we just started) matches
-        // the expected node from the proof.
-        if Some(RlpNode::from_rlp(node).as_slice()) != last_decoded_node.as_deref() {
+        // the expected node from the proof. The root is always referenced by its hash,
+        // even when it is short enough that a parent would embed it in place.
+        let node_ref: RlpNode<N> = if depth == 0 {
+            RlpNode::word_rlp(&H::hash(node))
+        } else {
+            RlpNode::from_rlp_inlining::<H, INLINE>(node)
+        };
+        if Some(node_ref.as_slice()) != last_decoded_node.as_deref() {
             let got = Some(Bytes::copy_from_slice(node));
             let expected = last_decoded_node.as_deref().map(Bytes::copy_from_slice);
             return Err(ProofVerificationError::ValueMismatch {
@@ -119,13 +242,28 @@ where
         }
 
         // Decode the next node from the proof.
-        last_decoded_node = match TrieNode::decode(&mut &node[..]).unwrap() {
-            TrieNode::Branch(branch) => process_branch(branch, &mut walked_path, &key)?,
+        last_decoded_node = match decode_node(node, allow_branch_values)? {
+            TrieNode::Branch(branch) => {
+                trace_step!(state_mask = ?branch.state_mask, "decoded branch node");
+                process_branch(branch, &mut walked_path, &key, allow_branch_values)?
+            }
             TrieNode::Extension(extension) => {
+                trace_step!(key = ?extension.key, "decoded extension node");
                 walked_path.extend_from_slice(&extension.key);
-                Some(NodeDecodingResult::Node(extension.child))
+                if extension.child.as_hash().is_some() {
+                    Some(NodeDecodingResult::Node(extension.child))
+                } else {
+                    // The child branch is encoded in-place, so it is not a separate proof node.
+                    process_extension_child(
+                        &extension.child,
+                        &mut walked_path,
+                        &key,
+                        allow_branch_values,
+                    )?
+                }
             }
             TrieNode::Leaf(leaf) => {
+                trace_step!(key = ?leaf.key, "decoded leaf node");
                 walked_path.extend_from_slice(&leaf.key);
                 Some(NodeDecodingResult::Value(leaf.value))
             }
@@ -134,6 +272,15 @@ where
     }
 
     // Last decoded node should have the key that we are looking for.
+    trace_step!(walked_path = ?walked_path, key = ?key, "finished walking proof");
+    // A key is only absent at an empty branch slot, or at a leaf or extension whose
+    // path diverges from it. A hashed child that the key still leads through may
+    // hold the key, so a proof truncated there proves nothing.
+    if matches!(last_decoded_node, Some(NodeDecodingResult::Node(_)))
+        && key.starts_with(&walked_path)
+    {
+        return Err(ProofVerificationError::IncompleteProof { path: walked_path });
+    }
     last_decoded_node = last_decoded_node.filter(|_| walked_path == key);
     if last_decoded_node.as_deref() == expected_value.as_deref() {
         Ok(())
@@ -152,14 +299,14 @@ where
 /// proof verification. It can either be a node that needs further processing
 /// or a value that has been found.
 #[derive(Debug, PartialEq, Eq)]
-enum NodeDecodingResult {
+enum NodeDecodingResult<const N: usize = MAX> {
     /// A node that needs further processing
-    Node(RlpNode),
+    Node(RlpNode<N>),
     /// A value that has been found
     Value(Vec<u8>),
 }
 
-impl Deref for NodeDecodingResult {
+impl<const N: usize> Deref for NodeDecodingResult<N> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
@@ -170,6 +317,20 @@ impl Deref for NodeDecodingResult {
     }
 }
 
+/// Decode a trie node, in strict Ethereum mode unless branch values are allowed.
+#[allow(clippy::result_large_err)]
+fn decode_node<const N: usize>(
+    node: &[u8],
+    allow_branch_values: bool,
+) -> Result<TrieNode<N>, ProofVerificationError> {
+    let result = if allow_branch_values {
+        TrieNode::decode_with_branch_values(&mut &node[..])
+    } else {
+        TrieNode::decode(&mut &node[..])
+    };
+    result.map_err(ProofVerificationError::Rlp)
+}
+
 /// Process a branch node during proof verification.
 ///
 /// This function handles the processing of a branch node during proof verification,
@@ -179,76 +340,606 @@ impl Deref for NodeDecodingResult {
 /// * `branch` - The branch node to process
 /// * `walked_path` - The path that has been traversed so far
 /// * `key` - The complete key being verified
+/// * `allow_branch_values` - Whether a key ending at this branch yields the branch's value
 ///
 /// # Returns
 /// * `Ok(Some(NodeDecodingResult))` if a node or value was found
 /// * `Ok(None)` if no matching node was found
 /// * `Err(ProofVerificationError)` if an error occurred during processing
 #[inline]
-fn process_branch(
-    mut branch: BranchNode,
+#[allow(clippy::result_large_err)]
+fn process_branch<const N: usize>(
+    mut branch: BranchNode<N>,
     walked_path: &mut Nibbles,
     key: &Nibbles,
-) -> Result<Option<NodeDecodingResult>, ProofVerificationError> {
-    if let Some(next) = key.get(walked_path.len()) {
-        let mut stack_ptr = branch.as_ref().first_child_index();
-        for index in CHILD_INDEX_RANGE {
-            if branch.state_mask.is_bit_set(index) {
-                if index == *next {
-                    walked_path.push(*next);
-
-                    let child = branch.stack.remove(stack_ptr);
-                    if child.len() == 33 {
-                        return Ok(Some(NodeDecodingResult::Node(child)));
-                    } else {
-                        // This node is encoded in-place.
-                        match TrieNode::decode(&mut &child[..]).unwrap() {
-                            TrieNode::Branch(child_branch) => {
-                                // An in-place branch node can only have direct, also in-place
-                                // encoded, leaf children, as anything else overflows this branch
-                                // node, making it impossible to be encoded in-place in the first
-                                // place.
-                                return process_branch(child_branch, walked_path, key);
-                            }
-                            TrieNode::Extension(child_extension) => {
-                                walked_path.extend_from_slice(&child_extension.key);
-
-                                // If the extension node's child is a hash, the encoded extension
-                                // node itself wouldn't fit for encoding in-place. So this extension
-                                // node must have a child that is also encoded in-place.
-                                //
-                                // Since the child cannot be a leaf node (otherwise this node itself
-                                // would be a leaf node, not an extension node), the child must be a
-                                // branch node encoded in-place.
-                                match TrieNode::decode(&mut &child_extension.child[..]).unwrap() {
-                                    TrieNode::Branch(extension_child_branch) => {
-                                        return process_branch(
-                                            extension_child_branch,
-                                            walked_path,
-                                            key,
-                                        );
-                                    }
-                                    node @ (TrieNode::EmptyRoot
-                                    | TrieNode::Extension(_)
-                                    | TrieNode::Leaf(_)) => {
-                                        unreachable!("unexpected extension node child: {node:?}")
-                                    }
-                                }
-                            }
-                            TrieNode::Leaf(child_leaf) => {
-                                walked_path.extend_from_slice(&child_leaf.key);
-                                return Ok(Some(NodeDecodingResult::Value(child_leaf.value)));
-                            }
-                            TrieNode::EmptyRoot => {
-                                return Err(ProofVerificationError::UnexpectedEmptyRoot)
-                            }
+    allow_branch_values: bool,
+) -> Result<Option<NodeDecodingResult<N>>, ProofVerificationError> {
+    let Some(next) = key.get(walked_path.len()) else {
+        // The key terminates at this branch, so the branch's own value is the result.
+        return Ok(branch.value.take().map(NodeDecodingResult::Value));
+    };
+    let mut stack_ptr = branch.as_ref().first_child_index();
+    for index in CHILD_INDEX_RANGE {
+        if branch.state_mask.is_bit_set(index) {
+            if index == *next {
+                trace_step!(index, walked_path = ?walked_path, "taking branch child");
+                walked_path.push(*next);
+
+                let child = branch.stack.remove(stack_ptr);
+                if child.as_hash().is_some() {
+                    return Ok(Some(NodeDecodingResult::Node(child)));
+                } else {
+                    // This node is encoded in-place.
+                    trace_step!(child = ?child, "decoding in-place child node");
+                    match decode_node(&child, allow_branch_values)? {
+                        TrieNode::Branch(child_branch) => {
+                            // An in-place branch node can only have direct, also in-place
+                            // encoded, leaf children, as anything else overflows this branch
+                            // node, making it impossible to be encoded in-place in the first
+                            // place.
+                            return process_branch(
+                                child_branch,
+                                walked_path,
+                                key,
+                                allow_branch_values,
+                            );
                         }
-                    };
-                }
-                stack_ptr += 1;
+                        TrieNode::Extension(child_extension) => {
+                            walked_path.extend_from_slice(&child_extension.key);
+
+                            // If the extension node's child is a hash, the encoded extension
+                            // node itself wouldn't fit for encoding in-place. So this extension
+                            // node must have a child that is also encoded in-place.
+                            return process_extension_child(
+                                &child_extension.child,
+                                walked_path,
+                                key,
+                                allow_branch_values,
+                            );
+                        }
+                        TrieNode::Leaf(child_leaf) => {
+                            walked_path.extend_from_slice(&child_leaf.key);
+                            return Ok(Some(NodeDecodingResult::Value(child_leaf.value)));
+                        }
+                        TrieNode::EmptyRoot => {
+                            return Err(ProofVerificationError::UnexpectedEmptyRoot)
+                        }
+                    }
+                };
             }
+            stack_ptr += 1;
         }
     }
 
     Ok(None)
 }
+
+/// Process the in-place encoded child of an extension node.
+///
+/// Since the child cannot be a leaf node (otherwise the extension itself would be
+/// a leaf node) or another extension node (otherwise the two would be merged), the
+/// child must be a branch node. Anything else can only come from a malformed proof
+/// and is rejected.
+#[allow(clippy::result_large_err)]
+fn process_extension_child<const N: usize>(
+    child: &[u8],
+    walked_path: &mut Nibbles,
+    key: &Nibbles,
+    allow_branch_values: bool,
+) -> Result<Option<NodeDecodingResult<N>>, ProofVerificationError> {
+    trace_step!(child = ?child, "decoding in-place extension child");
+    match decode_node(child, allow_branch_values)? {
+        TrieNode::Branch(branch) => process_branch(branch, walked_path, key, allow_branch_values),
+        TrieNode::EmptyRoot | TrieNode::Extension(_) | TrieNode::Leaf(_) => {
+            Err(ProofVerificationError::Rlp(timewave_rlp::Error::Custom(
+                "extension node child is not a branch node",
+            )))
+        }
+    }
+}
+
+/// The longest path a trie walk follows, that of a 32-byte key.
+const MAX_PATH_NIBBLES: usize = 64;
+
+/// List every key and value that a set of proof nodes proves under `root`.
+///
+/// Walks the trie from the root through all supplied nodes, in any order, and
+/// returns the full path and value of each leaf reached, ordered by path. This
+/// is meant for inspecting proofs, e.g. a multiproof whose keys are not known in
+/// advance. Nodes are matched by their hash, so nodes that do not belong to the
+/// trie are ignored, and a partial proof lists only the leaves it reaches:
+/// subtries referenced by a hash that no supplied node has are skipped.
+///
+/// The nodes are untrusted, so the walk is bounded: each node is expanded once
+/// per path, paths end after 64 nibbles, and the walk expands at most 16 hashed
+/// nodes per supplied node. A node set whose branches point many children at the
+/// same node is rejected rather than walked exponentially often.
+///
+/// # Arguments
+/// * `root` - The root hash of the trie
+/// * `nodes` - The RLP-encoded proof nodes
+///
+/// # Returns
+/// The path and value of every reachable leaf, empty if the root node is not supplied
+///
+/// # Errors
+/// * `Rlp` if a reachable node cannot be decoded, a path exceeds 64 nibbles or
+///   the walk exceeds its bound
+#[allow(clippy::result_large_err)]
+pub fn collect_proven_entries<I>(
+    root: &[u8; 32],
+    nodes: I,
+) -> Result<Vec<(Nibbles, Vec<u8>)>, ProofVerificationError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let nodes: Vec<I::Item> = nodes.into_iter().collect();
+    let by_hash: BTreeMap<[u8; 32], &[u8]> = nodes
+        .iter()
+        .map(|node| (KeccakHasher::hash(node.as_ref()), node.as_ref()))
+        .collect();
+    let mut collector = EntryCollector {
+        expansions_left: by_hash.len().saturating_mul(CHILD_INDEX_RANGE.len()),
+        by_hash: &by_hash,
+        visited: BTreeSet::new(),
+        entries: Vec::new(),
+    };
+    collector.collect_child(&RlpNode::word_rlp(root), Nibbles::default())?;
+    Ok(collector.entries)
+}
+
+/// The state of a walk over a set of untrusted proof nodes.
+struct EntryCollector<'a> {
+    /// The supplied nodes, by their hash
+    by_hash: &'a BTreeMap<[u8; 32], &'a [u8]>,
+    /// The hashed nodes expanded so far, with the path they were reached at
+    visited: BTreeSet<([u8; 32], Nibbles)>,
+    /// How many more hashed nodes the walk may expand
+    expansions_left: usize,
+    /// The leaves found so far
+    entries: Vec<(Nibbles, Vec<u8>)>,
+}
+
+impl EntryCollector<'_> {
+    /// Collect the leaves below an encoded node, whose path from the root is `path`.
+    #[allow(clippy::result_large_err)]
+    fn collect_entries(
+        &mut self,
+        node: &[u8],
+        mut path: Nibbles,
+    ) -> Result<(), ProofVerificationError> {
+        match decode_node(node, false)? {
+            TrieNode::Branch(branch) => {
+                let mut stack_ptr = branch.as_ref().first_child_index();
+                for index in CHILD_INDEX_RANGE {
+                    if branch.state_mask.is_bit_set(index) {
+                        let mut child_path = path.clone();
+                        child_path.push(index);
+                        self.collect_child(&branch.stack[stack_ptr], child_path)?;
+                        stack_ptr += 1;
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                path.extend_from_slice(&extension.key);
+                self.collect_child(&extension.child, path)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                path.extend_from_slice(&leaf.key);
+                check_path_len(&path)?;
+                self.entries.push((path, leaf.value));
+            }
+            // An empty trie proves no entries.
+            TrieNode::EmptyRoot => {}
+        }
+        Ok(())
+    }
+
+    /// Collect the leaves below a child reference, skipping hashed children that were not supplied.
+    #[allow(clippy::result_large_err)]
+    fn collect_child(
+        &mut self,
+        child: &RlpNode,
+        path: Nibbles,
+    ) -> Result<(), ProofVerificationError> {
+        check_path_len(&path)?;
+        let Some(hash) = child.as_hash() else {
+            // This node is encoded in-place.
+            return self.collect_entries(child, path);
+        };
+        let Some(node) = self.by_hash.get(&hash).copied() else {
+            return Ok(());
+        };
+        if !self.visited.insert((hash, path.clone())) {
+            return Ok(());
+        }
+        self.expansions_left =
+            self.expansions_left
+                .checked_sub(1)
+                .ok_or(ProofVerificationError::Rlp(timewave_rlp::Error::Custom(
+                    "proof nodes reach more trie positions than they can hold",
+                )))?;
+        self.collect_entries(node, path)
+    }
+}
+
+/// Rejects a path longer than any key of an Ethereum trie.
+#[allow(clippy::result_large_err)]
+fn check_path_len(path: &Nibbles) -> Result<(), ProofVerificationError> {
+    if path.len() > MAX_PATH_NIBBLES {
+        return Err(ProofVerificationError::Rlp(timewave_rlp::Error::Custom(
+            "trie path is longer than 64 nibbles",
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{merkle_lib::digest_keccak, timewave_rlp};
+
+    /// Builds a root branch holding `branch_value`, with a hashed leaf under nibble `0xc`
+    /// for the key `[0xcd]`.
+    fn branch_value_trie(branch_value: &[u8], leaf_value: &[u8]) -> (Bytes, Bytes, [u8; 32]) {
+        let mut leaf = Vec::new();
+        timewave_rlp::encode_list::<&[u8], [u8]>(&[&[0x3d], leaf_value], &mut leaf);
+        let leaf_hash = digest_keccak(&leaf);
+        let mut items: Vec<&[u8]> = alloc::vec![&[]; 17];
+        items[0xc] = &leaf_hash;
+        items[16] = branch_value;
+        let mut branch = Vec::new();
+        timewave_rlp::encode_list::<&[u8], [u8]>(&items, &mut branch);
+        let root = digest_keccak(&branch);
+        (Bytes::from(branch), Bytes::from(leaf), root)
+    }
+
+    #[test]
+    fn test_branch_value_rejected_in_strict_mode() {
+        let (branch, _, root) = branch_value_trie(b"branch-value", &[0x42; 40]);
+        let result = verify_proof(
+            &root,
+            Nibbles::default(),
+            Some(b"branch-value".to_vec()),
+            [&branch],
+        );
+        assert!(matches!(result, Err(ProofVerificationError::Rlp(_))));
+    }
+
+    #[test]
+    fn test_verify_proof_with_branch_values() {
+        let (branch, leaf, root) = branch_value_trie(b"branch-value", &[0x42; 40]);
+        verify_proof_with_branch_values(
+            &root,
+            Nibbles::default(),
+            Some(b"branch-value".to_vec()),
+            [&branch],
+        )
+        .unwrap();
+        verify_proof_with_branch_values(
+            &root,
+            Nibbles::unpack([0xcd]),
+            Some([0x42; 40].to_vec()),
+            [&branch, &leaf],
+        )
+        .unwrap();
+        assert!(verify_proof_with_branch_values(
+            &root,
+            Nibbles::default(),
+            Some(b"other-value".to_vec()),
+            [&branch],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_verify_proof_accepts_owned_and_borrowed_nodes() {
+        let (branch, leaf, root) = branch_value_trie(b"branch-value", &[0x42; 40]);
+        let key = Nibbles::unpack([0xcd]);
+        let owned: Vec<Vec<u8>> = alloc::vec![branch.to_vec(), leaf.to_vec()];
+        verify_proof_with_branch_values(&root, key.clone(), Some([0x42; 40].to_vec()), &owned)
+            .unwrap();
+        let slices: [&[u8]; 2] = [&branch, &leaf];
+        verify_proof_with_branch_values(&root, key.clone(), Some([0x42; 40].to_vec()), slices)
+            .unwrap();
+        verify_proof_with_branch_values(&root, key, Some([0x42; 40].to_vec()), [branch, leaf])
+            .unwrap();
+    }
+
+    /// Builds a branch node from its (already encoded) children, indexed by nibble.
+    fn branch_node(children: &[(usize, &[u8])]) -> Vec<u8> {
+        let mut items: Vec<&[u8]> = alloc::vec![&[EMPTY_STRING_CODE]; 17];
+        for (nibble, child) in children {
+            items[*nibble] = child;
+        }
+        timewave_rlp::encode_raw_list(&items)
+    }
+
+    /// Builds a leaf or extension node from its compact-encoded path and encoded child.
+    fn short_node(encoded_path: &[u8], child: &[u8]) -> Vec<u8> {
+        timewave_rlp::encode_raw_list(&[&timewave_rlp::encode(encoded_path), child])
+    }
+
+    // Leaves under keccak-hashed storage keys are too long to be encoded in place,
+    // so these vectors use raw keys, as in the receipts and transactions tries.
+
+    #[test]
+    fn test_verify_proof_in_place_leaf() {
+        // a tiny value whose leaf is embedded in the root branch, next to a hashed leaf
+        let small_leaf = short_node(&[0x31], &timewave_rlp::encode([0x01u8].as_slice()));
+        let large_leaf = short_node(&[0x32], &timewave_rlp::encode([0x42u8; 40].as_slice()));
+        assert!(small_leaf.len() < 32);
+        let large_hash = timewave_rlp::encode(digest_keccak(&large_leaf).as_slice());
+        let branch = branch_node(&[(0xa, &small_leaf), (0xb, &large_hash)]);
+        let root = digest_keccak(&branch);
+
+        verify_proof(
+            &root,
+            Nibbles::unpack([0xa1]),
+            Some(alloc::vec![0x01]),
+            [&branch],
+        )
+        .unwrap();
+        verify_proof(
+            &root,
+            Nibbles::unpack([0xb2]),
+            Some(alloc::vec![0x42; 40]),
+            [&branch, &large_leaf],
+        )
+        .unwrap();
+        assert!(verify_proof(
+            &root,
+            Nibbles::unpack([0xa1]),
+            Some(alloc::vec![0x02]),
+            [&branch]
+        )
+        .is_err());
+        // the in-place leaf's path diverges from the key, so the key is absent
+        verify_proof(&root, Nibbles::unpack([0xa2]), None, [&branch]).unwrap();
+    }
+
+    #[test]
+    fn test_verify_proof_extension_with_in_place_branch() {
+        // keys 0x1234 and 0x1235 share the prefix 123, held by the root extension; the
+        // branch below it and both of its leaves are small enough to be embedded
+        let leaf_4 = short_node(&[0x20], &timewave_rlp::encode([0x01u8].as_slice()));
+        let leaf_5 = short_node(&[0x20], &timewave_rlp::encode([0x02u8].as_slice()));
+        let branch = branch_node(&[(0x4, &leaf_4), (0x5, &leaf_5)]);
+        assert!(branch.len() < 32);
+        let extension = short_node(&[0x11, 0x23], &branch);
+        let root = digest_keccak(&extension);
+
+        verify_proof(
+            &root,
+            Nibbles::unpack([0x12, 0x34]),
+            Some(alloc::vec![0x01]),
+            [&extension],
+        )
+        .unwrap();
+        verify_proof(
+            &root,
+            Nibbles::unpack([0x12, 0x35]),
+            Some(alloc::vec![0x02]),
+            [&extension],
+        )
+        .unwrap();
+        verify_proof(&root, Nibbles::unpack([0x12, 0x36]), None, [&extension]).unwrap();
+    }
+
+    #[test]
+    fn test_verify_proof_extension_with_hashed_branch() {
+        let leaf_4 = short_node(&[0x30], &timewave_rlp::encode([0x01u8; 40].as_slice()));
+        let leaf_5 = short_node(&[0x30], &timewave_rlp::encode([0x02u8; 40].as_slice()));
+        let hash_4 = timewave_rlp::encode(digest_keccak(&leaf_4).as_slice());
+        let hash_5 = timewave_rlp::encode(digest_keccak(&leaf_5).as_slice());
+        let branch = branch_node(&[(0x4, &hash_4), (0x5, &hash_5)]);
+        let branch_hash = timewave_rlp::encode(digest_keccak(&branch).as_slice());
+        let extension = short_node(&[0x00, 0x12, 0x3f], &branch_hash);
+        let root = digest_keccak(&extension);
+
+        verify_proof(
+            &root,
+            Nibbles::unpack([0x12, 0x3f, 0x50]),
+            Some(alloc::vec![0x02; 40]),
+            [&extension, &branch, &leaf_5],
+        )
+        .unwrap();
+        // an exclusion proof that ends at the extension, whose path diverges from the key
+        verify_proof(
+            &root,
+            Nibbles::unpack([0x12, 0x40, 0x00]),
+            None,
+            [&extension],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_verify_proof_short_root() {
+        // a trie holding one tiny entry, whose root node is still referenced by its hash
+        let leaf = short_node(&[0x20, 0xab], &timewave_rlp::encode([0x01u8].as_slice()));
+        assert!(leaf.len() < 32);
+        let root = digest_keccak(&leaf);
+        verify_proof(
+            &root,
+            Nibbles::unpack([0xab]),
+            Some(alloc::vec![0x01]),
+            [&leaf],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_verify_proof_rejects_extension_over_leaf() {
+        // a malformed in-place extension whose child is a leaf must not panic
+        let leaf = short_node(&[0x20], &timewave_rlp::encode([0x01u8].as_slice()));
+        let extension = short_node(&[0x13], &leaf);
+        let branch = branch_node(&[(0x1, &extension), (0x2, &leaf)]);
+        let root = digest_keccak(&branch);
+        let result = verify_proof(
+            &root,
+            Nibbles::unpack([0x13]),
+            Some(alloc::vec![0x01]),
+            [&branch],
+        );
+        assert!(matches!(result, Err(ProofVerificationError::Rlp(_))));
+    }
+
+    #[test]
+    fn test_verify_proof_with_node_bound() {
+        // a trie that inlines nodes shorter than 40 bytes: a 36-byte leaf and a
+        // 33-byte leaf, as long as a hash reference, both inlined in the root branch
+        let leaf_a = short_node(&[0x31], &timewave_rlp::encode([0x07u8; 33].as_slice()));
+        let leaf_c = short_node(&[0x33], &timewave_rlp::encode([0x09u8; 30].as_slice()));
+        let large_leaf = short_node(&[0x32], &timewave_rlp::encode([0x42u8; 40].as_slice()));
+        assert_eq!((leaf_a.len(), leaf_c.len()), (36, 33));
+        let large_hash = timewave_rlp::encode(digest_keccak(&large_leaf).as_slice());
+        let branch = branch_node(&[(0xa, &leaf_a), (0xb, &large_hash), (0xc, &leaf_c)]);
+        let root = digest_keccak(&branch);
+
+        verify_proof_with_node_bound::<64, 40, _>(
+            &root,
+            Nibbles::unpack([0xa1]),
+            Some(alloc::vec![0x07; 33]),
+            [&branch],
+        )
+        .unwrap();
+        verify_proof_with_node_bound::<64, 40, _>(
+            &root,
+            Nibbles::unpack([0xc3]),
+            Some(alloc::vec![0x09; 30]),
+            [&branch],
+        )
+        .unwrap();
+        verify_proof_with_node_bound::<64, 40, _>(
+            &root,
+            Nibbles::unpack([0xb2]),
+            Some(alloc::vec![0x42; 40]),
+            [&branch, &large_leaf],
+        )
+        .unwrap();
+        // Ethereum's bound of 33 bytes rejects the inlined 36-byte leaf
+        assert!(matches!(
+            verify_proof(
+                &root,
+                Nibbles::unpack([0xa1]),
+                Some(alloc::vec![0x07; 33]),
+                [&branch]
+            ),
+            Err(ProofVerificationError::Rlp(_))
+        ));
+    }
+
+    struct Sha3Hasher;
+
+    impl TrieHasher for Sha3Hasher {
+        fn hash(data: &[u8]) -> [u8; 32] {
+            use sha3::Digest;
+            sha3::Sha3_256::digest(data).into()
+        }
+    }
+
+    #[test]
+    fn test_verify_proof_with_hasher() {
+        // a branch referencing a leaf by its hash, and the root by the branch hash
+        let leaf = short_node(&[0x3d], &timewave_rlp::encode([0x07u8; 40].as_slice()));
+        let hashed_leaf = timewave_rlp::encode(Sha3Hasher::hash(&leaf).as_slice());
+        let branch = branch_node(&[(0xc, &hashed_leaf), (0x1, &hashed_leaf)]);
+        let root = Sha3Hasher::hash(&branch);
+        let key = Nibbles::unpack([0xcd]);
+        verify_proof_with_hasher::<Sha3Hasher, _>(
+            &root,
+            key.clone(),
+            Some(alloc::vec![0x07; 40]),
+            [&branch, &leaf],
+        )
+        .unwrap();
+        assert!(verify_proof(&root, key, Some(alloc::vec![0x07; 40]), [&branch, &leaf]).is_err());
+
+        let empty_root = Sha3Hasher::empty_root();
+        let empty: [&[u8]; 0] = [];
+        verify_proof_with_hasher::<Sha3Hasher, _>(
+            &empty_root,
+            Nibbles::unpack([0xcd]),
+            None,
+            empty,
+        )
+        .unwrap();
+        assert_eq!(
+            KeccakHasher::empty_root(),
+            KeccakHasher::hash(&[EMPTY_STRING_CODE])
+        );
+    }
+
+    #[test]
+    fn test_collect_proven_entries() {
+        use crate::timewave_trie::builder::TrieBuilder;
+
+        let mut trie = TrieBuilder::new();
+        let keys: Vec<[u8; 32]> = (0u8..30).map(|i| digest_keccak(&[i])).collect();
+        for (i, key) in keys.iter().enumerate() {
+            trie.insert(key, timewave_rlp::encode(i as u64 + 1))
+                .unwrap();
+        }
+        let root = trie.root();
+
+        // the union of the proofs of three keys, duplicates and all
+        let nodes: Vec<Vec<u8>> = [3, 11, 27]
+            .iter()
+            .flat_map(|&i| trie.proof(&keys[i]))
+            .collect();
+        let entries = collect_proven_entries(&root, &nodes).unwrap();
+        for i in [3, 11, 27] {
+            let path = Nibbles::unpack(keys[i]);
+            let value = entries.iter().find(|(p, _)| *p == path).map(|(_, v)| v);
+            assert_eq!(value, Some(&timewave_rlp::encode(i as u64 + 1)));
+        }
+        // every entry found is a real key of the trie, and listed once, in order
+        assert!(entries.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(entries
+            .iter()
+            .all(|(p, _)| keys.iter().any(|k| Nibbles::unpack(k) == *p)));
+        assert!(entries.len() < keys.len());
+
+        // the proof of a single key lists at least that key; a wrong root lists nothing
+        let single = collect_proven_entries(&root, trie.proof(&keys[5])).unwrap();
+        assert!(single.iter().any(|(p, _)| *p == Nibbles::unpack(keys[5])));
+        assert!(collect_proven_entries(&[0u8; 32], &nodes)
+            .unwrap()
+            .is_empty());
+        let empty = collect_proven_entries(&KeccakHasher::empty_root(), [[EMPTY_STRING_CODE]]);
+        assert!(empty.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_collect_proven_entries_is_bounded() {
+        // every branch points all 16 children at the branch below it, which would
+        // reach the leaf at the bottom along 16^depth paths
+        let leaf = short_node(&[0x20], &timewave_rlp::encode([0x42u8; 40].as_slice()));
+        let mut node = leaf;
+        let mut nodes = Vec::from([node.clone()]);
+        for _ in 0..20 {
+            let hash = timewave_rlp::encode(digest_keccak(&node).as_slice());
+            let children: Vec<(usize, &[u8])> =
+                (0..16).map(|nibble| (nibble, hash.as_slice())).collect();
+            node = branch_node(&children);
+            nodes.push(node.clone());
+        }
+        let root = digest_keccak(&node);
+        assert!(matches!(
+            collect_proven_entries(&root, &nodes),
+            Err(ProofVerificationError::Rlp(_))
+        ));
+
+        // a leaf whose path runs past the 64 nibbles of a 32-byte key
+        let long_leaf = short_node(
+            &[[0x20].as_slice(), &[0xab; 32]].concat(),
+            &timewave_rlp::encode([0x01u8].as_slice()),
+        );
+        let hash = timewave_rlp::encode(digest_keccak(&long_leaf).as_slice());
+        let branch = branch_node(&[(0x1, &hash)]);
+        let root = digest_keccak(&branch);
+        assert!(collect_proven_entries(&root, [&branch, &long_leaf]).is_err());
+    }
+}
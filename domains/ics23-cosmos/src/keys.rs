@@ -9,15 +9,203 @@ use core::fmt;
 use std::fmt::Display;
 
 use anyhow::Context;
+use ics23::{HostFunctionsManager, HostFunctionsProvider};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "no-zkvm")]
 use {cosmrs::AccountId, cosmwasm_std::Addr, std::str::FromStr};
 
+/// Prefix of the `wasm` module store under which contract metadata is kept.
+pub const WASM_CONTRACT_INFO_PREFIX: u8 = 0x02;
+
+/// Prefix of the `wasm` module store under which contract state is kept.
+pub const WASM_CONTRACT_STORE_PREFIX: u8 = 0x03;
+
+/// Prefix of the `staking` module store under which delegations are kept.
+pub const STAKING_DELEGATION_PREFIX: u8 = 0x31;
+
+/// Builds the `wasm` store key of a cw-storage-plus `Map` entry.
+///
+/// The layout is `0x03 || contract || len(namespace) || namespace || key`, where the
+/// namespace length is a two-byte big-endian prefix as written by cw-storage-plus.
+///
+/// # Arguments
+/// * `contract` - The canonical (raw) address bytes of the contract
+/// * `namespace` - The namespace the map was declared with, e.g. `b"balances"`
+/// * `key` - The raw key of the map entry
+///
+/// # Errors
+/// Returns an error if the namespace is longer than `u16::MAX` bytes
+pub fn cosmwasm_namespace_key(contract: &[u8], namespace: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    let namespace_len = u16::try_from(namespace.len()).map_err(|_| {
+        anyhow::anyhow!(
+            "Namespace length {} exceeds the maximum of {} bytes",
+            namespace.len(),
+            u16::MAX
+        )
+    })?;
+    let mut key_bytes = Vec::with_capacity(1 + contract.len() + 2 + namespace.len() + key.len());
+    key_bytes.push(WASM_CONTRACT_STORE_PREFIX);
+    key_bytes.extend_from_slice(contract);
+    key_bytes.extend_from_slice(&namespace_len.to_be_bytes());
+    key_bytes.extend_from_slice(namespace);
+    key_bytes.extend_from_slice(key);
+    Ok(key_bytes)
+}
+
+/// Joins the components of a composite cw-storage-plus key, e.g. of a
+/// `Map<(&Addr, u64), _>`.
+///
+/// Every component but the last is prefixed with its length as two big-endian
+/// bytes, as `PrimaryKey::joined_key` does. A key of a single component is that
+/// component unchanged.
+///
+/// # Arguments
+/// * `components` - The raw bytes of each key component, in tuple order
+///
+/// # Errors
+/// Returns an error if a length-prefixed component is longer than `u16::MAX` bytes
+pub fn cosmwasm_joined_key(components: &[&[u8]]) -> Result<Vec<u8>> {
+    let Some((last, prefixed)) = components.split_last() else {
+        return Ok(Vec::new());
+    };
+    let mut key = Vec::new();
+    for component in prefixed {
+        let len = u16::try_from(component.len()).map_err(|_| {
+            anyhow::anyhow!(
+                "Key component length {} exceeds the maximum of {} bytes",
+                component.len(),
+                u16::MAX
+            )
+        })?;
+        key.extend_from_slice(&len.to_be_bytes());
+        key.extend_from_slice(component);
+    }
+    key.extend_from_slice(last);
+    Ok(key)
+}
+
+/// The store of a Cosmos SDK module that a key is proven in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ics23StorePrefix {
+    /// The `bank` module store
+    Bank,
+    /// The `wasm` module store
+    Wasm,
+    /// The `staking` module store
+    Staking,
+    /// Any other module store, by name
+    Custom(String),
+}
+
+impl Ics23StorePrefix {
+    /// Returns the name of the store as used in the multistore.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Ics23StorePrefix::Bank => "bank",
+            Ics23StorePrefix::Wasm => "wasm",
+            Ics23StorePrefix::Staking => "staking",
+            Ics23StorePrefix::Custom(name) => name,
+        }
+    }
+}
+
+impl From<&str> for Ics23StorePrefix {
+    fn from(name: &str) -> Self {
+        match name {
+            "bank" => Ics23StorePrefix::Bank,
+            "wasm" => Ics23StorePrefix::Wasm,
+            "staking" => Ics23StorePrefix::Staking,
+            _ => Ics23StorePrefix::Custom(name.to_string()),
+        }
+    }
+}
+
+impl Display for Ics23StorePrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How the key of an entry is turned into the key its store commits to.
+///
+/// The Cosmos SDK module stores on Neutron (`bank`, `staking`, `wasm`, ...) commit
+/// their keys unhashed, and cw-storage-plus length-prefixes the components of
+/// composite keys rather than hashing them, so [`KeyHashing::Raw`] covers
+/// every `Map`, `IndexedMap` and contract info entry. [`KeyHashing::Sha256`]
+/// is for contracts that key a map by the sha256 digest of a composite key
+/// (e.g. `Map<&[u8], _>` saved under `sha256(owner || id)`), where the
+/// key carries the preimage and verification hashes it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyHashing {
+    /// The store commits the key as is
+    #[default]
+    Raw,
+    /// The store commits the first `unhashed_len` bytes of the key (the store and
+    /// map prefix) followed by the sha256 digest of the remaining bytes
+    Sha256 {
+        /// The number of leading key bytes that are committed unhashed
+        unhashed_len: usize,
+    },
+}
+
+impl KeyHashing {
+    /// Returns `true` for [`KeyHashing::Raw`].
+    pub fn is_raw(&self) -> bool {
+        *self == KeyHashing::Raw
+    }
+
+    /// Applies the policy to a key.
+    ///
+    /// # Arguments
+    /// * `key` - The raw key bytes
+    ///
+    /// # Returns
+    /// The key the store commits to
+    ///
+    /// # Errors
+    /// Returns an error if the key is shorter than its unhashed prefix
+    pub fn apply(&self, key: &[u8]) -> Result<Vec<u8>> {
+        self.apply_with::<HostFunctionsManager>(key)
+    }
+
+    /// Applies the policy to a key, hashing with the host functions `H`.
+    ///
+    /// # Arguments
+    /// * `key` - The raw key bytes
+    ///
+    /// # Returns
+    /// The key the store commits to
+    ///
+    /// # Errors
+    /// Returns an error if the key is shorter than its unhashed prefix
+    pub fn apply_with<H: HostFunctionsProvider>(&self, key: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            KeyHashing::Raw => Ok(key.to_vec()),
+            KeyHashing::Sha256 { unhashed_len } => {
+                let (prefix, preimage) =
+                    key.split_at_checked(*unhashed_len).with_context(|| {
+                        format!(
+                            "Key is {} bytes, shorter than its unhashed prefix of {} bytes",
+                            key.len(),
+                            unhashed_len
+                        )
+                    })?;
+                let mut hashed = prefix.to_vec();
+                hashed.extend_from_slice(&H::sha2_256(preimage));
+                Ok(hashed)
+            }
+        }
+    }
+}
+
 /// Represents a key used to query state on the Ics23 blockchain.
 ///
 /// The key consists of a prefix (e.g., "bank", "wasm") and a key string that identifies
-/// the specific state to query. The prefix_len field is used for serialization purposes.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// the specific state to query. The prefix_len field is used for serialization purposes
+/// and must equal the length of the prefix; use [`Ics23Key::new`] to have it derived.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "Ics23KeyFields")]
 pub struct Ics23Key {
     /// The prefix indicating the type of state (e.g., "bank", "wasm")
     pub prefix: String,
@@ -25,70 +213,221 @@ pub struct Ics23Key {
     pub prefix_len: usize,
     /// The specific key identifying the state to query
     pub key: String,
+    /// How `key` maps to the key committed in the store
+    #[serde(default, skip_serializing_if = "KeyHashing::is_raw")]
+    pub key_hashing: KeyHashing,
+}
+
+/// The serialized fields of an [`Ics23Key`], checked for consistency on deserialization.
+#[derive(Deserialize)]
+struct Ics23KeyFields {
+    prefix: String,
+    prefix_len: usize,
+    key: String,
+    #[serde(default)]
+    key_hashing: KeyHashing,
 }
 
+impl TryFrom<Ics23KeyFields> for Ics23Key {
+    type Error = anyhow::Error;
+
+    fn try_from(fields: Ics23KeyFields) -> Result<Self> {
+        if fields.prefix_len != fields.prefix.len() {
+            anyhow::bail!(
+                "Key has prefix_len {} but its prefix {:?} is {} bytes long",
+                fields.prefix_len,
+                fields.prefix,
+                fields.prefix.len()
+            );
+        }
+        Ok(Self {
+            prefix: fields.prefix,
+            prefix_len: fields.prefix_len,
+            key: fields.key,
+            key_hashing: fields.key_hashing,
+        })
+    }
+}
+
+/// Separates the key from the unhashed prefix length of a [`KeyHashing::Sha256`]
+/// key in its string form. Keys are hex encoded, so it cannot occur in the key.
+const SHA256_KEY_SEPARATOR: &str = ":sha256:";
+
+/// Formats the key as its prefix length, prefix and key, followed by the hashing
+/// policy for keys that are not committed raw, so that keys committed
+/// differently never share a string form.
 impl Display for Ics23Key {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:03}{}{}", self.prefix_len, self.prefix, self.key)
+        write!(f, "{:03}{}{}", self.prefix.len(), self.prefix, self.key)?;
+        match self.key_hashing {
+            KeyHashing::Raw => Ok(()),
+            KeyHashing::Sha256 { unhashed_len } => {
+                write!(f, "{}{}", SHA256_KEY_SEPARATOR, unhashed_len)
+            }
+        }
     }
 }
 
 impl Ics23Key {
+    /// Creates a key in a module store, deriving `prefix_len` from the prefix.
+    ///
+    /// # Arguments
+    /// * `prefix` - The store the key lives in
+    /// * `key` - The hex-encoded key within the store
+    ///
+    /// # Returns
+    /// A new `Ics23Key` instance
+    pub fn new(prefix: Ics23StorePrefix, key: String) -> Self {
+        let prefix = prefix.as_str().to_string();
+        Self {
+            prefix_len: prefix.len(),
+            prefix,
+            key,
+            key_hashing: KeyHashing::Raw,
+        }
+    }
+
+    /// Sets how the key maps to the key committed in the store.
+    ///
+    /// # Arguments
+    /// * `key_hashing` - The hashing policy of the store entry
+    ///
+    /// # Returns
+    /// The key with the policy applied on verification
+    pub fn with_key_hashing(mut self, key_hashing: KeyHashing) -> Self {
+        self.key_hashing = key_hashing;
+        self
+    }
+
+    /// Returns the key committed in the store, applying the key's hashing policy.
+    ///
+    /// # Errors
+    /// Returns an error if the key is not hex encoded or is shorter than its
+    /// unhashed prefix
+    pub fn store_key(&self) -> Result<Vec<u8>> {
+        self.store_key_with::<HostFunctionsManager>()
+    }
+
+    /// Returns the key committed in the store, hashing with the host functions `H`.
+    ///
+    /// # Errors
+    /// Returns an error if the key is not hex encoded or is shorter than its
+    /// unhashed prefix
+    pub fn store_key_with<H: HostFunctionsProvider>(&self) -> Result<Vec<u8>> {
+        let key =
+            hex::decode(&self.key).with_context(|| format!("Key {} is not hex encoded", self))?;
+        self.key_hashing.apply_with::<H>(&key)
+    }
+
+    /// Returns the store the key lives in.
+    pub fn store_prefix(&self) -> Ics23StorePrefix {
+        Ics23StorePrefix::from(self.prefix.as_str())
+    }
+
     /// Deserializes a string back into a Ics23Key.
+    ///
+    /// # Errors
+    /// Returns an error if the string does not start with a three-digit prefix
+    /// length followed by a prefix of that length, or its hashing policy is malformed
     pub fn from_string(encoded: &str) -> Result<Self> {
-        let prefix_len: usize = encoded[..3].parse().context("Invalid prefix length")?;
-        let prefix = &encoded[3..(3 + prefix_len)];
+        let prefix_len: usize = encoded
+            .get(..3)
+            .context("Missing prefix length")?
+            .parse()
+            .context("Invalid prefix length")?;
+        let prefix = encoded
+            .get(3..(3 + prefix_len))
+            .with_context(|| format!("Key is shorter than its prefix length {}", prefix_len))?;
         let key = &encoded[(3 + prefix_len)..];
+        let (key, key_hashing) = match key.split_once(SHA256_KEY_SEPARATOR) {
+            Some((key, unhashed_len)) => (
+                key,
+                KeyHashing::Sha256 {
+                    unhashed_len: unhashed_len
+                        .parse()
+                        .context("Invalid unhashed key length")?,
+                },
+            ),
+            None => (key, KeyHashing::Raw),
+        };
 
-        Ok(Ics23Key {
-            prefix: prefix.to_string(),
-            prefix_len,
-            key: key.to_string(),
-        })
+        Ok(
+            Self::new(Ics23StorePrefix::from(prefix), key.to_string())
+                .with_key_hashing(key_hashing),
+        )
     }
     // create a new neutron key for a mapping from address:value that lives under some contract
     // this is useful for examples where users are assigned balances
     // store: name of the storage module (bank, wasm, etc)
     // key: the key of the mapping
     // contract_address: the address of the contract under which the mapping lives
+    // errors if contract_address is not a valid bech32 address
     #[cfg(feature = "no-zkvm")]
-    pub fn new_wasm_account_mapping(store: &[u8], key: &str, contract_address: &str) -> Self {
-        let mut key_bytes = vec![0x03];
-        key_bytes.append(
-            &mut AccountId::from_str(contract_address)
-                .expect("Invalid contract address")
-                .to_bytes(),
-        );
-        let length_bytes = (store.len() as u32).to_be_bytes();
-        let relevant_bytes = [length_bytes[2], length_bytes[3]];
-        key_bytes.extend_from_slice(&relevant_bytes);
-        key_bytes.extend_from_slice(store);
-        key_bytes.append(&mut Addr::unchecked(key).as_bytes().to_vec());
-        Self {
-            prefix: "wasm".to_string(),
-            prefix_len: 4,
-            key: hex::encode(&key_bytes),
-        }
+    pub fn new_wasm_account_mapping(
+        store: &[u8],
+        key: &str,
+        contract_address: &str,
+    ) -> Result<Self> {
+        Self::new_wasm_namespace_key(store, Addr::unchecked(key).as_bytes(), contract_address)
+    }
+
+    // create a new neutron key for an entry of a cw-storage-plus `Map` under a WASM contract
+    // namespace: the namespace the map was declared with, e.g. b"balances"
+    // key: the raw key of the map entry
+    // contract_address: the address of the contract under which the map lives
+    #[cfg(feature = "no-zkvm")]
+    pub fn new_wasm_namespace_key(
+        namespace: &[u8],
+        key: &[u8],
+        contract_address: &str,
+    ) -> Result<Self> {
+        let contract = AccountId::from_str(contract_address)
+            .map_err(|e| anyhow::anyhow!("Invalid contract address: {e}"))?;
+        let key_bytes = cosmwasm_namespace_key(&contract.to_bytes(), namespace, key)?;
+        Ok(Self::new(Ics23StorePrefix::Wasm, hex::encode(&key_bytes)))
+    }
+
+    // create a new neutron key for an entry of a cw-storage-plus `IndexedMap` under a WASM contract
+    // an `IndexedMap::new(pk_namespace, indexes)` stores its entries in a plain `Map` under
+    // `pk_namespace` itself, so the key is that of a `Map` entry; cw-storage-plus adds no
+    // suffix such as `_pk`. the indexes live under their own namespaces (by convention
+    // `<pk_namespace>__<index>`) and only map index values back to primary keys, so the
+    // entry itself is proven through its primary key
+    // contract: the address of the contract under which the map lives
+    // pk_namespace: the namespace the indexed map was declared with, e.g. b"orders"
+    // pk: the components of the primary key, a single one for non-tuple keys
+    #[cfg(feature = "no-zkvm")]
+    pub fn new_indexed_map_primary(
+        contract: &str,
+        pk_namespace: &[u8],
+        pk: &[&[u8]],
+    ) -> Result<Self> {
+        Self::new_wasm_namespace_key(pk_namespace, &cosmwasm_joined_key(pk)?, contract)
     }
 
     // create a new neutron key for a stored value under a WASM contract
     // this is useful for accessing simple key-value storage in a contract
     // key: the key of the mapping e.g. "shares"
     // contract_address: the address of the contract where the value is stored
+    // errors if contract_address is not a valid bech32 address
     #[cfg(feature = "no-zkvm")]
-    pub fn new_wasm_stored_value(key: &str, contract_address: &str) -> Self {
-        let mut key_bytes = vec![0x03];
-        key_bytes.append(
-            &mut AccountId::from_str(contract_address)
-                .expect("Invalid contract address")
-                .to_bytes(),
-        );
+    pub fn new_wasm_stored_value(key: &str, contract_address: &str) -> Result<Self> {
+        let contract = AccountId::from_str(contract_address)
+            .map_err(|e| anyhow::anyhow!("Invalid contract address: {e}"))?;
+        let mut key_bytes = vec![WASM_CONTRACT_STORE_PREFIX];
+        key_bytes.append(&mut contract.to_bytes());
         key_bytes.extend_from_slice(key.as_bytes());
-        Self {
-            prefix: "wasm".to_string(),
-            prefix_len: 4,
-            key: hex::encode(&key_bytes),
-        }
+        Ok(Self::new(Ics23StorePrefix::Wasm, hex::encode(&key_bytes)))
+    }
+
+    // create a new neutron key for the metadata of a WASM contract
+    // this is useful for binding proven contract state to the code the contract runs
+    // contract: the address of the contract
+    #[cfg(feature = "no-zkvm")]
+    pub fn new_wasm_contract_info(contract: &AccountId) -> Self {
+        let mut key_bytes = vec![WASM_CONTRACT_INFO_PREFIX];
+        key_bytes.extend_from_slice(&contract.to_bytes());
+        Self::new(Ics23StorePrefix::Wasm, hex::encode(key_bytes))
     }
 
     // create a new neutron key for the total supply of a denom
@@ -100,11 +439,23 @@ impl Ics23Key {
         // see https://protective-bearberry-a26.notion.site/Query-the-state-of-a-Cosmos-chain-and-verify-the-proof-1a55cfa0622c8055816ae6e6aec7f319?pvs=4
         let mut key_bytes = vec![0x00];
         key_bytes.extend_from_slice(denom.as_bytes());
-        Self {
-            prefix: "bank".to_string(),
-            prefix_len: 4,
-            key: hex::encode(key_bytes),
+        Self::new(Ics23StorePrefix::Bank, hex::encode(key_bytes))
+    }
+
+    // create a new neutron key for the delegation of a delegator to a validator
+    // this is useful for proving staked amounts in the staking module
+    // delegator: the account address of the delegator
+    // validator: the operator (valoper) address of the validator
+    #[cfg(feature = "no-zkvm")]
+    pub fn new_staking_delegation(delegator: &AccountId, validator: &AccountId) -> Self {
+        // delegation prefix is 0x31, both addresses are length-prefixed
+        let mut key_bytes = vec![STAKING_DELEGATION_PREFIX];
+        for address in [delegator, validator] {
+            let address_bytes = address.to_bytes();
+            key_bytes.push(address_bytes.len() as u8);
+            key_bytes.extend_from_slice(&address_bytes);
         }
+        Self::new(Ics23StorePrefix::Staking, hex::encode(key_bytes))
     }
 
     // create a new neutron key for the balance of an account
@@ -121,10 +472,6 @@ impl Ics23Key {
         key_bytes.push(address_bytes.len() as u8);
         key_bytes.extend_from_slice(&address_bytes);
         key_bytes.extend_from_slice(denom.as_bytes());
-        Self {
-            prefix: "bank".to_string(),
-            prefix_len: 4,
-            key: hex::encode(key_bytes),
-        }
+        Self::new(Ics23StorePrefix::Bank, hex::encode(key_bytes))
     }
 }
@@ -2,6 +2,8 @@
 //! Merkle proof verification and key management.
 
 pub mod keys;
+pub mod light_client;
 pub mod merkle_lib;
 #[cfg(feature = "no-zkvm")]
 pub mod rpc;
+pub mod values;
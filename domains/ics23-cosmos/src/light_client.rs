@@ -0,0 +1,70 @@
+//! Light-client checks for establishing a trusted app hash.
+//!
+//! [`Ics23MerkleProof::verify`](crate::merkle_lib::types::Ics23MerkleProof) takes the
+//! expected app hash as trusted input. This module lets that app hash be derived from
+//! a CometBFT signed header instead, by checking the commit signatures against the
+//! validator set that produced the block.
+
+use anyhow::{bail, Result};
+use tendermint::{block::Commit, block::Header, validator::Set as ValidatorSet};
+use tendermint_light_client_verifier::{
+    operations::{ProdVotingPowerCalculator, VotingPowerCalculator},
+    types::SignedHeader,
+};
+
+/// Verifies a signed CometBFT header and returns its app hash.
+///
+/// The header is accepted when all of the following hold:
+///
+/// * `validator_set` hashes to the header's `validators_hash`
+/// * the commit is for the header's height and its block id hashes to the header
+/// * validators holding strictly more than 2/3 of the total voting power of
+///   `validator_set` produced valid signatures over the header
+///
+/// The +2/3 threshold is the CometBFT safety bound: as long as less than 1/3 of
+/// the voting power is byzantine, no two conflicting headers can both gather it.
+/// Signatures are tallied per validator, so a validator signing twice is counted
+/// once, and absent or nil votes do not contribute.
+///
+/// The app hash in the header of block `H` is the one produced by executing
+/// block `H - 1`, so it commits to the state at height `H - 1`, not `H`. Proofs
+/// taken at height `H - 1` verify against it, e.g. with
+/// `verify_at_height(&app_hash, header.height.value() - 1)`.
+///
+/// Only ed25519 validator keys are supported.
+///
+/// # Arguments
+///
+/// * `header` - The block header committing to the app hash
+/// * `commit` - The commit carrying the validator signatures for `header`
+/// * `validator_set` - The validator set for the header's height
+///
+/// # Returns
+///
+/// The app hash of the header, committing to the state at the header's height
+/// minus one, to be passed as the expected root to
+/// [`Ics23MerkleProof::verify`](crate::merkle_lib::types::Ics23MerkleProof)
+pub fn verify_signed_header(
+    header: &Header,
+    commit: &Commit,
+    validator_set: &ValidatorSet,
+) -> Result<Vec<u8>> {
+    if header.validators_hash != validator_set.hash() {
+        bail!("Validator set does not match the header's validators hash");
+    }
+    if commit.height != header.height {
+        bail!(
+            "Commit height {} does not match header height {}",
+            commit.height,
+            header.height
+        );
+    }
+    if commit.block_id.hash != header.hash() {
+        bail!("Commit is not for the given header");
+    }
+    let signed_header = SignedHeader::new(header.clone(), commit.clone())?;
+    ProdVotingPowerCalculator::default()
+        .check_signers_overlap(&signed_header, validator_set)
+        .map_err(|e| anyhow::anyhow!("Insufficient voting power signed the header: {e}"))?;
+    Ok(header.app_hash.as_bytes().to_vec())
+}
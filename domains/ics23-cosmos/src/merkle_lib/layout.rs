@@ -0,0 +1,43 @@
+//! Multistore key layouts for ICS23 proof verification.
+//!
+//! An ICS23 proof for Cosmos state consists of a store proof (the key inside a
+//! module's IAVL store) and a multistore proof (the store's root inside the app
+//! hash). The key under which a store's root is committed to the multistore
+//! differs between chains, so it is derived through a [`StoreLayout`].
+
+use crate::keys::Ics23Key;
+
+/// Derives the key under which a store's root is committed in the multistore.
+///
+/// Closures of type `Fn(&Ics23Key) -> Vec<u8>` implement this trait, so a custom
+/// layout can be supplied inline.
+pub trait StoreLayout {
+    /// Returns the multistore key for the store holding `key`.
+    ///
+    /// # Arguments
+    /// * `key` - The key being proven
+    ///
+    /// # Returns
+    /// The key proven by the outer (multistore) proof
+    fn outer_key(&self, key: &Ics23Key) -> Vec<u8>;
+}
+
+/// The layout used by Neutron and most Cosmos SDK chains: stores are committed
+/// under their raw store name, e.g. `bank` or `wasm`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NeutronStoreLayout;
+
+impl StoreLayout for NeutronStoreLayout {
+    fn outer_key(&self, key: &Ics23Key) -> Vec<u8> {
+        key.prefix.as_bytes().to_vec()
+    }
+}
+
+impl<F> StoreLayout for F
+where
+    F: Fn(&Ics23Key) -> Vec<u8>,
+{
+    fn outer_key(&self, key: &Ics23Key) -> Vec<u8> {
+        self(key)
+    }
+}
@@ -1,5 +1,7 @@
 //! Merkle proof functionality for Neutron blockchain state verification.
 
 pub mod helpers;
+pub mod layout;
+pub mod root;
 mod tests;
 pub mod types;
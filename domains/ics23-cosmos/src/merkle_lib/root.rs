@@ -0,0 +1,67 @@
+//! A typed app hash for ICS23 proof verification.
+//!
+//! `MerkleVerifiable::verify` accepts any byte slice as a root. Verifying through
+//! [`CosmosAppHash`] instead makes it a type error to pass a root of another
+//! domain, such as an Ethereum state root.
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// The app hash of a Cosmos block, the root of its multistore.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CosmosAppHash(pub Vec<u8>);
+
+impl CosmosAppHash {
+    /// Returns the app hash as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Reads an app hash posted to an Ethereum contract's storage slot.
+    ///
+    /// Ethereum stores a `bytes32` slot as the RLP encoding of the word with
+    /// its leading zero bytes stripped, so the zeros are restored here.
+    ///
+    /// # Arguments
+    /// * `value` - The RLP-encoded slot value, as proven by an Ethereum storage proof
+    ///
+    /// # Returns
+    /// The 32-byte app hash
+    ///
+    /// # Errors
+    /// Returns an error if the slot is empty or its value is not an RLP byte
+    /// string of at most 32 bytes
+    pub fn from_storage_value(value: &[u8]) -> Result<Self> {
+        let word = match value {
+            [byte] if *byte < 0x80 => value,
+            [header, word @ ..] if (0x81..=0xa0).contains(header) => {
+                if word.len() != (*header - 0x80) as usize {
+                    anyhow::bail!("Storage value length does not match its RLP header");
+                }
+                word
+            }
+            [] => anyhow::bail!("Storage slot holds no app hash"),
+            _ => anyhow::bail!("Storage value is not a 32-byte word"),
+        };
+        let mut app_hash = vec![0u8; 32];
+        app_hash[32 - word.len()..].copy_from_slice(word);
+        Ok(Self(app_hash))
+    }
+}
+
+impl From<&[u8]> for CosmosAppHash {
+    fn from(app_hash: &[u8]) -> Self {
+        Self(app_hash.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for CosmosAppHash {
+    fn from(app_hash: Vec<u8>) -> Self {
+        Self(app_hash)
+    }
+}
+
+impl From<CosmosAppHash> for Vec<u8> {
+    fn from(app_hash: CosmosAppHash) -> Self {
+        app_hash.0
+    }
+}
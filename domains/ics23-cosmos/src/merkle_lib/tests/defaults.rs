@@ -1,3 +1,4 @@
+#[cfg(feature = "no-zkvm")]
 #[cfg(test)]
 pub(crate) mod constants {
     use crate::rpc::Ics23MerkleRpcClient;
@@ -28,7 +29,10 @@ pub(crate) mod constants {
         env::var("NEUTRON_DEFAULT_ACCOUNT_ADDRESS")
             .expect("Missing Neutron Default Account Address!")
     }
+}
 
+#[cfg(test)]
+pub(crate) mod test_vectors {
     use std::fs;
     use std::path::PathBuf;
 
@@ -0,0 +1,266 @@
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Uint128;
+    use cw_storage_plus::Map;
+
+    use crate::keys::{cosmwasm_namespace_key, WASM_CONTRACT_STORE_PREFIX};
+
+    #[test]
+    fn test_cosmwasm_namespace_key_matches_cw_storage_plus_map() {
+        let contract = [7u8; 32];
+        let holder = "neutron1m9l358xunhhwds0568za49mzhvuxx9ux8xafx2";
+        let map: Map<&str, Uint128> = Map::new("balances");
+        let storage_key = map.key(holder);
+
+        let key = cosmwasm_namespace_key(&contract, b"balances", holder.as_bytes()).unwrap();
+        assert_eq!(key[0], WASM_CONTRACT_STORE_PREFIX);
+        assert_eq!(&key[1..33], &contract);
+        assert_eq!(&key[33..], &*storage_key);
+    }
+
+    #[test]
+    fn test_cosmwasm_joined_key_matches_tuple_keys() {
+        use crate::keys::cosmwasm_joined_key;
+        use cw_storage_plus::PrimaryKey;
+
+        let owner: &[u8] = b"neutron1owner";
+        let id = 7u64.to_be_bytes();
+        let joined = (owner, 7u64).joined_key();
+        assert_eq!(cosmwasm_joined_key(&[owner, &id]).unwrap(), joined);
+        assert_eq!(cosmwasm_joined_key(&[owner]).unwrap(), owner);
+        assert!(cosmwasm_joined_key(&[&vec![0u8; 70_000], b"k"]).is_err());
+    }
+
+    #[cfg(feature = "no-zkvm")]
+    #[test]
+    fn test_indexed_map_primary_key() {
+        use crate::keys::Ics23Key;
+        use cosmrs::AccountId;
+        use cosmwasm_std::{testing::MockStorage, Storage};
+        use cw_storage_plus::{Index, IndexList, IndexedMap, MultiIndex};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Clone)]
+        struct Order {
+            owner: String,
+            amount: Uint128,
+        }
+
+        struct OrderIndexes<'a> {
+            owner: MultiIndex<'a, String, Order, (&'a str, u64)>,
+        }
+
+        impl IndexList<Order> for OrderIndexes<'_> {
+            fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Order>> + '_> {
+                let v: Vec<&dyn Index<Order>> = vec![&self.owner];
+                Box::new(v.into_iter())
+            }
+        }
+
+        // a DEX-style order book keyed by (market, order id)
+        let orders: IndexedMap<(&str, u64), Order, OrderIndexes> = IndexedMap::new(
+            "orders",
+            OrderIndexes {
+                owner: MultiIndex::new(|_, o: &Order| o.owner.clone(), "orders", "orders__owner"),
+            },
+        );
+        let order = Order {
+            owner: "neutron1owner".to_string(),
+            amount: Uint128::new(500),
+        };
+        let mut storage = MockStorage::new();
+        orders
+            .save(&mut storage, ("ntrn-usdc", 42), &order)
+            .unwrap();
+
+        let contract = AccountId::new("neutron", &[0x44; 32]).unwrap();
+        let key = Ics23Key::new_indexed_map_primary(
+            contract.as_ref(),
+            b"orders",
+            &[b"ntrn-usdc", &42u64.to_be_bytes()],
+        )
+        .unwrap();
+        let key_bytes = hex::decode(&key.key).unwrap();
+        assert_eq!(key_bytes[0], WASM_CONTRACT_STORE_PREFIX);
+        assert_eq!(&key_bytes[1..33], &[0x44; 32]);
+        let value = storage.get(&key_bytes[33..]).unwrap();
+        assert_eq!(value, br#"{"owner":"neutron1owner","amount":"500"}"#);
+    }
+
+    #[cfg(feature = "no-zkvm")]
+    #[test]
+    fn test_wasm_keys_reject_invalid_contract() {
+        use crate::keys::Ics23Key;
+
+        let holder = "neutron1m9l358xunhhwds0568za49mzhvuxx9ux8xafx2";
+        assert!(Ics23Key::new_wasm_account_mapping(b"balances", holder, "not-an-address").is_err());
+        assert!(Ics23Key::new_wasm_stored_value("shares", "not-an-address").is_err());
+    }
+
+    #[test]
+    fn test_cosmwasm_namespace_key_long_namespace() {
+        let namespace = vec![b'n'; 300];
+        let key = cosmwasm_namespace_key(&[1u8; 20], &namespace, b"k").unwrap();
+        assert_eq!(&key[21..23], &300u16.to_be_bytes());
+
+        let oversized = vec![b'n'; u16::MAX as usize + 1];
+        assert!(cosmwasm_namespace_key(&[1u8; 20], &oversized, b"k").is_err());
+    }
+
+    #[test]
+    fn test_store_prefix_derives_prefix_len() {
+        use crate::keys::{Ics23Key, Ics23StorePrefix, KeyHashing};
+
+        let key = Ics23Key::new(Ics23StorePrefix::Staking, "31".to_string());
+        assert_eq!((key.prefix.as_str(), key.prefix_len), ("staking", 7));
+        assert_eq!(key.store_prefix(), Ics23StorePrefix::Staking);
+        let custom = Ics23Key::new(
+            Ics23StorePrefix::Custom("acc".to_string()),
+            "01".to_string(),
+        );
+        assert_eq!(custom.prefix_len, 3);
+        assert_eq!(Ics23StorePrefix::from("wasm"), Ics23StorePrefix::Wasm);
+
+        assert_eq!(Ics23Key::from_string(&key.to_string()).unwrap(), key);
+        assert!(Ics23Key::from_string("00").is_err());
+        assert!(Ics23Key::from_string("009bank").is_err());
+
+        // the hashing policy is part of the string form
+        let hashed = key
+            .clone()
+            .with_key_hashing(KeyHashing::Sha256 { unhashed_len: 1 });
+        assert_ne!(hashed.to_string(), key.to_string());
+        assert_eq!(Ics23Key::from_string(&hashed.to_string()).unwrap(), hashed);
+        assert!(Ics23Key::from_string("007staking31:sha256:x").is_err());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_mismatched_prefix_len() {
+        use crate::keys::Ics23Key;
+
+        let key: Ics23Key =
+            serde_json::from_str(r#"{"prefix":"bank","prefix_len":4,"key":"02"}"#).unwrap();
+        assert_eq!(key.prefix, "bank");
+        assert!(
+            serde_json::from_str::<Ics23Key>(r#"{"prefix":"bank","prefix_len":3,"key":"02"}"#)
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "no-zkvm")]
+    #[test]
+    fn test_staking_delegation_key() {
+        use crate::keys::{Ics23Key, STAKING_DELEGATION_PREFIX};
+        use cosmrs::AccountId;
+
+        let delegator = AccountId::new("neutron", &[0x11; 20]).unwrap();
+        let validator = AccountId::new("neutronvaloper", &[0x22; 20]).unwrap();
+        let key = Ics23Key::new_staking_delegation(&delegator, &validator);
+        assert_eq!(key.prefix, "staking");
+        assert_eq!(key.prefix_len, key.prefix.len());
+
+        let mut expected = vec![STAKING_DELEGATION_PREFIX, 20];
+        expected.extend_from_slice(&[0x11; 20]);
+        expected.push(20);
+        expected.extend_from_slice(&[0x22; 20]);
+        assert_eq!(hex::decode(&key.key).unwrap(), expected);
+    }
+
+    #[cfg(feature = "no-zkvm")]
+    #[test]
+    fn test_wasm_contract_info_key() {
+        use crate::keys::{Ics23Key, WASM_CONTRACT_INFO_PREFIX};
+        use cosmrs::AccountId;
+
+        let contract = AccountId::new("neutron", &[0x33; 32]).unwrap();
+        let key = Ics23Key::new_wasm_contract_info(&contract);
+        assert_eq!(key.prefix, "wasm");
+
+        let mut expected = vec![WASM_CONTRACT_INFO_PREFIX];
+        expected.extend_from_slice(&[0x33; 32]);
+        assert_eq!(hex::decode(&key.key).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_decode_contract_info() {
+        use crate::values::decode_contract_info;
+        use cosmrs::proto::{cosmwasm::wasm::v1::ContractInfo, prost::Message};
+
+        let info = ContractInfo {
+            code_id: 1234,
+            creator: "neutron1creator".to_string(),
+            admin: "neutron1admin".to_string(),
+            label: "vault".to_string(),
+            ..Default::default()
+        };
+        let decoded = decode_contract_info(&info.encode_to_vec()).unwrap();
+        assert_eq!(decoded.code_id, 1234);
+        assert_eq!(decoded.creator, "neutron1creator");
+        assert!(decode_contract_info(&[0x12, 0x05, b'n']).is_err());
+    }
+
+    #[test]
+    fn test_decode_delegation() {
+        use crate::values::decode_delegation;
+        use cosmrs::proto::{cosmos::staking::v1beta1::Delegation, prost::Message};
+
+        let delegation = Delegation {
+            delegator_address: "neutron1delegator".to_string(),
+            validator_address: "neutronvaloper1validator".to_string(),
+            shares: "1500000000000000000000".to_string(),
+        };
+        let decoded = decode_delegation(&delegation.encode_to_vec()).unwrap();
+        assert_eq!(decoded, delegation);
+        assert!(decode_delegation(&[0x0a, 0x05, b'n']).is_err());
+    }
+
+    #[test]
+    fn test_decode_cw20_balance() {
+        use crate::values::decode_cw20_balance;
+        use cosmwasm_std::{testing::MockStorage, Addr, Storage};
+
+        // the entry cw20-base writes to its balance map
+        let balances: Map<&Addr, Uint128> = Map::new("balance");
+        let holder = Addr::unchecked("neutron1m9l358xunhhwds0568za49mzhvuxx9ux8xafx2");
+        let mut storage = MockStorage::new();
+        balances
+            .save(&mut storage, &holder, &Uint128::new(1_250_000))
+            .unwrap();
+        let value = storage.get(&balances.key(&holder)).unwrap();
+        assert_eq!(value, br#""1250000""#);
+        assert_eq!(decode_cw20_balance(&value).unwrap(), 1_250_000);
+
+        assert_eq!(
+            decode_cw20_balance(br#""340282366920938463463374607431768211455""#).unwrap(),
+            u128::MAX
+        );
+        assert!(decode_cw20_balance(b"1250000").is_err());
+        assert!(decode_cw20_balance(br#""-1""#).is_err());
+        assert!(decode_cw20_balance(br#""340282366920938463463374607431768211456""#).is_err());
+    }
+
+    #[test]
+    fn test_decode_decimal_amount() {
+        use crate::values::decode_decimal_amount;
+
+        assert_eq!(decode_decimal_amount(b"0").unwrap(), 0);
+        assert_eq!(decode_decimal_amount(b"001250000").unwrap(), 1_250_000);
+        assert_eq!(
+            decode_decimal_amount(b"340282366920938463463374607431768211455").unwrap(),
+            u128::MAX
+        );
+        assert!(decode_decimal_amount(b"340282366920938463463374607431768211456").is_err());
+        for malformed in [
+            &b""[..],
+            b" 1250000",
+            b"1250000\n",
+            b"+1250000",
+            b"-1",
+            b"12.5",
+            b"\"1250000\"",
+            &[0x31, 0xff],
+        ] {
+            assert!(decode_decimal_amount(malformed).is_err(), "{:?}", malformed);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use tendermint::{AppHash, Time};
+    use tendermint_testgen::{light_block::TmLightBlock, Generator, Header, LightBlock, Validator};
+
+    use crate::light_client::verify_signed_header;
+
+    fn light_block(validators: &[Validator], app_hash: &[u8]) -> TmLightBlock {
+        let header = Header::new(validators)
+            .height(10)
+            .chain_id("neutron-1")
+            .next_validators(validators)
+            .time(Time::from_unix_timestamp(1_700_000_000, 0).unwrap())
+            .app_hash(AppHash::try_from(app_hash.to_vec()).unwrap());
+        LightBlock::new_default_with_header(header)
+            .generate()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_signed_header_returns_app_hash() {
+        let validators = [
+            Validator::new("a").voting_power(50),
+            Validator::new("b").voting_power(50),
+        ];
+        let block = light_block(&validators, &[0xAB; 32]);
+        let app_hash = verify_signed_header(
+            &block.signed_header.header,
+            &block.signed_header.commit,
+            &block.validators,
+        )
+        .unwrap();
+        assert_eq!(app_hash, vec![0xAB; 32]);
+    }
+
+    #[test]
+    fn test_verify_signed_header_rejects_other_validator_set() {
+        let block = light_block(&[Validator::new("a").voting_power(50)], &[0xAB; 32]);
+        let other = light_block(&[Validator::new("b").voting_power(50)], &[0xAB; 32]);
+        assert!(verify_signed_header(
+            &block.signed_header.header,
+            &block.signed_header.commit,
+            &other.validators,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_verify_signed_header_requires_two_thirds() {
+        let validators = [
+            Validator::new("a").voting_power(50),
+            Validator::new("b").voting_power(50),
+            Validator::new("c").voting_power(50),
+        ];
+        let mut block = light_block(&validators, &[0xAB; 32]);
+        // drop two of the three signatures, leaving only 1/3 of the power
+        for sig in block.signed_header.commit.signatures.iter_mut().skip(1) {
+            *sig = tendermint::block::CommitSig::BlockIdFlagAbsent;
+        }
+        assert!(verify_signed_header(
+            &block.signed_header.header,
+            &block.signed_header.commit,
+            &block.validators,
+        )
+        .is_err());
+    }
+}
@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests {
     use crate::merkle_lib::{
-        tests::defaults::constants::{
-            get_latest_root_and_height, get_test_vector_neutron_storage_proof,
-            read_pion_1_default_account_address, TEST_VECTOR_NEUTRON_ROOT,
+        tests::defaults::{
+            constants::{get_latest_root_and_height, read_pion_1_default_account_address},
+            test_vectors::{get_test_vector_neutron_storage_proof, TEST_VECTOR_NEUTRON_ROOT},
         },
         types::Ics23MerkleProof,
     };
@@ -31,7 +31,8 @@ mod tests {
         let contract_address = "neutron1xlklun3vpf7ts08mm79tyyllyezles7mpp3np5a4ueadgfz9ndns350qw2";
         let initial_address = &read_pion_1_default_account_address();
         let neutron_key: Ics23Key =
-            Ics23Key::new_wasm_account_mapping(b"store", initial_address, contract_address);
+            Ics23Key::new_wasm_account_mapping(b"store", initial_address, contract_address)
+                .unwrap();
         let rpc_url = read_rpc_url();
         let prover = Ics23MerkleRpcClient { rpc_url };
         let (root, height) = get_latest_root_and_height().await;
@@ -1,5 +1,6 @@
-#[cfg(feature = "no-zkvm")]
 pub(crate) mod defaults;
+mod keys;
+mod light_client;
 #[cfg(feature = "no-zkvm")]
 pub mod merkle;
 #[cfg(feature = "no-zkvm")]
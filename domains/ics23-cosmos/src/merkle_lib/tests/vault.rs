@@ -21,7 +21,8 @@ mod tests {
             b"balances",
             &read_pion_1_default_account_address(),
             &read_pion_1_vault_contract_address(),
-        );
+        )
+        .unwrap();
         let (root, height) = get_latest_root_and_height().await;
         let proofs = prover
             .get_proof(&neutron_key.to_string(), "", height)
@@ -36,7 +37,8 @@ mod tests {
         let rpc_url = read_rpc_url();
         let prover = Ics23MerkleRpcClient { rpc_url };
         let neutron_key =
-            Ics23Key::new_wasm_stored_value("shares", &read_pion_1_vault_contract_address());
+            Ics23Key::new_wasm_stored_value("shares", &read_pion_1_vault_contract_address())
+                .unwrap();
         let (root, height) = get_latest_root_and_height().await;
 
         let proofs = prover
@@ -1,9 +1,22 @@
-use crate::{keys::Ics23Key, merkle_lib::helpers::convert_tm_to_ics_merkle_proof};
+use crate::{
+    keys::Ics23Key,
+    merkle_lib::{
+        helpers::convert_tm_to_ics_merkle_proof,
+        layout::{NeutronStoreLayout, StoreLayout},
+        root::CosmosAppHash,
+    },
+};
 use anyhow::{Context, Result};
-use common::merkle::types::MerkleVerifiable;
+use common::{
+    inspect::describe_bytes,
+    merkle::types::{
+        Domain, DomainProof, HasRoot, MerkleProofOutput, MerkleVerifiable, ProvenEntry,
+    },
+    merkle::versioned::{deserialize_version, PROOF_FORMAT_VERSION},
+};
 use ics23::{
     calculate_existence_root, commitment_proof::Proof, iavl_spec, tendermint_spec,
-    verify_membership,
+    verify_membership, HostFunctionsManager, HostFunctionsProvider,
 };
 use serde::{Deserialize, Serialize};
 use tendermint::merkle::proof::ProofOps;
@@ -13,36 +26,137 @@ use tendermint::merkle::proof::ProofOps;
 /// being proven, allowing for verification of state existence.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Ics23MerkleProof {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
     /// The Tendermint proof operations
     pub proof: ProofOps,
     /// The key being proven
     pub key: Ics23Key,
     /// The value being proven
     pub value: Vec<u8>,
+    /// The height of the state the proof was taken at, whose app hash is
+    /// committed in the header of the next block. This is reported by whoever
+    /// supplied the proof and is not authenticated by it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
 }
 
-impl MerkleVerifiable for Ics23MerkleProof {
-    fn verify(&self, expected_root: &[u8]) -> Result<bool> {
+common::impl_proof_eq!(Ics23MerkleProof, proof, key, value, height);
+
+impl Ics23MerkleProof {
+    /// Checks that the proof is well-formed before it is verified.
+    ///
+    /// # Errors
+    /// Returns an error naming the key if the value is empty, if the key's
+    /// `prefix_len` does not match its prefix, its key is not hex or is shorter
+    /// than its unhashed prefix, or if the proof does not consist of a store
+    /// proof followed by a multistore proof
+    pub fn validate(&self) -> Result<()> {
+        if self.value.is_empty() {
+            anyhow::bail!("Proof for key {} has an empty value", self.key);
+        }
+        if self.key.prefix.is_empty() || self.key.prefix_len != self.key.prefix.len() {
+            anyhow::bail!(
+                "Key {} has prefix_len {} but its prefix {:?} is {} bytes long",
+                self.key,
+                self.key.prefix_len,
+                self.key.prefix,
+                self.key.prefix.len()
+            );
+        }
+        self.key.store_key()?;
+        if self.proof.ops.len() != 2 {
+            anyhow::bail!(
+                "Proof for key {} has {} proof ops, expected a store and a multistore proof",
+                self.key,
+                self.proof.ops.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Verifies the proof against an app hash, deriving the multistore key with `layout`.
+    ///
+    /// `verify` uses [`NeutronStoreLayout`], which commits stores under their raw
+    /// store name; chains whose multistore commits keys differently can supply
+    /// their own [`StoreLayout`].
+    ///
+    /// # Arguments
+    /// * `expected_root` - The app hash to verify against
+    /// * `layout` - The multistore key layout of the chain
+    ///
+    /// # Returns
+    /// `true` if the proof is valid
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not verify
+    pub fn verify_with_layout(
+        &self,
+        expected_root: &[u8],
+        layout: &impl StoreLayout,
+    ) -> Result<bool> {
+        self.verify_with_host_functions::<HostFunctionsManager>(expected_root, layout)
+    }
+
+    /// Verifies the proof, computing every hash with the host functions `H`.
+    ///
+    /// `verify` and `verify_with_layout` use [`HostFunctionsManager`], which hashes
+    /// in software. Inside a zkVM, `H` can instead route sha256 (the only hash the
+    /// IAVL and Tendermint specs use) through the VM's precompile, e.g.
+    /// `struct Sp1HostFunctions;` implementing [`HostFunctionsProvider`] with
+    /// `sha2_256` backed by the patched `sha2` crate and the remaining methods
+    /// delegating to `HostFunctionsManager`.
+    ///
+    /// # Arguments
+    /// * `expected_root` - The app hash to verify against
+    /// * `layout` - The multistore key layout of the chain
+    ///
+    /// # Returns
+    /// `true` if the proof is valid
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not verify
+    pub fn verify_with_host_functions<H: HostFunctionsProvider>(
+        &self,
+        expected_root: &[u8],
+        layout: &impl StoreLayout,
+    ) -> Result<bool> {
+        self.validate()?;
         let proof_decoded = convert_tm_to_ics_merkle_proof(&self.proof)?;
         let inner_proof = proof_decoded.first().context("Failed to decode proof")?;
         let Some(Proof::Exist(existence_proof)) = &inner_proof.proof else {
             anyhow::bail!("Wrong proof type!");
         };
-        let inner_root = calculate_existence_root::<ics23::HostFunctionsManager>(existence_proof)?;
-        let is_valid = verify_membership::<ics23::HostFunctionsManager>(
+        let inner_root = calculate_existence_root::<H>(existence_proof)?;
+        let is_valid = verify_membership::<H>(
             inner_proof,
             &iavl_spec(),
             &inner_root,
-            &hex::decode(&self.key.key)?,
+            &self.key.store_key_with::<H>()?,
             &self.value,
         );
-        assert!(is_valid);
+        if !is_valid {
+            anyhow::bail!("Invalid store proof for key {}", self.key);
+        }
         let outer_proof = proof_decoded.last().context("Failed to decode proof")?;
-        let is_valid = verify_membership::<ics23::HostFunctionsManager>(
+        let Some(Proof::Exist(outer_existence_proof)) = &outer_proof.proof else {
+            anyhow::bail!("Wrong proof type!");
+        };
+        // the multistore must commit to the store root computed from the store
+        // proof, rather than have it trusted as the value of the outer check
+        if outer_existence_proof.value != inner_root {
+            anyhow::bail!(
+                "Multistore proof for key {} commits to a store root other than the one computed from the store proof",
+                self.key
+            );
+        }
+        let is_valid = verify_membership::<H>(
             outer_proof,
             &tendermint_spec(),
             &expected_root.to_vec(),
-            self.key.prefix.as_bytes(),
+            &layout.outer_key(&self.key),
             &inner_root,
         );
         match is_valid {
@@ -50,6 +164,270 @@ impl MerkleVerifiable for Ics23MerkleProof {
             false => anyhow::bail!("Invalid proof"),
         }
     }
+
+    /// Verifies the proof against a typed app hash.
+    ///
+    /// # Arguments
+    /// * `app_hash` - The app hash to verify against
+    ///
+    /// # Returns
+    /// `true` if the proof is valid
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not verify
+    pub fn verify_app_hash(&self, app_hash: &CosmosAppHash) -> Result<bool> {
+        self.verify(app_hash.as_bytes())
+    }
+
+    /// Verifies the proof against the app hash of the state at `height`.
+    ///
+    /// IAVL stores are versioned, so a proof only verifies against the app hash
+    /// of the height it was taken at. The recorded height is unauthenticated
+    /// metadata, so comparing it only catches a caller pairing a proof with the
+    /// app hash of another height by mistake; it is no security guarantee. What
+    /// the proof shows is bound to `app_hash` alone, which the caller must trust
+    /// to be the app hash of `height`.
+    ///
+    /// # Arguments
+    /// * `app_hash` - The app hash to verify against
+    /// * `height` - The height of the state `app_hash` commits to
+    ///
+    /// # Returns
+    /// `true` if the proof is valid
+    ///
+    /// # Errors
+    /// Returns an error if the proof does not record a height, records a
+    /// different height, or does not verify
+    pub fn verify_at_height(&self, app_hash: &[u8], height: u64) -> Result<bool> {
+        match self.height {
+            Some(proof_height) if proof_height == height => self.verify(app_hash),
+            Some(proof_height) => anyhow::bail!(
+                "Proof for key {} was taken at height {}, expected height {}",
+                self.key,
+                proof_height,
+                height
+            ),
+            None => anyhow::bail!("Proof for key {} does not record its height", self.key),
+        }
+    }
+
+    /// Verifies the proof and returns the proven key and value.
+    ///
+    /// The proof is consumed, so the key and value can only be obtained once
+    /// verification has succeeded and an unverified `value` is never read.
+    ///
+    /// # Arguments
+    /// * `expected_root` - The app hash to verify against
+    ///
+    /// # Returns
+    /// The proven key and value
+    ///
+    /// # Errors
+    /// Returns an error if the proof is malformed or does not verify
+    pub fn verify_and_extract(self, expected_root: &[u8]) -> Result<(Ics23Key, Vec<u8>)> {
+        if !self.verify(expected_root)? {
+            anyhow::bail!("Invalid proof for key {}", self.key);
+        }
+        Ok((self.key, self.value))
+    }
+
+    /// Renders the proof as a human-readable multi-line description.
+    ///
+    /// Shows the store prefix and hex key, every proof op with its field type and
+    /// key, and the value.
+    ///
+    /// # Returns
+    /// A multi-line description of the proof
+    pub fn inspect(&self) -> String {
+        let mut out = format!(
+            "Ics23MerkleProof\n  key: {} {}\n  proof ops: {}\n",
+            self.key.prefix,
+            self.key.key,
+            self.proof.ops.len()
+        );
+        for (index, op) in self.proof.ops.iter().enumerate() {
+            out.push_str(&format!(
+                "    [{index}] {} key: {} data: {} bytes\n",
+                op.field_type,
+                describe_bytes(&op.key),
+                op.data.len()
+            ));
+        }
+        out.push_str(&format!("  value: {}\n", describe_bytes(&self.value)));
+        out
+    }
+}
+
+impl MerkleVerifiable for Ics23MerkleProof {
+    fn verify(&self, expected_root: &[u8]) -> Result<bool> {
+        self.verify_with_layout(expected_root, &NeutronStoreLayout)
+    }
+}
+
+impl DomainProof for Ics23MerkleProof {
+    const DOMAIN: Domain = Domain::Ics23;
+}
+
+impl ProvenEntry for Ics23MerkleProof {
+    fn proven_key(&self) -> Vec<u8> {
+        self.key.to_string().into_bytes()
+    }
+
+    fn proven_value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+
+    fn proven_height(&self) -> Option<u64> {
+        self.height
+    }
+}
+
+/// Verifies an ICS23 proof against an app hash bridged into Ethereum storage.
+///
+/// Some bridges post the Cosmos app hash into a contract on Ethereum. The app
+/// hash is first proven from that contract's storage, then the ICS23 proof is
+/// verified against it, so the app hash is never taken as an untrusted input.
+/// The trust anchor is `eth_root`; the returned output carries the bridged app
+/// hash as its root. The proof must be for the bridge's app hash slot: any other
+/// 32-byte word under `eth_root`, such as a mapping entry users can write, is
+/// rejected.
+///
+/// # Arguments
+/// * `app_hash_proof` - An Ethereum proof of the slot holding the app hash: an
+///   `EthereumStorageProof` against the bridge contract's storage root, or an
+///   `EthereumCombinedProof` against a state root
+/// * `eth_root` - The trusted root to verify `app_hash_proof` against
+/// * `bridge_contract` - The address of the bridge contract if `eth_root` is a
+///   state root, `None` if it is the contract's storage root
+/// * `app_hash_slot` - The 32-byte storage slot the bridge keeps the app hash in
+/// * `neutron_proof` - The proof to verify against the bridged app hash
+///
+/// # Returns
+/// The entry proven by `neutron_proof`
+///
+/// # Errors
+/// Returns an error if `app_hash_proof` is not an Ethereum proof, is for another
+/// contract or slot, or does not verify, its value is not an app hash, or
+/// `neutron_proof` does not verify against it
+pub fn verify_bridged_neutron_proof<B: DomainProof>(
+    app_hash_proof: &B,
+    eth_root: &[u8],
+    bridge_contract: Option<&[u8]>,
+    app_hash_slot: &[u8],
+    neutron_proof: &Ics23MerkleProof,
+) -> Result<MerkleProofOutput> {
+    if B::DOMAIN != Domain::Ethereum {
+        anyhow::bail!("App hash must be proven from Ethereum, got {:?}", B::DOMAIN);
+    }
+    let expected_key = [bridge_contract.unwrap_or_default(), app_hash_slot].concat();
+    let bridged = app_hash_proof
+        .verify_output(eth_root)
+        .context("Failed to verify the bridged app hash")?;
+    if bridged.key != expected_key {
+        anyhow::bail!(
+            "App hash proof is for key 0x{}, expected 0x{}",
+            hex::encode(&bridged.key),
+            hex::encode(&expected_key)
+        );
+    }
+    let app_hash = CosmosAppHash::from_storage_value(&bridged.value)?;
+    neutron_proof.verify_output(app_hash.as_bytes())
+}
+
+/// A set of ICS23 proofs taken from the same Cosmos block.
+///
+/// All proofs, across any number of stores (e.g. `bank` and `wasm`), are verified
+/// against the single `app_hash` of the set, so the proven values are guaranteed
+/// to be read from the same block.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Ics23BlockProofSet {
+    /// The layout version the proof was serialized with, see
+    /// [`PROOF_FORMAT_VERSION`]
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: u8,
+    /// The app hash all proofs verify against
+    pub app_hash: Vec<u8>,
+    /// The height of the state the proofs were taken at
+    pub height: u64,
+    /// The proofs in the set
+    pub proofs: Vec<Ics23MerkleProof>,
+}
+
+common::impl_proof_eq!(Ics23BlockProofSet, app_hash, height, proofs);
+
+impl Ics23BlockProofSet {
+    /// Creates a new block proof set.
+    ///
+    /// # Arguments
+    /// * `app_hash` - The app hash all proofs verify against
+    /// * `height` - The height of the state the proofs were taken at
+    /// * `proofs` - The proofs in the set
+    ///
+    /// # Returns
+    /// A new `Ics23BlockProofSet` instance
+    pub fn new(app_hash: Vec<u8>, height: u64, proofs: Vec<Ics23MerkleProof>) -> Self {
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            app_hash,
+            height,
+            proofs,
+        }
+    }
+}
+
+/// Verifies every proof in the set against the set's app hash, which must equal
+/// the trusted `expected_root`. Every proof must record the set's height; as
+/// with `verify_at_height`, this catches mismatched proofs, not forged heights.
+impl MerkleVerifiable for Ics23BlockProofSet {
+    fn verify(&self, expected_root: &[u8]) -> Result<bool> {
+        if self.app_hash != expected_root {
+            anyhow::bail!(
+                "Proof set for height {} does not commit to the expected app hash",
+                self.height
+            );
+        }
+        if self.proofs.is_empty() {
+            anyhow::bail!("Proof set for height {} is empty", self.height);
+        }
+        for proof in &self.proofs {
+            if !proof.verify_at_height(&self.app_hash, self.height)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl HasRoot for Ics23BlockProofSet {
+    fn root(&self) -> Result<Vec<u8>> {
+        Ok(self.app_hash.clone())
+    }
+}
+
+/// The root of an ICS23 proof is the app hash computed from its outer (multistore) proof.
+impl HasRoot for Ics23MerkleProof {
+    fn root(&self) -> Result<Vec<u8>> {
+        let proof_decoded = convert_tm_to_ics_merkle_proof(&self.proof)?;
+        let outer_proof = proof_decoded.last().context("Failed to decode proof")?;
+        let Some(Proof::Exist(existence_proof)) = &outer_proof.proof else {
+            anyhow::bail!("Wrong proof type!");
+        };
+        calculate_existence_root::<HostFunctionsManager>(existence_proof)
+    }
+}
+
+/// The Neutron storage proof test vector and the app hash it verifies against.
+#[cfg(test)]
+fn test_vector() -> (Ics23MerkleProof, Vec<u8>) {
+    use super::tests::defaults::test_vectors::{
+        get_test_vector_neutron_storage_proof, TEST_VECTOR_NEUTRON_ROOT,
+    };
+    use base64::Engine;
+    let proof = serde_json::from_slice(&get_test_vector_neutron_storage_proof()).unwrap();
+    let app_hash = base64::engine::general_purpose::STANDARD
+        .decode(TEST_VECTOR_NEUTRON_ROOT)
+        .unwrap();
+    (proof, app_hash)
 }
 
 #[test]
@@ -59,8 +437,381 @@ fn test_neutron_key_serialization() {
         prefix: "some_long_key_to_rule_out_issues".to_string(),
         prefix_len: "some_long_key_to_rule_out_issues".to_string().len(),
         key: "0x000".to_string(),
+        key_hashing: Default::default(),
     };
     let key_serialized = key.to_string();
     let key_deserialized = Ics23Key::from_string(&key_serialized).unwrap();
     assert_eq!(key_deserialized, key);
 }
+
+#[test]
+fn test_neutron_proof_dedup() {
+    use std::collections::HashSet;
+    let (proof, _) = test_vector();
+    let (duplicate, _) = test_vector();
+    assert_eq!(proof, duplicate);
+    // the version a proof was serialized with does not change what it proves
+    let mut rebuilt = proof.clone();
+    rebuilt.version = if proof.version == 0 {
+        PROOF_FORMAT_VERSION
+    } else {
+        0
+    };
+    assert_eq!(proof, rebuilt);
+    let unique: HashSet<Ics23MerkleProof> = [proof, duplicate, rebuilt].into_iter().collect();
+    assert_eq!(unique.len(), 1);
+}
+
+#[test]
+fn test_neutron_proof_root_matches_app_hash() {
+    use common::merkle::types::assert_same_root;
+    let (proof, app_hash) = test_vector();
+    assert_eq!(proof.root().unwrap(), app_hash);
+    assert_same_root(&[&proof, &proof.clone()]).unwrap();
+}
+
+#[test]
+fn test_neutron_proof_validate() {
+    let (proof, _) = test_vector();
+    proof.validate().unwrap();
+
+    let mut empty_value = proof.clone();
+    empty_value.value.clear();
+    let error = empty_value.validate().unwrap_err().to_string();
+    assert!(error.contains(&proof.key.to_string()));
+    assert!(empty_value.verify(&[0u8; 32]).is_err());
+
+    let mut wrong_prefix_len = proof.clone();
+    wrong_prefix_len.key.prefix_len += 1;
+    assert!(wrong_prefix_len.validate().is_err());
+
+    let mut no_ops = proof;
+    no_ops.proof.ops.clear();
+    assert!(no_ops.validate().is_err());
+}
+
+#[test]
+fn test_neutron_proof_verify_with_layout() {
+    let (proof, app_hash) = test_vector();
+    assert!(proof.verify(&app_hash).unwrap());
+    assert!(proof
+        .verify_with_layout(&app_hash, &|key: &Ics23Key| key.prefix.as_bytes().to_vec())
+        .unwrap());
+    assert!(proof
+        .verify_with_layout(&app_hash, &|key: &Ics23Key| {
+            [b"s/k:".as_slice(), key.prefix.as_bytes()].concat()
+        })
+        .is_err());
+}
+
+#[test]
+fn test_neutron_proof_verify_and_extract() {
+    let (proof, app_hash) = test_vector();
+
+    assert!(proof.verify_app_hash(&app_hash.as_slice().into()).unwrap());
+    let (key, value) = proof.clone().verify_and_extract(&app_hash).unwrap();
+    assert_eq!(key, proof.key);
+    assert_eq!(value, proof.value);
+
+    let mut tampered = proof;
+    tampered.value.push(0);
+    assert!(tampered.verify_and_extract(&app_hash).is_err());
+}
+
+#[test]
+fn test_neutron_proof_inspect() {
+    let (proof, _) = test_vector();
+    let description = proof.inspect();
+    assert!(description.starts_with("Ics23MerkleProof\n"));
+    assert!(description.contains(&format!("  key: {} {}\n", proof.key.prefix, proof.key.key)));
+    assert!(description.contains("  proof ops: 2\n"));
+    for op in &proof.proof.ops {
+        assert!(description.contains(&op.field_type));
+    }
+}
+
+#[test]
+fn test_neutron_proof_verify_output() {
+    let (proof, app_hash) = test_vector();
+    let output = proof.verify_output(&app_hash).unwrap();
+    assert_eq!(output.domain, Domain::Ics23);
+    assert_eq!(output.root, app_hash);
+    assert_eq!(output.key, proof.key.to_string().into_bytes());
+    assert_eq!(output.value, proof.value);
+}
+
+#[test]
+fn test_neutron_block_proof_set() {
+    let (unversioned, app_hash) = test_vector();
+    // a proof that does not record its height cannot join a set
+    assert!(
+        Ics23BlockProofSet::new(app_hash.clone(), 1, vec![unversioned.clone()])
+            .verify(&app_hash)
+            .is_err()
+    );
+    let proof = Ics23MerkleProof {
+        height: Some(1),
+        ..unversioned
+    };
+
+    let set = Ics23BlockProofSet::new(app_hash.clone(), 1, vec![proof.clone(), proof.clone()]);
+    assert!(set.verify(&app_hash).unwrap());
+    assert!(set.verify(&[0u8; 32]).is_err());
+    assert!(Ics23BlockProofSet::new(app_hash.clone(), 1, vec![])
+        .verify(&app_hash)
+        .is_err());
+
+    let mut tampered = proof.clone();
+    tampered.value.push(0);
+    let set = Ics23BlockProofSet::new(app_hash.clone(), 1, vec![proof, tampered]);
+    assert!(set.verify(&app_hash).is_err());
+}
+
+#[test]
+fn test_unversioned_vector_reads_as_v0() {
+    let (legacy, _) = test_vector();
+    assert_eq!(legacy.version, 0);
+
+    let tagged = Ics23MerkleProof {
+        version: PROOF_FORMAT_VERSION,
+        ..legacy.clone()
+    };
+    let serialized = serde_json::to_vec(&tagged).unwrap();
+    assert!(serialized.starts_with(br#"{"version":1,"#));
+    let decoded: Ics23MerkleProof = serde_json::from_slice(&serialized).unwrap();
+    assert_eq!(decoded, tagged);
+
+    // the version is checked before the rest of the proof
+    let future = [br#"{"version":2,"#.as_slice(), &serialized[13..]].concat();
+    let error = serde_json::from_slice::<Ics23MerkleProof>(&future).unwrap_err();
+    assert!(error.to_string().starts_with("Unsupported proof version 2"));
+}
+
+#[test]
+fn test_rejects_store_root_not_committed_by_multistore() {
+    use cosmrs::proto::prost::Message;
+    use ics23::CommitmentProof;
+
+    let (proof, _) = test_vector();
+    let app_hash = proof.root().unwrap();
+    assert!(proof.verify(&app_hash).unwrap());
+
+    // a store proof of another value verifies on its own, but computes a store
+    // root that the multistore proof does not commit to
+    let mut tampered = proof.clone();
+    let mut store_proof = CommitmentProof::decode(tampered.proof.ops[0].data.as_slice()).unwrap();
+    let Some(Proof::Exist(existence_proof)) = &mut store_proof.proof else {
+        panic!("store proof is not an existence proof");
+    };
+    existence_proof.value.push(0x00);
+    tampered.value = existence_proof.value.clone();
+    tampered.proof.ops[0].data = store_proof.encode_to_vec();
+    let error = tampered.verify(&app_hash).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("commits to a store root other than the one computed from the store proof"));
+}
+
+#[test]
+fn test_verify_sha256_hashed_wasm_map_key() {
+    use crate::keys::Ics23StorePrefix;
+    use crate::keys::{cosmwasm_joined_key, cosmwasm_namespace_key, KeyHashing};
+    use cosmrs::proto::prost::Message;
+    use cosmwasm_std::Uint128;
+    use cw_storage_plus::Map;
+    use ics23::{CommitmentProof, ExistenceProof};
+    use tendermint::merkle::proof::ProofOp;
+
+    fn single_leaf_proof(leaf: ics23::LeafOp, key: &[u8], value: &[u8]) -> (ProofOp, Vec<u8>) {
+        let existence_proof = ExistenceProof {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            leaf: Some(leaf),
+            path: vec![],
+        };
+        let root = calculate_existence_root::<HostFunctionsManager>(&existence_proof).unwrap();
+        let op = ProofOp {
+            field_type: "ics23".to_string(),
+            key: key.to_vec(),
+            data: CommitmentProof {
+                proof: Some(Proof::Exist(existence_proof)),
+            }
+            .encode_to_vec(),
+        };
+        (op, root)
+    }
+
+    // a contract keying its positions by the digest of (owner, position id)
+    let contract = [0x55; 32];
+    let preimage = cosmwasm_joined_key(&[b"neutron1owner", &7u64.to_be_bytes()]).unwrap();
+    let digest = HostFunctionsManager::sha2_256(&preimage);
+    let positions: Map<&[u8], Uint128> = Map::new("positions");
+    let store_key = cosmwasm_namespace_key(&contract, b"positions", &digest).unwrap();
+    assert_eq!(&store_key[33..], &*positions.key(&digest[..]));
+
+    let unhashed_len = store_key.len() - digest.len();
+    let key = Ics23Key::new(
+        Ics23StorePrefix::Wasm,
+        hex::encode(cosmwasm_namespace_key(&contract, b"positions", &preimage).unwrap()),
+    )
+    .with_key_hashing(KeyHashing::Sha256 { unhashed_len });
+    assert_eq!(key.store_key().unwrap(), store_key);
+
+    // a single-leaf IAVL store committed under `wasm` in a single-store multistore
+    let mut iavl_leaf = iavl_spec().leaf_spec.unwrap();
+    iavl_leaf.prefix = vec![0, 2, 2];
+    let value = br#""900""#.to_vec();
+    let (store_op, store_root) = single_leaf_proof(iavl_leaf, &store_key, &value);
+    let (multistore_op, app_hash) =
+        single_leaf_proof(tendermint_spec().leaf_spec.unwrap(), b"wasm", &store_root);
+    let proof = Ics23MerkleProof {
+        version: PROOF_FORMAT_VERSION,
+        proof: ProofOps {
+            ops: vec![store_op, multistore_op],
+        },
+        key,
+        value,
+        height: None,
+    };
+    assert!(proof.verify(&app_hash).unwrap());
+
+    let serialized = serde_json::to_string(&proof).unwrap();
+    assert!(serialized.contains(r#""key_hashing":{"sha256":{"unhashed_len":"#));
+    let deserialized: Ics23MerkleProof = serde_json::from_str(&serialized).unwrap();
+    assert!(deserialized.verify(&app_hash).unwrap());
+
+    // the preimage itself is not committed
+    let mut raw = proof.clone();
+    raw.key.key_hashing = KeyHashing::Raw;
+    assert!(raw.verify(&app_hash).is_err());
+    let mut short = proof;
+    short.key.key_hashing = KeyHashing::Sha256 { unhashed_len: 1000 };
+    assert!(short.verify(&app_hash).is_err());
+}
+
+#[test]
+fn test_verify_with_custom_host_functions() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static SHA256_CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    // stands in for a precompile-backed provider, counting the sha256 calls routed to it
+    struct CountingHostFunctions;
+
+    impl HostFunctionsProvider for CountingHostFunctions {
+        fn sha2_256(message: &[u8]) -> [u8; 32] {
+            SHA256_CALLS.with(|calls| calls.set(calls.get() + 1));
+            HostFunctionsManager::sha2_256(message)
+        }
+        fn sha2_512(message: &[u8]) -> [u8; 64] {
+            HostFunctionsManager::sha2_512(message)
+        }
+        fn sha2_512_truncated(message: &[u8]) -> [u8; 32] {
+            HostFunctionsManager::sha2_512_truncated(message)
+        }
+        fn keccak_256(message: &[u8]) -> [u8; 32] {
+            HostFunctionsManager::keccak_256(message)
+        }
+        fn ripemd160(message: &[u8]) -> [u8; 20] {
+            HostFunctionsManager::ripemd160(message)
+        }
+        fn blake2b_512(message: &[u8]) -> [u8; 64] {
+            HostFunctionsManager::blake2b_512(message)
+        }
+        fn blake2s_256(message: &[u8]) -> [u8; 32] {
+            HostFunctionsManager::blake2s_256(message)
+        }
+        fn blake3(message: &[u8]) -> [u8; 32] {
+            HostFunctionsManager::blake3(message)
+        }
+    }
+
+    let (proof, _) = test_vector();
+    let app_hash = proof.root().unwrap();
+    assert!(proof
+        .verify_with_host_functions::<CountingHostFunctions>(&app_hash, &NeutronStoreLayout)
+        .unwrap());
+    assert!(SHA256_CALLS.with(Cell::get) > 0);
+}
+
+#[test]
+fn test_neutron_proof_verify_at_height() {
+    let (unversioned, app_hash) = test_vector();
+    assert_eq!(unversioned.height, None);
+    assert!(unversioned.verify_at_height(&app_hash, 1).is_err());
+
+    let proof = Ics23MerkleProof {
+        height: Some(1),
+        ..unversioned.clone()
+    };
+    assert!(proof.verify_at_height(&app_hash, 1).unwrap());
+    assert!(proof.verify_at_height(&app_hash, 2).is_err());
+    assert_eq!(proof.verify_output(&app_hash).unwrap().height, Some(1));
+    assert_eq!(unversioned.verify_output(&app_hash).unwrap().height, None);
+
+    let serialized = serde_json::to_vec(&proof).unwrap();
+    assert_eq!(
+        serde_json::from_slice::<Ics23MerkleProof>(&serialized).unwrap(),
+        proof
+    );
+
+    // a proof from height 1 cannot join a set for the app hash of height 2
+    assert!(
+        Ics23BlockProofSet::new(app_hash.clone(), 1, vec![proof.clone()])
+            .verify(&app_hash)
+            .unwrap()
+    );
+    assert!(Ics23BlockProofSet::new(app_hash.clone(), 2, vec![proof])
+        .verify(&app_hash)
+        .is_err());
+}
+
+#[test]
+fn test_verify_bridged_neutron_proof() {
+    use ethereum::mock::{combined_proof_for, storage_proof_for};
+    let (proof, app_hash) = test_vector();
+
+    // the bridge contract stores the app hash as a bytes32 slot
+    let slot = [0x03; 32];
+    let (app_hash_proof, storage_root) =
+        storage_proof_for(&slot, &[&[0xa0][..], &app_hash].concat());
+    let output =
+        verify_bridged_neutron_proof(&app_hash_proof, &storage_root, None, &slot, &proof).unwrap();
+    assert_eq!(output.root, app_hash);
+    assert_eq!(output.domain, Domain::Ics23);
+
+    // another app hash in the slot does not verify the proof
+    let (other_proof, other_root) = storage_proof_for(&slot, &[&[0xa0][..], &[0x01; 32]].concat());
+    assert!(verify_bridged_neutron_proof(&other_proof, &other_root, None, &slot, &proof).is_err());
+    assert!(
+        verify_bridged_neutron_proof(&app_hash_proof, &[0u8; 32], None, &slot, &proof).is_err()
+    );
+
+    // the app hash written to another slot, e.g. a user-writable mapping entry
+    let other_slot = [0x04; 32];
+    let (user_proof, user_root) =
+        storage_proof_for(&other_slot, &[&[0xa0][..], &app_hash].concat());
+    assert!(verify_bridged_neutron_proof(&user_proof, &user_root, None, &slot, &proof).is_err());
+
+    // against a state root, the proof must also be for the bridge contract
+    let bridge = [0x42; 20];
+    let (combined, state_root) =
+        combined_proof_for(&bridge, &slot, &[&[0xa0][..], &app_hash].concat());
+    assert!(
+        verify_bridged_neutron_proof(&combined, &state_root, Some(&bridge), &slot, &proof).is_ok()
+    );
+    assert!(
+        verify_bridged_neutron_proof(&combined, &state_root, Some(&[0x43; 20]), &slot, &proof)
+            .is_err()
+    );
+    assert!(verify_bridged_neutron_proof(&combined, &state_root, None, &slot, &proof).is_err());
+
+    assert_eq!(
+        CosmosAppHash::from_storage_value(&[0x81, 0x80]).unwrap().0[30..],
+        [0x00, 0x80]
+    );
+    assert!(CosmosAppHash::from_storage_value(&[]).is_err());
+    assert!(CosmosAppHash::from_storage_value(&[0x82, 0x01]).is_err());
+}
@@ -2,11 +2,14 @@ use std::str::FromStr;
 
 use anyhow::{Context, Result};
 use base64::Engine;
-use common::merkle::types::MerkleClient;
+use common::merkle::{types::MerkleClient, versioned::PROOF_FORMAT_VERSION};
 use tendermint::block::Height;
 use tendermint_rpc::{Client, HttpClient, Url as TendermintUrl};
 
-use crate::{keys::Ics23Key, merkle_lib::types::Ics23MerkleProof};
+use crate::{
+    keys::Ics23Key,
+    merkle_lib::types::{Ics23BlockProofSet, Ics23MerkleProof},
+};
 
 /// A prover implementation for retrieving Merkle proofs from a Neutron RPC endpoint.
 ///
@@ -26,18 +29,20 @@ impl MerkleClient for Ics23MerkleRpcClient {
             .abci_query(
                 // "store/bank/key", "store/wasm/key", ...
                 Some(format!("{}{}{}", "store/", neutron_key.prefix, "/key")),
-                hex::decode(neutron_key.key.clone())?,
+                neutron_key.store_key()?,
                 Some(Height::from(height as u32)),
                 true, // Include proof
             )
             .await?;
-        let proof = response.proof.context("Failed to get proof")?;
-        assert!(!response.value.is_empty());
-        Ok(serde_json::to_vec(&Ics23MerkleProof {
-            proof: proof.clone(),
+        let proof = Ics23MerkleProof {
+            version: PROOF_FORMAT_VERSION,
+            proof: response.proof.context("Failed to get proof")?,
             key: neutron_key,
             value: response.value,
-        })?)
+            height: Some(response.height.value()),
+        };
+        proof.validate()?;
+        Ok(serde_json::to_vec(&proof)?)
     }
 }
 
@@ -57,4 +62,27 @@ impl Ics23MerkleRpcClient {
             height,
         )
     }
+
+    /// Retrieves proofs for several keys, across any stores, from the same block.
+    ///
+    /// The proofs are taken at the height whose state is committed by the app hash
+    /// of the latest block, see [`Self::get_latest_root_and_height`].
+    ///
+    /// # Arguments
+    /// * `keys` - The serialized keys to prove, as accepted by `get_proof`
+    ///
+    /// # Returns
+    /// A proof set whose proofs all verify against its app hash
+    ///
+    /// # Errors
+    /// Returns an error if any proof cannot be retrieved or decoded
+    pub async fn get_block_proof_set(&self, keys: &[&str]) -> Result<Ics23BlockProofSet> {
+        let (app_hash, height) = self.get_latest_root_and_height().await;
+        let mut proofs = Vec::with_capacity(keys.len());
+        for key in keys {
+            let proof = self.get_proof(key, "", height).await?;
+            proofs.push(serde_json::from_slice(&proof)?);
+        }
+        Ok(Ics23BlockProofSet::new(app_hash, height, proofs))
+    }
 }
@@ -0,0 +1,94 @@
+//! Decoding of proven Cosmos SDK store values.
+//!
+//! Module stores keep their state protobuf-encoded, while CosmWasm contracts
+//! store JSON. The functions in this module decode values returned by a verified
+//! ICS23 proof into their SDK and contract types.
+
+use anyhow::{Context, Result};
+use cosmrs::proto::{
+    cosmos::staking::v1beta1::Delegation, cosmwasm::wasm::v1::ContractInfo, prost::Message,
+};
+
+/// Decodes a staking delegation as stored under
+/// [`Ics23Key::new_staking_delegation`](crate::keys::Ics23Key::new_staking_delegation).
+///
+/// # Arguments
+/// * `value` - The proven value of the delegation key
+///
+/// # Returns
+/// The delegation. Its `shares` are a decimal with 18 fractional digits,
+/// stored as the integer string of the scaled amount.
+///
+/// # Errors
+/// Returns an error if the value is not a protobuf-encoded `Delegation`
+pub fn decode_delegation(value: &[u8]) -> Result<Delegation> {
+    Delegation::decode(value).context("Failed to decode delegation")
+}
+
+/// Decodes the metadata of a CosmWasm contract as stored under
+/// [`Ics23Key::new_wasm_contract_info`](crate::keys::Ics23Key::new_wasm_contract_info).
+///
+/// # Arguments
+/// * `value` - The proven value of the contract info key
+///
+/// # Returns
+/// The contract info, including the `code_id` of the code the contract runs
+/// and its `creator`
+///
+/// # Errors
+/// Returns an error if the value is not a protobuf-encoded `ContractInfo`
+pub fn decode_contract_info(value: &[u8]) -> Result<ContractInfo> {
+    ContractInfo::decode(value).context("Failed to decode contract info")
+}
+
+/// Decodes a cw20 token balance as stored by `cw20-base` in its `balance` map.
+///
+/// The contract stores each balance as a JSON-serialized `Uint128`, which is a
+/// decimal string, e.g. `"1000000"`.
+///
+/// # Arguments
+/// * `value` - The proven value of the holder's balance map entry
+///
+/// # Returns
+/// The balance in the token's base units
+///
+/// # Errors
+/// Returns an error if the value is not a JSON string holding a `u128`
+pub fn decode_cw20_balance(value: &[u8]) -> Result<u128> {
+    let amount: String =
+        serde_json::from_slice(value).context("cw20 balance is not a JSON string")?;
+    decode_decimal_amount(amount.as_bytes()).context("Invalid cw20 balance")
+}
+
+/// Decodes an amount stored as the plain decimal string of an unsigned integer,
+/// e.g. `1000000`.
+///
+/// The value must consist of ASCII digits only: signs, whitespace and any other
+/// bytes are rejected rather than skipped, so that a proven value cannot be read
+/// as a different amount.
+///
+/// # Arguments
+/// * `value` - The proven value
+///
+/// # Returns
+/// The amount
+///
+/// # Errors
+/// Returns an error if the value is not UTF-8, is empty, contains a byte other
+/// than an ASCII digit, or exceeds `u128::MAX`
+pub fn decode_decimal_amount(value: &[u8]) -> Result<u128> {
+    let amount = core::str::from_utf8(value).context("Amount is not UTF-8")?;
+    if amount.is_empty() {
+        anyhow::bail!("Amount is empty");
+    }
+    if let Some(position) = amount.bytes().position(|b| !b.is_ascii_digit()) {
+        anyhow::bail!(
+            "Amount {:?} has a non-digit byte at position {}",
+            amount,
+            position
+        );
+    }
+    amount
+        .parse()
+        .with_context(|| format!("Amount {} exceeds u128", amount))
+}